@@ -4,17 +4,20 @@
 //!
 //! Note: Collision is hard and may be broken.
 
+use crate::snapshot::{snapshot_fields, Snapshot};
 use crate::{Event, EventType, LendingIterator, Logic, Reaction};
 use macroquad::math::Vec2;
 
 /// Information for each contact. If the entities at the indices `i` and `j` are both unfixed or both fixed, then `i < j`. If one is unfixed and the other is fixed, `i` will be the index of the unfixed entity.
 #[derive(PartialEq, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Contact {
     /// The index of the first contact in `centers`, `half_sizes`, `velocities`, `metadata`, and `displacements`.
     pub i: usize,
     /// The index of the second contact in `centers`, `half_sizes`, `velocities`, `metadata`, and `displacements`.
     pub j: usize,
     /// The projected displacement of each contact---not actual restituted displacement. If both colliding bodies are fixed, or one of them is **not** solid, defaults to a `Vec2` with a magnitude of 0.0.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_math"))]
     pub displacement: Vec2,
 }
 
@@ -33,6 +36,7 @@ impl Contact {
 
 /// Metadata of each collision entity.
 #[derive(Default, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CollisionData<ID: Copy + Eq> {
     /// True if the entity is solid, i.e. can stop other entities.
     ///
@@ -46,12 +50,17 @@ pub struct CollisionData<ID: Copy + Eq> {
 }
 
 /// A collision logic for axis-aligned bounding boxes.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AabbCollision<ID: Copy + Eq> {
     /// A vector of the centers of the bounding box.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_math"))]
     pub centers: Vec<Vec2>,
     /// A vector of half the width and half the height of the bounding box.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_math"))]
     pub half_sizes: Vec<Vec2>,
     /// A vector of the velocity of the entities.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_math"))]
     pub velocities: Vec<Vec2>,
     /// A vector of entity metadata.
     pub metadata: Vec<CollisionData<ID>>,
@@ -230,6 +239,10 @@ pub struct AabbColDataMut<'data, ID: Copy + Eq> {
     pub meta: &'data mut CollisionData<ID>,
 }
 
+impl<ID: Copy + Eq> Snapshot for AabbCollision<ID> {
+    snapshot_fields!(centers, half_sizes, velocities, metadata, contacts);
+}
+
 impl<ID: Copy + Eq + 'static> Logic for AabbCollision<ID> {
     type Event = Contact;
     type Reaction = CollisionReaction<ID>;
@@ -367,10 +380,8 @@ where
 {
     type Item<'a> = (
         <AabbCollision<ID> as Logic>::Ident,
-        <AabbCollision<ID> as Logic>::IdentData<'a>
-    )
-    where
-        Self: 'a;
+        <AabbCollision<ID> as Logic>::IdentData<'a>,
+    );
 
     fn next(&mut self) -> Option<Self::Item<'_>> {
         if self.count == self.collision.centers.len() {
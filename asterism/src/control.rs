@@ -3,9 +3,17 @@
 //! Control logics communicate that different entities are controlled by different inputs at different times. They map button inputs, AI intentions, network socket messages, etc onto high-level game actions.
 //!
 //! We're currently trying to consider analog as well as digital inputs, but we haven't implemented controller support, so some of these fields don't really make sense yet.
+use crate::snapshot::Snapshot;
 use crate::{Event, EventType, LendingIterator, Logic, Reaction};
+use macroquad::math::Vec2;
+pub use network::{NetKey, NetPeer, NetworkInputWrapper};
 pub use wrapper::*;
 
+mod network;
+#[cfg(feature = "testing")]
+#[doc(hidden)]
+pub mod testing;
+
 /// Information for a key/button press.
 trait Input {
     fn min(&self) -> f32;
@@ -15,6 +23,16 @@ trait Input {
 /// A keyboard control logic.
 ///
 /// A Wrapper is a helper struct that helps keep track of keypress information that libraries may not but we do want. This is currently only necessary if you're using `winit_input_helper`.
+///
+/// With the `serde` feature, keys are saved by their [BindingName]. The input wrapper isn't saved, and a new one is made when loading.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "ID: serde::Serialize, Wrapper::KeyCode: BindingName",
+        deserialize = "ID: serde::Deserialize<'de>, Wrapper::KeyCode: BindingName"
+    ))
+)]
 pub struct KeyboardControl<ID, Wrapper>
 where
     ID: Copy + Eq + Ord,
@@ -27,7 +45,24 @@ where
     /// events
     events: Vec<ControlEvent<ID>>,
     /// An input wrapper
+    #[cfg_attr(feature = "serde", serde(skip, default = "Wrapper::new"))]
     input_wrapper: Wrapper,
+    /// The action waiting to be bound to the next key pressed, see `capture_next_key`.
+    capturing: Option<(usize, ID)>,
+    /// Actions made up of other actions, see [Composite].
+    pub composites: Vec<CompositeAction<ID>>,
+    /// how many times the logic has been updated
+    frame: usize,
+    /// recent presses: the set, the action, and the frame it was pressed on
+    history: Vec<(usize, ID, usize)>,
+    /// Named groups of actions that are turned on and off together, see [InputContext].
+    pub contexts: Vec<InputContext<ID>>,
+    /// the contexts that have been pushed, with the last one on top
+    context_stack: Vec<ContextID>,
+    /// actions held this frame by something other than the input wrapper, see `press_synthetic`
+    synthetic: Vec<(usize, ID)>,
+    /// the synthetic presses from last frame, so releases can be detected
+    last_synthetic: Vec<(usize, ID)>,
 }
 
 impl<ID, Wrapper> Logic for KeyboardControl<ID, Wrapper>
@@ -60,6 +95,24 @@ where
                     action.is_valid = false;
                 }
             }
+            ControlReaction::Rebind(set, id, key) => {
+                if self.rebind(*set, *id, *key) {
+                    self.events.push(ControlEvent {
+                        set: *set,
+                        action_id: *id,
+                        event_type: ControlEventType::Rebound,
+                    });
+                }
+            }
+            ControlReaction::CaptureNextKey(set, id) => self.capture_next_key(*set, *id),
+            ControlReaction::ConsumeBuffered(set, id) => {
+                self.consume_buffered(*set, *id);
+            }
+            ControlReaction::PushContext(context) => self.push_context(*context),
+            ControlReaction::PopContext => {
+                self.pop_context();
+            }
+            ControlReaction::PressSynthetic(set, id) => self.press_synthetic(*set, *id),
         }
     }
 
@@ -81,6 +134,74 @@ where
     }
 }
 
+/// Everything in a [KeyboardControl] except its input wrapper, which keeps tracking the real keyboard when the logic is rewound.
+#[derive(Clone)]
+pub struct ControlSnapshot<ID, KeyCode: Copy> {
+    pub mapping: Vec<Vec<Action<ID, KeyCode>>>,
+    pub values: Vec<Vec<Values>>,
+    events: Vec<ControlEvent<ID>>,
+    capturing: Option<(usize, ID)>,
+    pub composites: Vec<CompositeAction<ID>>,
+    frame: usize,
+    history: Vec<(usize, ID, usize)>,
+    pub contexts: Vec<InputContext<ID>>,
+    context_stack: Vec<ContextID>,
+    synthetic: Vec<(usize, ID)>,
+    last_synthetic: Vec<(usize, ID)>,
+}
+
+impl<ID, Wrapper> Snapshot for KeyboardControl<ID, Wrapper>
+where
+    ID: Copy + Eq + Ord,
+    Wrapper: InputWrapper,
+{
+    type State = ControlSnapshot<ID, Wrapper::KeyCode>;
+
+    fn snapshot(&self) -> Self::State {
+        ControlSnapshot {
+            mapping: self.mapping.clone(),
+            values: self.values.clone(),
+            events: self.events.clone(),
+            capturing: self.capturing,
+            composites: self.composites.clone(),
+            frame: self.frame,
+            history: self.history.clone(),
+            contexts: self.contexts.clone(),
+            context_stack: self.context_stack.clone(),
+            synthetic: self.synthetic.clone(),
+            last_synthetic: self.last_synthetic.clone(),
+        }
+    }
+
+    fn snapshot_into(&self, state: &mut Self::State) {
+        state.mapping.clone_from(&self.mapping);
+        state.values.clone_from(&self.values);
+        state.events.clone_from(&self.events);
+        state.capturing = self.capturing;
+        state.composites.clone_from(&self.composites);
+        state.frame = self.frame;
+        state.history.clone_from(&self.history);
+        state.contexts.clone_from(&self.contexts);
+        state.context_stack.clone_from(&self.context_stack);
+        state.synthetic.clone_from(&self.synthetic);
+        state.last_synthetic.clone_from(&self.last_synthetic);
+    }
+
+    fn restore(&mut self, state: &Self::State) {
+        self.mapping.clone_from(&state.mapping);
+        self.values.clone_from(&state.values);
+        self.events.clone_from(&state.events);
+        self.capturing = state.capturing;
+        self.composites.clone_from(&state.composites);
+        self.frame = state.frame;
+        self.history.clone_from(&state.history);
+        self.contexts.clone_from(&state.contexts);
+        self.context_stack.clone_from(&state.context_stack);
+        self.synthetic.clone_from(&state.synthetic);
+        self.last_synthetic.clone_from(&state.last_synthetic);
+    }
+}
+
 impl<ID, Wrapper> KeyboardControl<ID, Wrapper>
 where
     ID: Copy + Eq + Ord + 'static,
@@ -92,6 +213,14 @@ where
             values: Vec::new(),
             events: Vec::new(),
             input_wrapper: Wrapper::new(),
+            capturing: None,
+            composites: Vec::new(),
+            frame: 0,
+            history: Vec::new(),
+            contexts: Vec::new(),
+            context_stack: Vec::new(),
+            synthetic: Vec::new(),
+            last_synthetic: Vec::new(),
         }
     }
 
@@ -99,6 +228,7 @@ where
     pub fn update(&mut self, events: &Wrapper::InputHelper) {
         self.input_wrapper.clear();
         self.events.clear();
+        self.frame += 1;
 
         for (i, (map, map_values)) in self.mapping.iter().zip(self.values.iter_mut()).enumerate() {
             for (action, mut values) in map.iter().zip(map_values.iter_mut()) {
@@ -108,13 +238,32 @@ where
                     is_valid,
                     ..
                 } = action;
-                let Values { value, changed_by } = &mut values;
+                let Values {
+                    value,
+                    changed_by,
+                    axes,
+                } = &mut values;
                 // if not valid, reset and skip check. could cause problems if a key were pressed before it became valid then the key became valid while still being held. this is probably semi-reasonable, actually
                 if !*is_valid {
                     *value = 0.0;
                     *changed_by = 0.0;
                     continue;
                 }
+                if *input_type == InputType::Analog {
+                    let new_axes = self
+                        .input_wrapper
+                        .analog_value(&key_input.keycode, events)
+                        .unwrap_or(Vec2::ZERO);
+                    if new_axes != *axes {
+                        *axes = new_axes;
+                        self.events.push(ControlEvent {
+                            set: i,
+                            action_id: action.id,
+                            event_type: ControlEventType::AxisChanged,
+                        });
+                    }
+                    continue;
+                }
                 match input_type {
                     InputType::Digital => {
                         // NOTE: if update_held isn't called for every key in the mappings, it can completely break some of the input wrappers.
@@ -126,7 +275,11 @@ where
                             } else {
                                 *changed_by = 0.0;
                             }
-                        } else if self.input_wrapper.is_released(&key_input.keycode, events)
+                        } else if self.synthetic.contains(&(i, action.id)) {
+                            // synthetic presses act like a held key: pressed on the first frame they show up
+                            *changed_by = if *value == 0.0 { 1.0 } else { 0.0 };
+                        } else if (self.input_wrapper.is_released(&key_input.keycode, events)
+                            || self.last_synthetic.contains(&(i, action.id)))
                         // see comment earlier about keypresses that are invalid. logic may not be correct though
                             && *value != 0.0
                         {
@@ -135,7 +288,7 @@ where
                             *changed_by = 0.0;
                         }
                     }
-                    InputType::Analog => unimplemented!(),
+                    InputType::Analog => unreachable!("analog inputs are handled above"),
                 }
                 *value = (*value + *changed_by)
                     .max(key_input.min())
@@ -166,6 +319,293 @@ where
                 });
             }
         }
+
+        self.last_synthetic = std::mem::take(&mut self.synthetic);
+        self.update_composites();
+
+        // checked after the mappings so the key used to rebind doesn't also count as pressing the action
+        if let Some((set, id)) = self.capturing {
+            if let Some(key) = self.input_wrapper.last_key_pressed(events) {
+                self.capturing = None;
+                if self.rebind(set, id, key) {
+                    self.events.push(ControlEvent {
+                        set,
+                        action_id: id,
+                        event_type: ControlEventType::Rebound,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Checks the composite actions against this frame's presses and what's being held.
+    fn update_composites(&mut self) {
+        let frame = self.frame;
+        for event in self.events.iter() {
+            if event.event_type == ControlEventType::KeyPressed {
+                self.history.push((event.set, event.action_id, frame));
+            }
+        }
+        let longest = self
+            .composites
+            .iter()
+            .map(|action| action.composite.window())
+            .max()
+            .unwrap_or(0);
+        self.history
+            .retain(|(.., pressed)| frame - pressed <= longest);
+
+        let (mapping, values, history) = (&self.mapping, &self.values, &self.history);
+        let held = |set: usize, id: &ID| {
+            mapping[set]
+                .iter()
+                .position(|act| act.id == *id)
+                .map(|i| values[set][i].value != 0.0)
+                .unwrap_or(false)
+        };
+        let pressed_now = |set: usize, id: &ID| {
+            history
+                .iter()
+                .any(|(s, act, pressed)| *s == set && act == id && *pressed == frame)
+        };
+
+        let events = &mut self.events;
+        for action in self.composites.iter_mut() {
+            let (set, action_id) = (action.set, action.id);
+            let mut log = |event_type| {
+                events.push(ControlEvent {
+                    set,
+                    action_id,
+                    event_type,
+                })
+            };
+            match &action.composite {
+                Composite::Chord(ids) => {
+                    let all_held = !ids.is_empty() && ids.iter().all(|id| held(set, id));
+                    if all_held && !action.active && ids.iter().any(|id| pressed_now(set, id)) {
+                        action.active = true;
+                        log(ControlEventType::KeyPressed);
+                    } else if !all_held && action.active {
+                        action.active = false;
+                        log(ControlEventType::KeyReleased);
+                    }
+                    log(if action.active {
+                        ControlEventType::KeyHeld
+                    } else {
+                        ControlEventType::KeyUnheld
+                    });
+                }
+                Composite::Sequence(ids, window) => {
+                    let presses: Vec<_> = history.iter().filter(|(s, ..)| *s == set).collect();
+                    if !ids.is_empty() && presses.len() >= ids.len() {
+                        let tail = &presses[presses.len() - ids.len()..];
+                        if tail[tail.len() - 1].2 == frame
+                            && tail
+                                .iter()
+                                .zip(ids.iter())
+                                .all(|((_, id, _), step)| id == step)
+                            && tail.windows(2).all(|pair| pair[1].2 - pair[0].2 <= *window)
+                        {
+                            log(ControlEventType::KeyPressed);
+                        }
+                    }
+                }
+                Composite::DoubleTap(id, window) => {
+                    let mut presses = history
+                        .iter()
+                        .rev()
+                        .filter(|(s, act, _)| *s == set && act == id);
+                    if let (Some(last), Some(prev)) = (presses.next(), presses.next()) {
+                        if last.2 == frame && last.2 - prev.2 <= *window {
+                            log(ControlEventType::KeyPressed);
+                        }
+                    }
+                }
+                Composite::Buffered(id, frames) => {
+                    if pressed_now(set, id) {
+                        action.buffer_left = *frames;
+                        log(ControlEventType::KeyPressed);
+                    } else {
+                        action.buffer_left = action.buffer_left.saturating_sub(1);
+                    }
+                    if action.buffer_left > 0 {
+                        log(ControlEventType::KeyHeld);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Adds an action made up of other actions in the same set. Its events use its own ID.
+    pub fn add_composite(&mut self, set: <Self as Logic>::Ident, id: ID, composite: Composite<ID>) {
+        self.composites.push(CompositeAction {
+            set,
+            id,
+            composite,
+            active: false,
+            buffer_left: 0,
+        });
+    }
+
+    /// Uses up a [Composite::Buffered] press, so it isn't acted on twice. Returns whether there was a press to use.
+    pub fn consume_buffered(&mut self, set: <Self as Logic>::Ident, id: ID) -> bool {
+        match self
+            .composites
+            .iter_mut()
+            .find(|action| action.set == set && action.id == id)
+        {
+            Some(action) if action.buffer_left > 0 => {
+                action.buffer_left = 0;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Removes a whole set of mappings, along with its composite actions. Sets after it move down by one.
+    pub fn remove_set(&mut self, set: <Self as Logic>::Ident) {
+        self.mapping.remove(set);
+        self.values.remove(set);
+        self.composites.retain(|action| action.set != set);
+        for action in self.composites.iter_mut() {
+            if action.set > set {
+                action.set -= 1;
+            }
+        }
+        for context in self.contexts.iter_mut() {
+            context.actions.retain(|(s, _)| *s != set);
+            for (s, _) in context.actions.iter_mut() {
+                if *s > set {
+                    *s -= 1;
+                }
+            }
+        }
+        self.history.clear();
+        self.capturing = None;
+        self.synthetic.clear();
+        self.last_synthetic.clear();
+    }
+
+    /// The input wrapper, for wrappers that need setting up, ex. connecting a [NetworkInputWrapper] to its peer.
+    pub fn input_wrapper_mut(&mut self) -> &mut Wrapper {
+        &mut self.input_wrapper
+    }
+
+    /// Holds an action for the next update as if its key were down, for inputs that don't come from the input wrapper (ex. an [AiController]). It goes through the same validity checks and makes the same events as a real keypress. Call it every frame the action should stay held.
+    pub fn press_synthetic(&mut self, set: <Self as Logic>::Ident, id: ID) {
+        if !self.synthetic.contains(&(set, id)) {
+            self.synthetic.push((set, id));
+        }
+    }
+
+    /// Adds an empty input context. If it's blocking, contexts under it on the stack are turned off while it's pushed.
+    pub fn add_context(&mut self, name: impl Into<String>, blocking: bool) -> ContextID {
+        self.contexts.push(InputContext {
+            name: name.into(),
+            actions: Vec::new(),
+            blocking,
+        });
+        let id = ContextID(self.contexts.len() - 1);
+        self.apply_contexts();
+        id
+    }
+
+    /// Puts an action in a context. Once an action is in a context, it's only valid while one of its contexts is active.
+    pub fn add_to_context(&mut self, context: ContextID, set: <Self as Logic>::Ident, id: ID) {
+        self.contexts[context.0].actions.push((set, id));
+        self.apply_contexts();
+    }
+
+    pub fn context_by_name(&self, name: &str) -> Option<ContextID> {
+        self.contexts
+            .iter()
+            .position(|context| context.name == name)
+            .map(ContextID)
+    }
+
+    pub fn push_context(&mut self, context: ContextID) {
+        self.context_stack.push(context);
+        self.apply_contexts();
+    }
+
+    /// Removes the context on top of the stack, returning it.
+    pub fn pop_context(&mut self) -> Option<ContextID> {
+        let context = self.context_stack.pop();
+        self.apply_contexts();
+        context
+    }
+
+    /// The contexts whose actions are currently on: from the top of the stack down to the first blocking context.
+    pub fn active_contexts(&self) -> impl Iterator<Item = ContextID> + '_ {
+        let mut blocked = false;
+        self.context_stack
+            .iter()
+            .rev()
+            .copied()
+            .take_while(move |context| {
+                let active = !blocked;
+                blocked = blocked || self.contexts[context.0].blocking;
+                active
+            })
+    }
+
+    /// Sets the validity of every action that's in a context, all at once.
+    fn apply_contexts(&mut self) {
+        let active: Vec<_> = self.active_contexts().collect();
+        for context in self.contexts.iter() {
+            for (set, id) in context.actions.iter() {
+                if let Some(action) = self
+                    .mapping
+                    .get_mut(*set)
+                    .and_then(|map| map.iter_mut().find(|act| act.id == *id))
+                {
+                    action.is_valid = false;
+                }
+            }
+        }
+        for context in active {
+            for (set, id) in self.contexts[context.0].actions.iter() {
+                if let Some(action) = self
+                    .mapping
+                    .get_mut(*set)
+                    .and_then(|map| map.iter_mut().find(|act| act.id == *id))
+                {
+                    action.is_valid = true;
+                }
+            }
+        }
+    }
+
+    /// Changes the key bound to an action. Returns false if the action isn't in the set.
+    pub fn rebind(
+        &mut self,
+        set: <Self as Logic>::Ident,
+        id: ID,
+        keycode: Wrapper::KeyCode,
+    ) -> bool {
+        match self
+            .mapping
+            .get(set)
+            .and_then(|map| map.iter().position(|act| act.id == id))
+        {
+            Some(i) => {
+                self.mapping[set][i].key_input.keycode = keycode;
+                // otherwise the action could be stuck as held if the old key was down
+                self.values[set][i] = Values::new();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Binds the next key pressed to the given action, ex. for a controls menu. Produces a [ControlEventType::Rebound] event once it happens. Only works with input wrappers that implement [InputWrapper::last_key_pressed].
+    pub fn capture_next_key(&mut self, set: <Self as Logic>::Ident, id: ID) {
+        self.capturing = Some((set, id));
+    }
+
+    /// If the logic is waiting for a key to bind, and for which action.
+    pub fn capturing(&self) -> Option<(<Self as Logic>::Ident, ID)> {
+        self.capturing
     }
 
     /// Returns the [Values] for the first action in the mapping with the given ID.
@@ -198,11 +638,327 @@ where
             self.mapping.resize_with(locus_idx + 1, Default::default);
             self.values.resize_with(locus_idx + 1, Default::default);
         }
-        self.mapping[locus_idx].push(Action::new(id, keycode, InputType::Digital, valid));
+        let input_type = Wrapper::input_type(&keycode);
+        self.mapping[locus_idx].push(Action::new(id, keycode, input_type, valid));
         self.values[locus_idx].push(Values::new());
     }
 }
 
+/// Ways to build an action out of other actions in the same set.
+///
+/// ```
+/// use asterism::control::testing::TestWrapper;
+/// use asterism::control::*;
+/// use asterism::Logic;
+///
+/// let mut control = KeyboardControl::<&str, TestWrapper>::new();
+/// control.add_key_map(0, 's', "shift", true);
+/// control.add_key_map(0, ' ', "space", true);
+/// control.add_key_map(0, 'd', "dash", true);
+/// control.add_composite(0, "super jump", Composite::Chord(vec!["shift", "space"]));
+/// control.add_composite(0, "sprint", Composite::DoubleTap("dash", 10));
+///
+/// fn pressed(control: &KeyboardControl<&'static str, TestWrapper>, id: &str) -> bool {
+///     control.events().iter().any(|e| e.action_id == id && e.event_type == ControlEventType::KeyPressed)
+/// }
+///
+/// control.update(&vec!['s']);
+/// control.update(&vec!['s', ' ']);
+/// assert!(pressed(&control, "super jump"));
+///
+/// control.update(&vec!['d']);
+/// control.update(&vec![]);
+/// control.update(&vec!['d']);
+/// assert!(pressed(&control, "sprint"));
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Composite<ID> {
+    /// All of the actions held at once, ex. Shift+Space. Pressed when the last one goes down, held until any of them is let go.
+    Chord(Vec<ID>),
+    /// The actions pressed in order, each within the given number of frames of the one before, with no other actions of the set pressed in between, ex. down, down, serve.
+    Sequence(Vec<ID>, usize),
+    /// The action pressed twice within the given number of frames.
+    DoubleTap(ID, usize),
+    /// The action stays held for the given number of frames after it's pressed, or until it's used with `consume_buffered`, so a press that comes a little early still counts.
+    Buffered(ID, usize),
+}
+
+impl<ID> Composite<ID> {
+    /// how many frames of presses the composite needs to remember
+    fn window(&self) -> usize {
+        match self {
+            Composite::Sequence(ids, window) => ids.len() * window,
+            Composite::DoubleTap(_, window) => *window,
+            Composite::Chord(_) | Composite::Buffered(..) => 0,
+        }
+    }
+}
+
+/// A composite action and its state.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CompositeAction<ID> {
+    pub set: usize,
+    pub id: ID,
+    pub composite: Composite<ID>,
+    /// if a chord is currently held
+    active: bool,
+    /// frames left on a buffered press
+    buffer_left: usize,
+}
+
+/// Identifies an [InputContext] in a control logic.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ContextID(usize);
+
+impl ContextID {
+    pub fn idx(&self) -> usize {
+        self.0
+    }
+}
+
+/// A named layer of controls, ex. "menu", "serving", or "rally". Contexts are pushed onto and popped off of a stack as the game's state changes, turning their actions on and off together.
+///
+/// ```
+/// use asterism::control::*;
+/// use macroquad::input::KeyCode;
+///
+/// let mut control = KeyboardControl::<&str, MacroquadInputWrapper>::new();
+/// control.add_key_map(0, KeyCode::Up, "move", true);
+/// control.add_key_map(0, KeyCode::Enter, "select", true);
+/// let rally = control.add_context("rally", false);
+/// let menu = control.add_context("menu", true);
+/// control.add_to_context(rally, 0, "move");
+/// control.add_to_context(menu, 0, "select");
+///
+/// control.push_context(rally);
+/// control.push_context(menu);
+/// // the menu blocks everything under it
+/// assert!(!control.mapping[0][0].is_valid);
+/// assert!(control.mapping[0][1].is_valid);
+///
+/// control.pop_context();
+/// assert!(control.mapping[0][0].is_valid);
+/// assert!(!control.mapping[0][1].is_valid);
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InputContext<ID> {
+    pub name: String,
+    /// the set and ID of each action in the context
+    pub actions: Vec<(usize, ID)>,
+    /// if contexts below this one on the stack are turned off while it's active
+    pub blocking: bool,
+}
+
+/// Decides which actions to hold this frame from some observation of the game. Closures taking an observation and returning a list of actions are policies.
+pub trait Policy<Obs, ID> {
+    fn decide(&mut self, observation: &Obs) -> Vec<ID>;
+}
+
+impl<Obs, ID, F> Policy<Obs, ID> for F
+where
+    F: FnMut(&Obs) -> Vec<ID>,
+{
+    fn decide(&mut self, observation: &Obs) -> Vec<ID> {
+        self(observation)
+    }
+}
+
+/// Drives a control set with a [Policy] instead of a player. Its decisions become synthetic presses in the control logic, so they follow the same validity rules and make the same events as keys would.
+///
+/// ```
+/// use asterism::control::testing::TestWrapper;
+/// use asterism::control::*;
+///
+/// // no keys are ever down, so only the AI moves anything
+/// let no_keys = vec![];
+///
+/// let mut control = KeyboardControl::<&str, TestWrapper>::new();
+/// control.add_key_map(0, 'w', "up", true);
+/// control.add_key_map(0, 's', "down", true);
+///
+/// // observes the paddle's and the ball's heights and follows the ball
+/// let mut ai = AiController::new(0, |&(paddle, ball): &(f32, f32)| {
+///     if ball < paddle {
+///         vec!["up"]
+///     } else if ball > paddle {
+///         vec!["down"]
+///     } else {
+///         vec![]
+///     }
+/// });
+///
+/// ai.act(&(10.0, 4.0), &mut control);
+/// control.update(&no_keys);
+/// assert_eq!(control.values[0][0].value, 1.0);
+///
+/// ai.act(&(10.0, 10.0), &mut control);
+/// control.update(&no_keys);
+/// assert_eq!(control.values[0][0].value, 0.0);
+///
+/// // invalid actions can't be pressed by the AI either
+/// control.mapping[0][1].is_valid = false;
+/// ai.act(&(10.0, 20.0), &mut control);
+/// control.update(&no_keys);
+/// assert_eq!(control.values[0][1].value, 0.0);
+/// ```
+pub struct AiController<Obs, ID> {
+    /// the control set being driven
+    pub set: usize,
+    pub policy: Box<dyn Policy<Obs, ID>>,
+}
+
+impl<Obs, ID> AiController<Obs, ID>
+where
+    ID: Copy + Eq + Ord + 'static,
+{
+    pub fn new(set: usize, policy: impl Policy<Obs, ID> + 'static) -> Self {
+        Self {
+            set,
+            policy: Box::new(policy),
+        }
+    }
+
+    /// Asks the policy what to hold without touching a control logic, for when the observation borrows from the same place as the control logic.
+    pub fn decide(&mut self, observation: &Obs) -> Vec<ID> {
+        self.policy.decide(observation)
+    }
+
+    /// Asks the policy what to hold and holds it in the control logic. Call this every frame before the control logic updates.
+    pub fn act<Wrapper: InputWrapper + 'static>(
+        &mut self,
+        observation: &Obs,
+        control: &mut KeyboardControl<ID, Wrapper>,
+    ) {
+        for id in self.policy.decide(observation) {
+            control.press_synthetic(self.set, id);
+        }
+    }
+}
+
+/// Binding profiles are plain text, with one binding per line: the control set, the action's name, and the key's name, separated by whitespace, ex. `0 Up W`. Lines starting with `#` are ignored.
+///
+/// ```
+/// use asterism::control::*;
+/// use macroquad::input::KeyCode;
+///
+/// #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+/// enum Act { Jump, Duck }
+///
+/// impl BindingName for Act {
+///     fn binding_name(&self) -> String {
+///         format!("{:?}", self)
+///     }
+///     fn from_binding_name(name: &str) -> Option<Self> {
+///         match name {
+///             "Jump" => Some(Act::Jump),
+///             "Duck" => Some(Act::Duck),
+///             _ => None,
+///         }
+///     }
+/// }
+///
+/// let mut control = KeyboardControl::<Act, MacroquadInputWrapper>::new();
+/// control.add_key_map(0, KeyCode::Space, Act::Jump, true);
+/// control.add_key_map(0, KeyCode::S, Act::Duck, true);
+///
+/// control.load_profile("# wasd\n0 Jump W").unwrap();
+/// assert_eq!(control.mapping[0][0].get_keycode(), &KeyCode::W);
+/// assert_eq!(control.save_profile(), "0 Jump W\n0 Duck S\n");
+/// assert!(control.load_profile("0 Jump Hyperspace").is_err());
+///
+/// // only bindings that already exist can be changed
+/// control.add_key_map(1, KeyCode::Up, Act::Jump, true);
+/// assert!(control.load_profile("1 Duck Down").is_err());
+/// assert!(control.load_profile("2 Jump W").is_err());
+/// ```
+impl<ID, Wrapper> KeyboardControl<ID, Wrapper>
+where
+    ID: Copy + Eq + Ord + BindingName + 'static,
+    Wrapper: InputWrapper + 'static,
+    Wrapper::KeyCode: BindingName,
+{
+    /// Writes out every binding in the logic as a profile.
+    pub fn save_profile(&self) -> String {
+        let mut profile = String::new();
+        for (set, map) in self.mapping.iter().enumerate() {
+            for action in map.iter() {
+                profile.push_str(&format!(
+                    "{} {} {}\n",
+                    set,
+                    action.id.binding_name(),
+                    action.get_keycode().binding_name()
+                ));
+            }
+        }
+        profile
+    }
+
+    /// Applies the bindings in a profile. Only rebinds actions that are already mapped, so a typo in a set or action is an error instead of a new binding. If any line can't be read, none of the bindings are applied.
+    pub fn load_profile(&mut self, profile: &str) -> Result<(), String> {
+        let mut bindings = Vec::new();
+        for (line_num, line) in profile.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let err = |what: &str| format!("line {} of binding profile: {}", line_num + 1, what);
+            let mut words = line.split_whitespace();
+            let (set, id, key) = match (words.next(), words.next(), words.next(), words.next()) {
+                (Some(set), Some(id), Some(key), None) => (set, id, key),
+                _ => return Err(err("expected a set, an action, and a key")),
+            };
+            let set = set
+                .parse::<usize>()
+                .map_err(|_| err(&format!("{} isn't a control set", set)))?;
+            let id =
+                ID::from_binding_name(id).ok_or_else(|| err(&format!("unknown action {}", id)))?;
+            let map = self
+                .mapping
+                .get(set)
+                .ok_or_else(|| err(&format!("there's no control set {}", set)))?;
+            if !map.iter().any(|act| act.id == id) {
+                return Err(err(&format!(
+                    "control set {} doesn't have the action {}",
+                    set,
+                    id.binding_name()
+                )));
+            }
+            let key = Wrapper::KeyCode::from_binding_name(key)
+                .ok_or_else(|| err(&format!("unknown key {}", key)))?;
+            bindings.push((set, id, key));
+        }
+
+        for (set, id, key) in bindings {
+            self.rebind(set, id, key);
+        }
+        Ok(())
+    }
+
+    pub fn save_profile_to_file(&self, path: impl AsRef<std::path::Path>) -> Result<(), String> {
+        std::fs::write(path.as_ref(), self.save_profile())
+            .map_err(|e| format!("couldn't save binding profile: {}", e))
+    }
+
+    pub fn load_profile_from_file(
+        &mut self,
+        path: impl AsRef<std::path::Path>,
+    ) -> Result<(), String> {
+        let profile = std::fs::read_to_string(path.as_ref())
+            .map_err(|e| format!("couldn't load binding profile: {}", e))?;
+        self.load_profile(&profile)
+    }
+}
+
+/// Converts between a binding (a keycode or an action ID) and the name it's saved under in a binding profile. Names can't have whitespace in them.
+pub trait BindingName: Sized {
+    fn binding_name(&self) -> String;
+    fn from_binding_name(name: &str) -> Option<Self>;
+}
+
 pub struct CtrlDataIter<'ctrl, ID, Wrapper>
 where
     ID: Copy + Eq + Ord + 'static,
@@ -217,13 +973,13 @@ where
     ID: Copy + Eq + Ord + 'static,
     Wrapper: InputWrapper + 'static,
 {
-    type Item<'a> = (<KeyboardControl<ID, Wrapper> as Logic>::Ident, <KeyboardControl<ID, Wrapper> as Logic>::IdentDataMut<'a>) where Self: 'a;
+    type Item<'a> = (<KeyboardControl<ID, Wrapper> as Logic>::Ident, <KeyboardControl<ID, Wrapper> as Logic>::IdentDataMut<'a>);
 
     fn next(&mut self) -> Option<Self::Item<'_>> {
-        self.count += 1;
         if self.count == self.control.mapping.len() {
             None
         } else {
+            self.count += 1;
             Some((
                 self.count - 1,
                 self.control.get_ident_data_mut(self.count - 1),
@@ -250,7 +1006,26 @@ impl<KeyCode: Copy> Input for KeyInput<KeyCode> {
     }
 }
 
+/// Key inputs are saved by their [BindingName], since most keycode types can't be serialized themselves.
+#[cfg(feature = "serde")]
+impl<KeyCode: Copy + BindingName> serde::Serialize for KeyInput<KeyCode> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.keycode.binding_name())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, KeyCode: Copy + BindingName> serde::Deserialize<'de> for KeyInput<KeyCode> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = <String as serde::Deserialize>::deserialize(deserializer)?;
+        KeyCode::from_binding_name(&name)
+            .map(|keycode| KeyInput { keycode })
+            .ok_or_else(|| serde::de::Error::custom(format!("unknown key {}", name)))
+    }
+}
+
 #[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum InputType {
     /// an input that can be a range of values (joystick, etc)
     Analog,
@@ -260,11 +1035,15 @@ pub enum InputType {
 
 /// Information about the player's input related to one action.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Values {
     /// How much the value of the input was changed last frame.
     pub changed_by: f32,
     /// What the value of the input is now.
     pub value: f32,
+    /// Where an analog input is along its axes, ex. the cursor's position. Always zero for digital inputs, and analog inputs leave `changed_by` and `value` at zero.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_math"))]
+    pub axes: Vec2,
 }
 
 impl Values {
@@ -272,12 +1051,21 @@ impl Values {
         Self {
             changed_by: 0.0,
             value: 0.0,
+            axes: Vec2::ZERO,
         }
     }
 }
 
 /// Information for an action and the input it's attached to.
 #[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "ID: serde::Serialize, KeyCode: BindingName",
+        deserialize = "ID: serde::Deserialize<'de>, KeyCode: BindingName"
+    ))
+)]
 pub struct Action<ID, KeyCode: Copy> {
     pub id: ID,
     /// The input's keycode and min/max.
@@ -309,11 +1097,24 @@ pub enum ControlReaction<ID: Copy + Eq, KeyCode: Copy + Eq> {
     AddKeyToSet(usize, ID, KeyCode, bool),
     SetKeyValid(usize, ID),
     SetKeyInvalid(usize, ID),
+    /// bind an action in a set to a different key
+    Rebind(usize, ID, KeyCode),
+    /// bind an action in a set to whatever key is pressed next
+    CaptureNextKey(usize, ID),
+    /// use up a buffered press, see [Composite::Buffered]
+    ConsumeBuffered(usize, ID),
+    /// turn on an input context, see [InputContext]
+    PushContext(ContextID),
+    /// turn off the input context on top of the stack
+    PopContext,
+    /// hold an action for the next update without its key, see `KeyboardControl::press_synthetic`
+    PressSynthetic(usize, ID),
 }
 
 impl<ID: Copy + Eq, KeyCode: Copy + Eq> Reaction for ControlReaction<ID, KeyCode> {}
 
 #[derive(PartialEq, Eq, Ord, PartialOrd, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ControlEvent<ID> {
     pub event_type: ControlEventType,
     pub set: usize,
@@ -328,11 +1129,16 @@ impl<ID> Event for ControlEvent<ID> {
 }
 
 #[derive(PartialEq, Eq, Ord, PartialOrd, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ControlEventType {
     KeyPressed,
     KeyReleased,
     KeyHeld,
     KeyUnheld,
+    /// the action was bound to a different key
+    Rebound,
+    /// an analog input moved, see [Values::axes]
+    AxisChanged,
 }
 
 impl EventType for ControlEventType {}
@@ -357,9 +1163,30 @@ pub mod wrapper {
 
         /// if the key has just been released or not
         fn is_released(&self, key: &Self::KeyCode, events: &Self::InputHelper) -> bool;
+
+        /// the key pressed most recently this frame, if any, whether or not it's in the mappings. Used for capturing keys to rebind to; wrappers that can't tell return `None`.
+        fn last_key_pressed(&self, _events: &Self::InputHelper) -> Option<Self::KeyCode> {
+            None
+        }
+
+        /// where an analog input is along its axes. Wrappers without analog inputs return `None`.
+        fn analog_value(&self, _key: &Self::KeyCode, _events: &Self::InputHelper) -> Option<Vec2> {
+            None
+        }
+
+        /// if the input is digital or analog
+        fn input_type(_key: &Self::KeyCode) -> InputType {
+            InputType::Digital
+        }
     }
 
-    use macroquad::prelude::{is_key_down, is_key_pressed, is_key_released, KeyCode as MqKeyCode};
+    use super::{BindingName, InputType};
+    use macroquad::math::Vec2;
+    use macroquad::prelude::{
+        get_last_key_pressed, is_key_down, is_key_pressed, is_key_released, is_mouse_button_down,
+        is_mouse_button_pressed, is_mouse_button_released, mouse_position, mouse_wheel,
+        KeyCode as MqKeyCode, MouseButton,
+    };
     /// Macroquad's input handler already correctly handles the information we need, so this is just a wrapper for their functions
     pub struct MacroquadInputWrapper {}
 
@@ -383,6 +1210,145 @@ pub mod wrapper {
         fn is_released(&self, key: &MqKeyCode, _events: &()) -> bool {
             is_key_released(*key)
         }
+
+        fn last_key_pressed(&self, _events: &()) -> Option<MqKeyCode> {
+            get_last_key_pressed()
+        }
+    }
+
+    /// keys are named the same as their variants
+    macro_rules! binding_names {
+        ($keycode:ident: $($key:ident),*) => {
+            impl BindingName for $keycode {
+                fn binding_name(&self) -> String {
+                    format!("{:?}", self)
+                }
+
+                fn from_binding_name(name: &str) -> Option<Self> {
+                    match name {
+                        $(stringify!($key) => Some($keycode::$key),)*
+                        _ => None,
+                    }
+                }
+            }
+        };
+    }
+
+    binding_names!(MqKeyCode: Space, Apostrophe, Comma, Minus, Period, Slash, Key0, Key1, Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9, Semicolon, Equal, A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, LeftBracket, Backslash, RightBracket, GraveAccent, World1, World2, Escape, Enter, Tab, Backspace, Insert, Delete, Right, Left, Down, Up, PageUp, PageDown, Home, End, CapsLock, ScrollLock, NumLock, PrintScreen, Pause, F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12, F13, F14, F15, F16, F17, F18, F19, F20, F21, F22, F23, F24, F25, Kp0, Kp1, Kp2, Kp3, Kp4, Kp5, Kp6, Kp7, Kp8, Kp9, KpDecimal, KpDivide, KpMultiply, KpSubtract, KpAdd, KpEnter, KpEqual, LeftShift, LeftControl, LeftAlt, LeftSuper, RightShift, RightControl, RightAlt, RightSuper, Menu, Unknown);
+
+    /// A keyboard key, mouse button, or the mouse's analog inputs.
+    #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+    pub enum PointerInput {
+        Key(MqKeyCode),
+        Button(MouseButton),
+        /// the cursor's position in the window, in pixels
+        Cursor,
+        /// how far the wheel scrolled this frame
+        Wheel,
+    }
+
+    impl From<MqKeyCode> for PointerInput {
+        fn from(key: MqKeyCode) -> Self {
+            Self::Key(key)
+        }
+    }
+
+    impl From<MouseButton> for PointerInput {
+        fn from(button: MouseButton) -> Self {
+            Self::Button(button)
+        }
+    }
+
+    /// the mouse buttons that can be bound
+    const MOUSE_BUTTONS: [MouseButton; 3] = [MouseButton::Left, MouseButton::Right, MouseButton::Middle];
+
+    /// Mouse buttons are named like `Mouse:Left`, and keys are named the same as for [MacroquadInputWrapper].
+    impl BindingName for PointerInput {
+        fn binding_name(&self) -> String {
+            match self {
+                Self::Key(key) => key.binding_name(),
+                Self::Button(button) => format!("Mouse:{:?}", button),
+                Self::Cursor => "Cursor".to_string(),
+                Self::Wheel => "Wheel".to_string(),
+            }
+        }
+
+        fn from_binding_name(name: &str) -> Option<Self> {
+            match name {
+                "Cursor" => Some(Self::Cursor),
+                "Wheel" => Some(Self::Wheel),
+                _ => match name.strip_prefix("Mouse:") {
+                    Some(button) => MOUSE_BUTTONS
+                        .iter()
+                        .find(|b| format!("{:?}", b) == button)
+                        .map(|b| Self::Button(*b)),
+                    None => MqKeyCode::from_binding_name(name).map(Self::Key),
+                },
+            }
+        }
+    }
+
+    /// Like [MacroquadInputWrapper], but with the mouse as well as the keyboard.
+    pub struct MacroquadPointerWrapper {}
+
+    impl InputWrapper for MacroquadPointerWrapper {
+        type KeyCode = PointerInput;
+        type InputHelper = ();
+        fn new() -> Self {
+            Self {}
+        }
+
+        fn clear(&mut self) {}
+
+        fn update_held(&mut self, key: &PointerInput, _events: &()) -> bool {
+            match key {
+                PointerInput::Key(key) => is_key_down(*key),
+                PointerInput::Button(button) => is_mouse_button_down(*button),
+                PointerInput::Cursor | PointerInput::Wheel => false,
+            }
+        }
+
+        fn is_pressed(&self, key: &PointerInput, _events: &()) -> bool {
+            match key {
+                PointerInput::Key(key) => is_key_pressed(*key),
+                PointerInput::Button(button) => is_mouse_button_pressed(*button),
+                PointerInput::Cursor | PointerInput::Wheel => false,
+            }
+        }
+
+        fn is_released(&self, key: &PointerInput, _events: &()) -> bool {
+            match key {
+                PointerInput::Key(key) => is_key_released(*key),
+                PointerInput::Button(button) => is_mouse_button_released(*button),
+                PointerInput::Cursor | PointerInput::Wheel => false,
+            }
+        }
+
+        /// Keys come before mouse buttons, so clicking to rebind an action works as long as no key was pressed the same frame.
+        fn last_key_pressed(&self, _events: &()) -> Option<PointerInput> {
+            get_last_key_pressed().map(PointerInput::Key).or_else(|| {
+                MOUSE_BUTTONS
+                    .iter()
+                    .copied()
+                    .find(|button| is_mouse_button_pressed(*button))
+                    .map(PointerInput::Button)
+            })
+        }
+
+        fn analog_value(&self, key: &PointerInput, _events: &()) -> Option<Vec2> {
+            match key {
+                PointerInput::Cursor => Some(mouse_position().into()),
+                PointerInput::Wheel => Some(mouse_wheel().into()),
+                _ => None,
+            }
+        }
+
+        fn input_type(key: &PointerInput) -> InputType {
+            match key {
+                PointerInput::Cursor | PointerInput::Wheel => InputType::Analog,
+                _ => InputType::Digital,
+            }
+        }
     }
 
     #[cfg(feature = "winit-render")]
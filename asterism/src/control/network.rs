@@ -0,0 +1,324 @@
+//! Playing with someone in another process, by sending what keys are held back and forth over UDP.
+//!
+//! Both sides run in lockstep: every frame, each side sends the keys its player held and waits for the other side's keys for the same frame before updating. Both sides then see the same inputs on the same frame, one frame after they were pressed.
+
+use std::collections::VecDeque;
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::time::Duration;
+
+use super::{BindingName, InputType, InputWrapper};
+use macroquad::math::Vec2;
+
+/// A key on this computer or on the peer's.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum NetKey<KeyCode> {
+    Local(KeyCode),
+    Remote(KeyCode),
+}
+
+/// One end of a lockstep connection.
+///
+/// Messages are plain text: the frame number, then the names of the held keys, ex. `12 W Space`.
+pub struct NetPeer {
+    socket: UdpSocket,
+    /// messages from the peer for frames we haven't gotten to yet
+    received: VecDeque<(u32, String)>,
+    /// our messages for the frame before and the current frame, in case the peer didn't get one. The peer is never more than a frame behind, since it needs our message for the frame before to get to this one
+    sent: VecDeque<(u32, String)>,
+    /// how many times to resend a frame before giving up on the peer
+    pub retries: u32,
+}
+
+impl NetPeer {
+    /// Opens a socket at the given address. Use port 0 to let the OS pick one.
+    pub fn bind(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        let socket = UdpSocket::bind(addr)?;
+        socket.set_read_timeout(Some(Duration::from_millis(50)))?;
+        Ok(Self {
+            socket,
+            received: VecDeque::new(),
+            sent: VecDeque::new(),
+            retries: 100,
+        })
+    }
+
+    /// Only exchange frames with the peer at this address.
+    pub fn connect(&self, addr: impl ToSocketAddrs) -> io::Result<()> {
+        self.socket.connect(addr)
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.socket.local_addr()
+    }
+
+    /// Sends the keys held on this side for a frame and waits for the peer's keys for the same frame.
+    pub fn exchange<KeyCode: BindingName>(
+        &mut self,
+        frame: u32,
+        held: &[KeyCode],
+    ) -> io::Result<Vec<KeyCode>> {
+        let mut message = frame.to_string();
+        for key in held {
+            message.push(' ');
+            message.push_str(&key.binding_name());
+        }
+        self.socket.send(message.as_bytes())?;
+        self.sent.push_back((frame, message));
+        if self.sent.len() > 2 {
+            self.sent.pop_front();
+        }
+
+        let mut buf = [0; 1024];
+        let mut tries = 0;
+        loop {
+            if let Some(idx) = self.received.iter().position(|(f, _)| *f == frame) {
+                let (_, keys) = self.received.remove(idx).unwrap();
+                self.received.retain(|(f, _)| *f > frame);
+                return keys
+                    .split_whitespace()
+                    .map(|name| {
+                        KeyCode::from_binding_name(name).ok_or_else(|| {
+                            io::Error::new(
+                                io::ErrorKind::InvalidData,
+                                format!("peer sent unknown key {}", name),
+                            )
+                        })
+                    })
+                    .collect();
+            }
+
+            match self.socket.recv(&mut buf) {
+                Ok(len) => {
+                    let text = String::from_utf8_lossy(&buf[..len]);
+                    let (f, keys) = text.split_once(' ').unwrap_or((&text, ""));
+                    let f: u32 = f.parse().map_err(|_| {
+                        io::Error::new(io::ErrorKind::InvalidData, "peer sent a bad frame number")
+                    })?;
+                    if f >= frame {
+                        self.received.push_back((f, keys.to_string()));
+                    } else {
+                        // the peer is still waiting on an earlier frame, so our message for it must have gotten lost
+                        self.resend(f)?;
+                    }
+                }
+                Err(e)
+                    if e.kind() == io::ErrorKind::WouldBlock
+                        || e.kind() == io::ErrorKind::TimedOut =>
+                {
+                    tries += 1;
+                    if tries > self.retries {
+                        return Err(io::Error::new(
+                            io::ErrorKind::TimedOut,
+                            format!("peer didn't send frame {}", frame),
+                        ));
+                    }
+                    self.resend(frame)?;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    fn resend(&self, frame: u32) -> io::Result<()> {
+        if let Some((_, message)) = self.sent.iter().find(|(f, _)| *f == frame) {
+            self.socket.send(message.as_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+/// Wraps another input wrapper so that some control sets can be bound to a remote player's keys. Local keys are read with the inner wrapper; remote keys come from a [NetPeer], which is set with `connect`.
+///
+/// Only digital inputs are sent over the network. While there's no peer, local keys are read straight from the inner wrapper with no delay, so the same controls work for playing on one computer.
+///
+/// ```
+/// use asterism::control::testing::{Key, TestWrapper};
+/// use asterism::control::*;
+///
+/// type NetControl = KeyboardControl<&'static str, NetworkInputWrapper<TestWrapper<Key>>>;
+///
+/// let p1 = NetPeer::bind("127.0.0.1:0").unwrap();
+/// let p2 = NetPeer::bind("127.0.0.1:0").unwrap();
+/// p1.connect(p2.local_addr().unwrap()).unwrap();
+/// p2.connect(p1.local_addr().unwrap()).unwrap();
+///
+/// // player 2's process: set 0 is the other player, set 1 is them
+/// let other = std::thread::spawn(move || {
+///     let mut control = NetControl::new();
+///     control.add_key_map(0, NetKey::Remote(Key('w')), "up", true);
+///     control.add_key_map(1, NetKey::Local(Key('o')), "up", true);
+///     control.input_wrapper_mut().connect(p2);
+///     for _ in 0..2 {
+///         control.update(&vec![Key('o')]);
+///     }
+///     (control.values[0][0].value, control.values[1][0].value)
+/// });
+///
+/// // player 1's process: set 0 is them, set 1 is the other player
+/// let mut control = NetControl::new();
+/// control.add_key_map(0, NetKey::Local(Key('w')), "up", true);
+/// control.add_key_map(1, NetKey::Remote(Key('o')), "up", true);
+/// control.input_wrapper_mut().connect(p1);
+/// for _ in 0..2 {
+///     control.update(&vec![Key('w')]);
+/// }
+///
+/// // both players' presses show up on both sides on the same frame
+/// assert_eq!((control.values[0][0].value, control.values[1][0].value), (1.0, 1.0));
+/// assert_eq!(other.join().unwrap(), (1.0, 1.0));
+/// ```
+pub struct NetworkInputWrapper<W: InputWrapper> {
+    local: W,
+    peer: Option<NetPeer>,
+    frame: u32,
+    /// local keys held during the current update, sent at the start of the next one
+    pending: Vec<W::KeyCode>,
+    /// the keys held on each side for the frame being played, and for the frame before
+    this_local: Vec<W::KeyCode>,
+    last_local: Vec<W::KeyCode>,
+    this_remote: Vec<W::KeyCode>,
+    last_remote: Vec<W::KeyCode>,
+    error: Option<io::Error>,
+}
+
+impl<W: InputWrapper> NetworkInputWrapper<W> {
+    /// Starts exchanging keys with a peer. Frames are counted from when the peer is connected, so both sides should connect before their first update.
+    pub fn connect(&mut self, peer: NetPeer) {
+        self.peer = Some(peer);
+        self.frame = 0;
+    }
+
+    /// Stops talking to the peer. Remote keys are let go.
+    pub fn disconnect(&mut self) -> Option<NetPeer> {
+        self.peer.take()
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.peer.is_some()
+    }
+
+    /// The last error from talking to the peer, if any. The peer is disconnected when there's an error.
+    pub fn take_error(&mut self) -> Option<io::Error> {
+        self.error.take()
+    }
+
+    fn held(&self, key: &NetKey<W::KeyCode>) -> (bool, bool) {
+        match key {
+            NetKey::Local(key) => (self.this_local.contains(key), self.last_local.contains(key)),
+            NetKey::Remote(key) => (
+                self.this_remote.contains(key),
+                self.last_remote.contains(key),
+            ),
+        }
+    }
+}
+
+impl<W> InputWrapper for NetworkInputWrapper<W>
+where
+    W: InputWrapper,
+    W::KeyCode: BindingName,
+{
+    type KeyCode = NetKey<W::KeyCode>;
+    type InputHelper = W::InputHelper;
+
+    fn new() -> Self {
+        Self {
+            local: W::new(),
+            peer: None,
+            frame: 0,
+            pending: Vec::new(),
+            this_local: Vec::new(),
+            last_local: Vec::new(),
+            this_remote: Vec::new(),
+            last_remote: Vec::new(),
+            error: None,
+        }
+    }
+
+    fn clear(&mut self) {
+        self.local.clear();
+        let held = std::mem::take(&mut self.pending);
+        let remote = match &mut self.peer {
+            Some(peer) => match peer.exchange(self.frame, &held) {
+                Ok(remote) => remote,
+                Err(e) => {
+                    self.error = Some(e);
+                    self.peer = None;
+                    Vec::new()
+                }
+            },
+            None => Vec::new(),
+        };
+        self.frame += 1;
+        self.last_local = std::mem::replace(&mut self.this_local, held);
+        self.last_remote = std::mem::replace(&mut self.this_remote, remote);
+    }
+
+    fn update_held(&mut self, key: &Self::KeyCode, events: &Self::InputHelper) -> bool {
+        if let NetKey::Local(local) = key {
+            let held = self.local.update_held(local, events);
+            if self.peer.is_none() {
+                return held;
+            }
+            if held && !self.pending.contains(local) {
+                self.pending.push(*local);
+            }
+        }
+        self.held(key).0
+    }
+
+    fn is_pressed(&self, key: &Self::KeyCode, events: &Self::InputHelper) -> bool {
+        match key {
+            NetKey::Local(local) if self.peer.is_none() => self.local.is_pressed(local, events),
+            _ => {
+                let (this, last) = self.held(key);
+                this && !last
+            }
+        }
+    }
+
+    fn is_released(&self, key: &Self::KeyCode, events: &Self::InputHelper) -> bool {
+        match key {
+            NetKey::Local(local) if self.peer.is_none() => self.local.is_released(local, events),
+            _ => {
+                let (this, last) = self.held(key);
+                !this && last
+            }
+        }
+    }
+
+    fn last_key_pressed(&self, events: &Self::InputHelper) -> Option<Self::KeyCode> {
+        self.local.last_key_pressed(events).map(NetKey::Local)
+    }
+
+    fn analog_value(&self, key: &Self::KeyCode, events: &Self::InputHelper) -> Option<Vec2> {
+        match key {
+            NetKey::Local(local) => self.local.analog_value(local, events),
+            NetKey::Remote(_) => None,
+        }
+    }
+
+    fn input_type(key: &Self::KeyCode) -> InputType {
+        match key {
+            NetKey::Local(local) => W::input_type(local),
+            NetKey::Remote(_) => InputType::Digital,
+        }
+    }
+}
+
+impl<KeyCode: BindingName> BindingName for NetKey<KeyCode> {
+    fn binding_name(&self) -> String {
+        match self {
+            NetKey::Local(key) => key.binding_name(),
+            NetKey::Remote(key) => format!("Remote:{}", key.binding_name()),
+        }
+    }
+
+    fn from_binding_name(name: &str) -> Option<Self> {
+        match name.strip_prefix("Remote:") {
+            Some(name) => KeyCode::from_binding_name(name).map(NetKey::Remote),
+            None => KeyCode::from_binding_name(name).map(NetKey::Local),
+        }
+    }
+}
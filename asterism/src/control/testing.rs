@@ -0,0 +1,48 @@
+//! Stand-ins for a real input handler, shared by the control doctests and the integration tests.
+
+use super::{BindingName, InputWrapper};
+
+/// An input wrapper where the input helper is the keys that are down this frame.
+pub struct TestWrapper<KeyCode = char> {
+    last: Vec<KeyCode>,
+    this: Vec<KeyCode>,
+}
+
+impl<KeyCode: Copy + Eq> InputWrapper for TestWrapper<KeyCode> {
+    type KeyCode = KeyCode;
+    type InputHelper = Vec<KeyCode>;
+    fn new() -> Self {
+        Self {
+            last: Vec::new(),
+            this: Vec::new(),
+        }
+    }
+    fn clear(&mut self) {
+        self.last = std::mem::take(&mut self.this);
+    }
+    fn update_held(&mut self, key: &KeyCode, down: &Vec<KeyCode>) -> bool {
+        if down.contains(key) {
+            self.this.push(*key);
+        }
+        down.contains(key)
+    }
+    fn is_pressed(&self, key: &KeyCode, _: &Vec<KeyCode>) -> bool {
+        self.this.contains(key) && !self.last.contains(key)
+    }
+    fn is_released(&self, key: &KeyCode, down: &Vec<KeyCode>) -> bool {
+        self.last.contains(key) && !down.contains(key)
+    }
+}
+
+/// A key that's saved by name, for when keys need a [BindingName].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Key(pub char);
+
+impl BindingName for Key {
+    fn binding_name(&self) -> String {
+        self.0.to_string()
+    }
+    fn from_binding_name(name: &str) -> Option<Self> {
+        name.chars().next().map(Key)
+    }
+}
@@ -3,9 +3,12 @@
 //! Entity-state logics communicate that game entities act in different ways or have different capabilities at different times, in ways that are intrinsic to each such entity. They govern the finite, discrete states of a set of game characters or other entities, update states when necessary, and condition the operators of other logics on entities' discrete states.
 
 use crate::graph::StateMachine;
+use crate::snapshot::{snapshot_fields, Snapshot};
 use crate::{Event, EventType, LendingIterator, Logic, Reaction};
 
 /// An entity-state logic for flat entity state machines.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FlatEntityState<ID: Copy + Eq> {
     /// A vec of state machines
     pub graphs: Vec<StateMachine<ID>>,
@@ -110,6 +113,7 @@ pub struct State<ID> {
 }
 
 #[derive(Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EntityEvent {
     pub graph: usize,
     pub node: usize,
@@ -117,6 +121,7 @@ pub struct EntityEvent {
 }
 
 #[derive(Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EntityEventType {
     Activated,
     Traversed(usize), // last node (which edge)
@@ -137,6 +142,10 @@ pub enum EntityReaction {
 
 impl Reaction for EntityReaction {}
 
+impl<ID: Copy + Eq> Snapshot for FlatEntityState<ID> {
+    snapshot_fields!(graphs, just_traversed, events);
+}
+
 impl<ID: Copy + Eq + 'static> Logic for FlatEntityState<ID> {
     type Event = EntityEvent;
     type Reaction = EntityReaction;
@@ -193,10 +202,8 @@ where
 {
     type Item<'a> = (
         <FlatEntityState<ID> as Logic>::Ident,
-        <FlatEntityState<ID> as Logic>::IdentDataMut<'a>
-    )
-    where
-        Self: 'a;
+        <FlatEntityState<ID> as Logic>::IdentDataMut<'a>,
+    );
 
     fn next(&mut self) -> Option<Self::Item<'_>> {
         if self.count == self.ent_state.graphs.len() {
@@ -210,29 +217,3 @@ where
         }
     }
 }
-
-pub struct FesEventIter<'logic, ID>
-where
-    ID: Copy + Eq + 'static,
-{
-    ent_state: &'logic FlatEntityState<ID>,
-    count: usize,
-}
-
-impl<'logic, ID> LendingIterator for FesEventIter<'logic, ID>
-where
-    ID: Copy + Eq + 'static,
-{
-    type Item<'a> = &'a EntityEvent
-    where
-        Self: 'a;
-
-    fn next(&mut self) -> Option<Self::Item<'_>> {
-        self.count += 1;
-        if self.count == self.ent_state.events.len() {
-            None
-        } else {
-            Some(&self.ent_state.events[self.count - 1])
-        }
-    }
-}
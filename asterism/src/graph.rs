@@ -5,6 +5,8 @@
 /// Uses a condition table to check if an edge is traversable. If `graph.conditions[node_idx] == true`, then the edge from `graph.nodes[current_node]` to `graph.nodes[node_idx]` is traversable.
 ///
 /// should definitely eventually separate the condition table part from the actual graph representation
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct StateMachine<NodeID: Copy + Eq> {
     /// graph
     pub graph: Graph<NodeID>,
@@ -59,6 +61,7 @@ impl<NodeID: Copy + Eq> StateMachine<NodeID> {
 
 #[derive(Clone)]
 /// A graph implemented with an adjacency matrix
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Graph<NodeID: Clone + Eq> {
     /// list of nodes in the graph. Possibly unnecessary, I can't decide if I want to remove this or not
     pub nodes: Vec<NodeID>,
@@ -6,6 +6,7 @@ pub use macroquad::{
     texture::DrawTextureParams,
 };
 
+use crate::snapshot::Snapshot;
 use macroquad::{shapes::*, text, texture};
 
 use futures::executor::block_on;
@@ -51,6 +52,34 @@ impl<LogicsList> Draw<LogicsList> {
     }
 }
 
+/// The drawables in a [Draw] and where they're drawn, for rewinding a game. Textures stay loaded, so they aren't included.
+#[derive(Clone)]
+pub struct DrawSnapshot<LogicsList> {
+    pub positions: Vec<DrawType<LogicsList>>,
+    pub drawables: Vec<Drawable>,
+}
+
+impl<LogicsList: Clone> Snapshot for Draw<LogicsList> {
+    type State = DrawSnapshot<LogicsList>;
+
+    fn snapshot(&self) -> Self::State {
+        DrawSnapshot {
+            positions: self.positions.clone(),
+            drawables: self.drawables.clone(),
+        }
+    }
+
+    fn snapshot_into(&self, state: &mut Self::State) {
+        state.positions.clone_from(&self.positions);
+        state.drawables.clone_from(&self.drawables);
+    }
+
+    fn restore(&mut self, state: &Self::State) {
+        self.positions.clone_from(&state.positions);
+        self.drawables.clone_from(&state.drawables);
+    }
+}
+
 pub struct DrawRects<LogicsList> {
     positions: Vec<DrawType<LogicsList>>,
     drawables: Vec<Drawable>,
@@ -80,7 +109,7 @@ impl<LogicsList> DrawRects<LogicsList> {
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum Drawable {
     Rectangle(Vec2, Color),
     Sprite(Vec2, TexID),
@@ -146,6 +175,7 @@ impl Drawable {
 #[derive(PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Debug)]
 pub struct TexID(u32);
 
+#[derive(Clone)]
 pub enum DrawType<WhichLogic> {
     FromLogic(WhichLogic),
     Offset(WhichLogic, Vec2),
@@ -189,14 +219,14 @@ impl Textures {
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Animation {
     rect: Rect,
     animations: Vec<AnimData>,
     current_frame: (usize, usize),
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 struct AnimData {
     frames: Vec<TexID>,
     loops: bool,
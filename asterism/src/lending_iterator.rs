@@ -1,22 +1,124 @@
 use std::iter::Iterator;
 
+use polonius_the_crab::{polonius_loop, polonius_return};
+
+/// An iterator whose items can borrow from the iterator itself, like the data of a logic.
+///
+/// `Item` has no `where Self: 'a` bound, so that closures over items (like the ones [filter](LendingIterator::filter) and [for_each](LendingIterator::for_each) take) work on iterators that borrow from a logic. Those closures have to take an item of any lifetime, and with the bound, that would mean the iterator itself has to outlive any lifetime, i.e. be `'static`. The catch is that an item has to make sense for any lifetime too, so it can't name one of the iterator's type parameters behind `'a` unless that parameter is `'static`. Iterators over generic data, like [QueuedResources](crate::resources::QueuedResources)' pools, lend items for the iterator's own lifetime instead.
+///
+/// ```
+/// use asterism::physics::PointPhysics;
+/// use asterism::{LendingIterator, Logic};
+/// use macroquad::math::Vec2;
+///
+/// let mut physics = PointPhysics::new();
+/// physics.add_physics_entity(Vec2::ZERO, Vec2::ZERO, Vec2::ZERO);
+/// physics.add_physics_entity(Vec2::ZERO, Vec2::X, Vec2::ZERO);
+/// physics.add_physics_entity(Vec2::ZERO, Vec2::Y, Vec2::ZERO);
+///
+/// // stop everything that's moving sideways
+/// physics
+///     .data_iter()
+///     .filter(|(_, data)| data.vel.x != 0.0)
+///     .for_each(|(_, data)| *data.vel = Vec2::ZERO);
+///
+/// let moving: Vec<_> = physics
+///     .data_iter()
+///     .filter(|(_, data)| *data.vel != Vec2::ZERO)
+///     .map(|(idx, _)| idx)
+///     .collect();
+/// assert_eq!(moving, vec![2]);
+/// ```
 pub trait LendingIterator {
-    type Item<'a>
-    where
-        Self: 'a;
+    type Item<'a>;
 
     fn next(&mut self) -> Option<Self::Item<'_>>;
 
-    /* fn filter<F>(self, function: F) -> Filter<Self>
+    /// Never needs implementing. Rust requires `Item` to have a `where Self: 'a` bound unless some method uses `Item` without borrowing `self` (rust-lang/rust#87479), so this is that method.
+    #[doc(hidden)]
+    fn item_without_self<'a>(item: Self::Item<'a>) -> Self::Item<'a> {
+        item
+    }
+
+    /// Only lends out the items that `predicate` returns true for.
+    fn filter<P>(self, predicate: P) -> Filter<Self, P>
     where
-        F: Fn(&<Self as LendingIterator>::Item<'_>) -> bool + 'static,
+        P: FnMut(&Self::Item<'_>) -> bool,
         Self: std::marker::Sized,
     {
         Filter {
             iter: self,
-            function: Box::new(function),
+            predicate,
+        }
+    }
+
+    /// Turns each item into something that doesn't borrow from the iterator, making a regular [Iterator], ex. getting the idents of a logic's entities to collect them.
+    fn map<B, F>(self, function: F) -> Map<Self, F>
+    where
+        F: FnMut(Self::Item<'_>) -> B,
+        Self: std::marker::Sized,
+    {
+        Map {
+            iter: self,
+            function,
+        }
+    }
+
+    /// Calls `function` on every item.
+    fn for_each<F>(mut self, mut function: F)
+    where
+        F: FnMut(Self::Item<'_>),
+        Self: std::marker::Sized,
+    {
+        while let Some(item) = self.next() {
+            function(item);
         }
-    } */
+    }
+
+    /// The first item that `predicate` returns true for.
+    fn find<P>(&mut self, mut predicate: P) -> Option<Self::Item<'_>>
+    where
+        P: FnMut(&Self::Item<'_>) -> bool,
+    {
+        // the borrow checker can't tell that an item only outlives the loop when it's returned (rust-lang/rust#54663), so polonius_the_crab checks it instead
+        let mut iter = self;
+        polonius_loop!(|iter| -> Option<Self::Item<'polonius>> {
+            match iter.next() {
+                Some(item) if predicate(&item) => polonius_return!(Some(item)),
+                Some(_) => {}
+                None => polonius_return!(None),
+            }
+        })
+    }
+
+    /// Lends out at most `n` items.
+    fn take(self, n: usize) -> Take<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        Take { iter: self, n }
+    }
+
+    /// Skips the first `n` items.
+    fn skip(self, n: usize) -> Skip<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        Skip { iter: self, n }
+    }
+
+    /// Combines every item into one value, starting from `init`.
+    fn fold<B, F>(mut self, init: B, mut function: F) -> B
+    where
+        F: FnMut(B, Self::Item<'_>) -> B,
+        Self: std::marker::Sized,
+    {
+        let mut acc = init;
+        while let Some(item) = self.next() {
+            acc = function(acc, item);
+        }
+        acc
+    }
 
     fn zip_lending<'item, I>(self, other_iter: I) -> ZipLending<Self, I>
     where
@@ -54,18 +156,97 @@ pub trait LendingIterator {
     }
 }
 
-/* pub struct Filter<I: LendingIterator> {
+pub struct Filter<I, P> {
+    iter: I,
+    predicate: P,
+}
+
+impl<I, P> LendingIterator for Filter<I, P>
+where
+    I: LendingIterator,
+    P: FnMut(&I::Item<'_>) -> bool,
+{
+    type Item<'a> = I::Item<'a>;
+
+    fn next(&mut self) -> Option<Self::Item<'_>> {
+        let Self { iter, predicate } = self;
+        iter.find(predicate)
+    }
+}
+
+pub struct Map<I, F> {
+    iter: I,
+    function: F,
+}
+
+impl<I, B, F> Iterator for Map<I, F>
+where
+    I: LendingIterator,
+    F: FnMut(I::Item<'_>) -> B,
+{
+    type Item = B;
+
+    fn next(&mut self) -> Option<B> {
+        self.iter.next().map(&mut self.function)
+    }
+}
+
+pub struct Take<I> {
+    iter: I,
+    n: usize,
+}
+
+impl<I: LendingIterator> LendingIterator for Take<I> {
+    type Item<'a> = I::Item<'a>;
+
+    fn next(&mut self) -> Option<Self::Item<'_>> {
+        if self.n == 0 {
+            None
+        } else {
+            self.n -= 1;
+            self.iter.next()
+        }
+    }
+}
+
+pub struct Skip<I> {
     iter: I,
-    function: Box<dyn Fn(&I::Item<'_>) -> bool>,
+    n: usize,
 }
 
-impl<I: LendingIterator> LendingIterator for Filter<I> {
-    type Item<'a> = I::Item<'a> where I: 'a;
+impl<I: LendingIterator> LendingIterator for Skip<I> {
+    type Item<'a> = I::Item<'a>;
 
     fn next(&mut self) -> Option<Self::Item<'_>> {
-        self.iter.next().filter(self.function.as_mut())
+        while self.n > 0 {
+            self.n -= 1;
+            self.iter.next()?;
+        }
+        self.iter.next()
     }
-} */
+}
+
+/// A lending iterator over a slice of events, see [Logic::events_iter][crate::Logic::events_iter].
+pub struct EventsIter<'logic, E> {
+    events: &'logic [E],
+    count: usize,
+}
+
+impl<'logic, E> EventsIter<'logic, E> {
+    pub fn new(events: &'logic [E]) -> Self {
+        Self { events, count: 0 }
+    }
+}
+
+impl<'logic, E> LendingIterator for EventsIter<'logic, E> {
+    type Item<'a> = &'logic E;
+
+    fn next(&mut self) -> Option<Self::Item<'_>> {
+        let event = self.events.get(self.count)?;
+        self.count += 1;
+        Some(event)
+    }
+}
 
 pub struct ZipLending<I: LendingIterator, J: LendingIterator> {
     iter_fst: I,
@@ -73,7 +254,7 @@ pub struct ZipLending<I: LendingIterator, J: LendingIterator> {
 }
 
 impl<I: LendingIterator, J: LendingIterator> LendingIterator for ZipLending<I, J> {
-    type Item<'a> = (I::Item<'a>, J::Item<'a>) where I: 'a, J: 'a;
+    type Item<'a> = (I::Item<'a>, J::Item<'a>);
 
     fn next(&mut self) -> Option<Self::Item<'_>> {
         let fst = self.iter_fst.next();
@@ -93,7 +274,7 @@ pub struct Zip<I: LendingIterator, J: Iterator> {
 }
 
 impl<I: LendingIterator, J: Iterator> LendingIterator for Zip<I, J> {
-    type Item<'a> = (I::Item<'a>, J::Item) where I: 'a, J: 'a;
+    type Item<'a> = (I::Item<'a>, J::Item);
 
     fn next(&mut self) -> Option<Self::Item<'_>> {
         let fst = self.iter_fst.next();
@@ -113,7 +294,7 @@ pub struct Enumerate<I: LendingIterator> {
 }
 
 impl<I: LendingIterator> LendingIterator for Enumerate<I> {
-    type Item<'a> = (usize, I::Item<'a>) where I: 'a;
+    type Item<'a> = (usize, I::Item<'a>);
 
     fn next(&mut self) -> Option<Self::Item<'_>> {
         let count = self.count;
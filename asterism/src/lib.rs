@@ -7,6 +7,8 @@
 //! The descriptions of logics in the modules are lightly modified from Prof Osborn's dissertation.
 //!
 //! Requires at least Rust 1.68---if this doesn't compile, update your rustc.
+//!
+//! With the `serde` feature, the logics (and the data and events inside them) can be serialized and deserialized, so games can be saved and loaded.
 
 #![allow(clippy::new_without_default)]
 #![allow(clippy::upper_case_acronyms)]
@@ -15,13 +17,21 @@ pub mod control;
 pub mod entity_state;
 pub mod linking;
 pub mod physics;
+pub mod predicates;
 pub mod resources;
+pub mod scheduler;
+pub mod snapshot;
 
 pub mod graph;
 pub mod graphics;
 pub mod lending_iterator;
 
-pub use lending_iterator::LendingIterator;
+#[cfg(feature = "serde")]
+mod serde_map;
+#[cfg(feature = "serde")]
+pub mod serde_math;
+
+pub use lending_iterator::{EventsIter, LendingIterator};
 
 /// An operational logic
 pub trait Logic {
@@ -61,6 +71,10 @@ pub trait Logic {
     fn data_iter(&mut self) -> Self::DataIter<'_>;
     /// returns an array slice for the events of the logic
     fn events(&self) -> &[Self::Event];
+    /// returns a [lending iterator](lending_iterator::LendingIterator) for the events of the logic, so they can be zipped with `data_iter` and the like
+    fn events_iter(&self) -> EventsIter<'_, Self::Event> {
+        EventsIter::new(self.events())
+    }
 }
 
 /// An event produced by the logic. Holds both the data associated with the event and information about what the event is---these should be separated for easier matching.
@@ -4,11 +4,14 @@
 //!
 //! Linking logics are incredibly broad and have a wide range of uses.
 use crate::graph::StateMachine;
+use crate::snapshot::{snapshot_fields, Snapshot};
 use crate::{Event, EventType, LendingIterator, Logic, Reaction};
 
 /// A generic linking logic. See [StateMachine][crate::graph::StateMachine] documentation for more information.
 ///
 /// I think this is the exact same code as FlatEntityState actually. The difference might make become more clear when rendering?
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GraphedLinking<NodeID: Copy + Eq> {
     /// A vec of state machines
     pub graphs: Vec<StateMachine<NodeID>>,
@@ -29,8 +32,12 @@ impl<NodeID: Copy + Eq + 'static> GraphedLinking<NodeID> {
     /// Updates the linking logic.
     ///
     /// Check the status of all the links from the current node in the condition table. If any of those links are `true`, i.e. that node can be moved to, move the current position.
+    ///
+    /// Each edge is checked by the condition of the node it leads to, and taking it moves the graph to that node. Only the events from this update are kept.
     pub fn update(&mut self) {
         self.just_traversed.fill(false);
+        self.events.clear();
+
         for (i, (graph, traversed)) in self
             .graphs
             .iter_mut()
@@ -56,14 +63,14 @@ impl<NodeID: Copy + Eq + 'static> GraphedLinking<NodeID> {
             self.events.append(&mut activated);
 
             for edge in graph.graph.get_edges(graph.current_node) {
-                if graph.conditions[i] {
+                if graph.conditions[edge] {
                     *traversed = true;
                     self.events.push(LinkingEvent {
                         graph: i,
                         node: edge,
                         event_type: LinkingEventType::Traversed(graph.current_node),
                     });
-                    graph.current_node = i;
+                    graph.current_node = edge;
                     break;
                 }
             }
@@ -97,6 +104,7 @@ impl<NodeID: Copy + Eq + 'static> GraphedLinking<NodeID> {
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LinkingEvent {
     pub graph: usize,
     pub node: usize,
@@ -104,6 +112,7 @@ pub struct LinkingEvent {
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum LinkingEventType {
     Activated,
     Traversed(usize), // last node (which edge)
@@ -128,6 +137,10 @@ pub enum LinkingReaction {
 
 impl Reaction for LinkingReaction {}
 
+impl<NodeID: Copy + Eq> Snapshot for GraphedLinking<NodeID> {
+    snapshot_fields!(graphs, just_traversed, events);
+}
+
 impl<NodeID: Copy + Eq + 'static> Logic for GraphedLinking<NodeID> {
     type Event = LinkingEvent;
     type Reaction = LinkingReaction;
@@ -184,10 +197,8 @@ where
 {
     type Item<'a> = (
         <GraphedLinking<ID> as Logic>::Ident,
-        <GraphedLinking<ID> as Logic>::IdentDataMut<'a>
-    )
-    where
-        Self: 'a;
+        <GraphedLinking<ID> as Logic>::IdentDataMut<'a>,
+    );
 
     fn next(&mut self) -> Option<Self::Item<'_>> {
         if self.count == self.linking.graphs.len() {
@@ -201,29 +212,3 @@ where
         }
     }
 }
-
-pub struct LinkingEventIter<'logic, ID>
-where
-    ID: Copy + Eq + 'static,
-{
-    linking: &'logic GraphedLinking<ID>,
-    count: usize,
-}
-
-impl<'logic, ID> LendingIterator for LinkingEventIter<'logic, ID>
-where
-    ID: Copy + Eq + 'static,
-{
-    type Item<'a> = &'a LinkingEvent
-    where
-        Self: 'a;
-
-    fn next(&mut self) -> Option<Self::Item<'_>> {
-        self.count += 1;
-        if self.count == self.linking.events.len() {
-            None
-        } else {
-            Some(&self.linking.events[self.count - 1])
-        }
-    }
-}
@@ -2,13 +2,19 @@
 //!
 //! Physics logics communicate that physical laws govern the movement of some in-game entities. They update and honor objects' physical properties like position, velocity, density, etc., according to physical laws integrated over time.
 
+use crate::snapshot::{snapshot_fields, Snapshot};
 use crate::{Event, EventType, LendingIterator, Logic, Reaction};
 use macroquad::math::Vec2;
 
 /// A physics logic using 2d points.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PointPhysics {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_math"))]
     pub positions: Vec<Vec2>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_math"))]
     pub velocities: Vec<Vec2>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_math"))]
     pub accelerations: Vec<Vec2>,
     pub events: Vec<PhysicsEvent>,
 }
@@ -24,6 +30,10 @@ pub struct PointPhysDataMut<'data> {
     pub acc: &'data mut Vec2,
 }
 
+impl Snapshot for PointPhysics {
+    snapshot_fields!(positions, velocities, accelerations, events);
+}
+
 impl Logic for PointPhysics {
     type Reaction = PhysicsReaction;
     type Event = PhysicsEvent;
@@ -146,6 +156,7 @@ pub enum PhysicsReaction {
 impl Reaction for PhysicsReaction {}
 
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PhysicsEvent {
     ent: usize,
     event_type: PhysicsEventType,
@@ -159,6 +170,7 @@ impl Event for PhysicsEvent {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PhysicsEventType {
     VelChange,
     PosChange,
@@ -171,7 +183,7 @@ pub struct PtPhysicsDataIter<'phys> {
 }
 
 impl<'phys> LendingIterator for PtPhysicsDataIter<'phys> {
-    type Item<'a> = (<PointPhysics as Logic>::Ident, <PointPhysics as Logic>::IdentDataMut<'a>) where Self: 'a;
+    type Item<'a> = (<PointPhysics as Logic>::Ident, <PointPhysics as Logic>::IdentDataMut<'a>);
 
     fn next(&mut self) -> Option<Self::Item<'_>> {
         if self.count == self.physics.positions.len() {
@@ -185,21 +197,3 @@ impl<'phys> LendingIterator for PtPhysicsDataIter<'phys> {
         }
     }
 }
-
-pub struct PtPhysicsEventIter<'phys> {
-    physics: &'phys PointPhysics,
-    count: usize,
-}
-
-impl<'phys> LendingIterator for PtPhysicsEventIter<'phys> {
-    type Item<'a> = &'a PhysicsEvent where Self: 'a;
-
-    fn next(&mut self) -> Option<Self::Item<'_>> {
-        self.count += 1;
-        if self.count == self.physics.events.len() {
-            None
-        } else {
-            Some(&self.physics.events[self.count - 1])
-        }
-    }
-}
@@ -0,0 +1,282 @@
+//! # Predicate tables
+//!
+//! Engines decide what to do each frame by checking the logics' events against the conditions the game cares about. A [PredicateTable] holds those conditions as [Rule]s over an engine's logics (see [Scheduler](crate::scheduler::Scheduler)), each with an action to take when it's satisfied. Rules can need events from more than one logic at once, look at the data in those events, and check the logics' state, ex. "the ball and the paddle are touching _and_ the score is over 3".
+//!
+//! Rules are looked up by the type of event they need, so only the rules that could be satisfied by this frame's events are checked. When a rule is satisfied, the events that satisfied it come along with its action, ex. to remove whatever the ball just hit.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::mem::{discriminant, Discriminant};
+
+use crate::scheduler::LogicID;
+use crate::{Event, Logic};
+
+type EventKey<L> = Discriminant<<<L as Logic>::Event as Event>::EventType>;
+type Pattern<C, L> = Box<dyn Fn(&<L as Logic>::Event, &C) -> bool>;
+type StateCondition<C> = Box<dyn Fn(&C) -> bool>;
+
+/// Something that has to happen for a rule to be satisfied, along with what the game does when it is.
+pub struct Rule<C, A> {
+    action: A,
+    events: Vec<Box<dyn EventCondition<C>>>,
+    states: Vec<StateCondition<C>>,
+}
+
+impl<C: 'static, A> Rule<C, A> {
+    /// A rule that's always satisfied until conditions are added.
+    pub fn new(action: A) -> Self {
+        Self {
+            action,
+            events: Vec::new(),
+            states: Vec::new(),
+        }
+    }
+
+    /// Needs `logic` to have made an event of the same type as `event_type` in its last update that `pattern` returns true for. `pattern` gets all of the logics too, so it can look events' data up in them. Only the variant of `event_type` matters, so any data in it can be filled in with anything and checked in `pattern` instead.
+    pub fn on_event<L: Logic + 'static>(
+        mut self,
+        logic: LogicID<C, L>,
+        event_type: <L::Event as Event>::EventType,
+        pattern: impl Fn(&L::Event, &C) -> bool + 'static,
+    ) -> Self {
+        self.events.push(Box::new(OnEvent {
+            logic,
+            key: discriminant(&event_type),
+            pattern: Box::new(pattern),
+        }));
+        self
+    }
+
+    /// Needs `condition` to be true of the logics, ex. a resource being over some amount.
+    pub fn when(mut self, condition: impl Fn(&C) -> bool + 'static) -> Self {
+        self.states.push(Box::new(condition));
+        self
+    }
+
+    pub fn action(&self) -> &A {
+        &self.action
+    }
+
+    /// the events that satisfy the rule, if it's satisfied
+    fn satisfied_by(&self, logics: &C) -> Option<Vec<(usize, usize)>> {
+        let mut matched = Vec::new();
+        for event in self.events.iter() {
+            if !event.matches(logics, &mut matched) {
+                return None;
+            }
+        }
+        if !self.states.iter().all(|state| state(logics)) {
+            return None;
+        }
+        matched.sort_unstable();
+        matched.dedup();
+        Some(matched)
+    }
+}
+
+trait EventCondition<C> {
+    /// adds the logic and index of each matching event, and returns whether there were any
+    fn matches(&self, logics: &C, matched: &mut Vec<(usize, usize)>) -> bool;
+    /// adds the rule this condition is from to the index for its logic
+    fn index(&self, rule: usize, indices: &mut HashMap<usize, Box<dyn EventIndex<C>>>);
+}
+
+struct OnEvent<C, L: Logic> {
+    logic: LogicID<C, L>,
+    key: EventKey<L>,
+    pattern: Pattern<C, L>,
+}
+
+impl<C: 'static, L: Logic + 'static> EventCondition<C> for OnEvent<C, L> {
+    fn matches(&self, logics: &C, matched: &mut Vec<(usize, usize)>) -> bool {
+        let before = matched.len();
+        matched.extend(
+            self.logic
+                .get(logics)
+                .events()
+                .iter()
+                .enumerate()
+                .filter(|(_, event)| {
+                    discriminant(event.get_type()) == self.key && (self.pattern)(event, logics)
+                })
+                .map(|(i, _)| (self.logic.idx(), i)),
+        );
+        matched.len() > before
+    }
+
+    fn index(&self, rule: usize, indices: &mut HashMap<usize, Box<dyn EventIndex<C>>>) {
+        let index = indices.entry(self.logic.idx()).or_insert_with(|| {
+            Box::new(IndexFor {
+                logic: self.logic,
+                rules: HashMap::new(),
+            })
+        });
+        let index: &mut IndexFor<C, L> = index
+            .as_any_mut()
+            .downcast_mut()
+            .expect("logic IDs always have the right type");
+        index.rules.entry(self.key).or_default().push(rule);
+    }
+}
+
+/// The rules that need each type of event from one logic.
+trait EventIndex<C> {
+    /// adds the rules that need any of the logic's current events
+    fn triggered(&self, logics: &C, rules: &mut Vec<usize>);
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+struct IndexFor<C, L: Logic> {
+    logic: LogicID<C, L>,
+    rules: HashMap<EventKey<L>, Vec<usize>>,
+}
+
+impl<C: 'static, L: Logic + 'static> EventIndex<C> for IndexFor<C, L> {
+    fn triggered(&self, logics: &C, rules: &mut Vec<usize>) {
+        for event in self.logic.get(logics).events() {
+            if let Some(r) = self.rules.get(&discriminant(event.get_type())) {
+                rules.extend(r);
+            }
+        }
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Rules to check against the logics every frame.
+///
+/// ```
+/// use asterism::collision::{AabbCollision, CollisionEventType};
+/// use asterism::predicates::{PredicateTable, Rule};
+/// use asterism::resources::{PoolValues, QueuedResources};
+/// use asterism::scheduler::Scheduler;
+/// use macroquad::math::Vec2;
+///
+/// struct Logics {
+///     collision: AabbCollision<char>,
+///     resources: QueuedResources<&'static str, i32>,
+/// }
+///
+/// let mut logics = Logics {
+///     collision: AabbCollision::new(),
+///     resources: QueuedResources::new(),
+/// };
+/// logics.collision.add_collision_entity(Vec2::ZERO, Vec2::ONE, Vec2::ZERO, true, false, 'a');
+/// logics.collision.add_collision_entity(Vec2::X, Vec2::ONE, Vec2::ZERO, true, false, 'b');
+/// logics.resources.items.insert("score", PoolValues::new(0, 0, 10));
+///
+/// let mut scheduler = Scheduler::new();
+/// let collision = scheduler.add_logic(
+///     "collision",
+///     |logics: &Logics| &logics.collision,
+///     |logics| &mut logics.collision,
+///     |logics| logics.collision.update(),
+/// );
+///
+/// let mut table = PredicateTable::new();
+/// table.add_rule(
+///     Rule::new("a touched b")
+///         .on_event(collision, CollisionEventType::Touching, |contact, logics| {
+///             logics.collision.get_ids(contact) == ('a', 'b')
+///         }),
+/// );
+/// table.add_rule(
+///     Rule::new("a touched b with a high score")
+///         .on_event(collision, CollisionEventType::Touching, |contact, logics| {
+///             logics.collision.get_ids(contact) == ('a', 'b')
+///         })
+///         .when(|logics: &Logics| logics.resources.get_value_by_itemtype(&"score") > Some(3)),
+/// );
+///
+/// scheduler.update(&mut logics);
+/// let fired = table.evaluate(&logics);
+/// assert_eq!(fired.len(), 1);
+/// assert_eq!(*fired[0].action, "a touched b");
+/// // the contact between a and b comes along with the action
+/// let contacts = fired[0].events(collision, &logics);
+/// assert_eq!(logics.collision.get_ids(contacts[0]), ('a', 'b'));
+///
+/// logics.resources.items.get_mut("score").unwrap().val = 5;
+/// let fired: Vec<_> = table.evaluate(&logics).iter().map(|fired| *fired.action).collect();
+/// assert_eq!(fired, vec!["a touched b", "a touched b with a high score"]);
+/// ```
+pub struct PredicateTable<C, A> {
+    rules: Vec<Rule<C, A>>,
+    /// the rules that need events, by the logic they need them from
+    indices: HashMap<usize, Box<dyn EventIndex<C>>>,
+    /// the rules that don't need any events, which have to be checked every time
+    unindexed: Vec<usize>,
+}
+
+impl<C: 'static, A> PredicateTable<C, A> {
+    pub fn new() -> Self {
+        Self {
+            rules: Vec::new(),
+            indices: HashMap::new(),
+            unindexed: Vec::new(),
+        }
+    }
+
+    pub fn add_rule(&mut self, rule: Rule<C, A>) {
+        let idx = self.rules.len();
+        if rule.events.is_empty() {
+            self.unindexed.push(idx);
+        }
+        for event in rule.events.iter() {
+            event.index(idx, &mut self.indices);
+        }
+        self.rules.push(rule);
+    }
+
+    pub fn rules(&self) -> &[Rule<C, A>] {
+        &self.rules
+    }
+
+    /// Every rule that's satisfied by the logics' current events and state, in the order the rules were added.
+    pub fn evaluate(&self, logics: &C) -> Vec<Fired<'_, A>> {
+        let mut candidates = self.unindexed.clone();
+        for index in self.indices.values() {
+            index.triggered(logics, &mut candidates);
+        }
+        candidates.sort_unstable();
+        candidates.dedup();
+
+        candidates
+            .into_iter()
+            .filter_map(|rule| {
+                self.rules[rule].satisfied_by(logics).map(|events| Fired {
+                    rule,
+                    action: &self.rules[rule].action,
+                    events,
+                })
+            })
+            .collect()
+    }
+}
+
+/// A rule that was satisfied, and the events that satisfied it.
+pub struct Fired<'table, A> {
+    /// where the rule is in [PredicateTable::rules]
+    pub rule: usize,
+    pub action: &'table A,
+    /// the logic and index of each matching event
+    events: Vec<(usize, usize)>,
+}
+
+impl<'table, A> Fired<'table, A> {
+    /// The events from `logic` that satisfied the rule, in the order the logic made them.
+    pub fn events<'a, C, L: Logic + 'static>(
+        &self,
+        logic: LogicID<C, L>,
+        logics: &'a C,
+    ) -> Vec<&'a L::Event> {
+        let events = logic.get(logics).events();
+        self.events
+            .iter()
+            .filter(|(from, _)| *from == logic.idx())
+            .map(|(_, i)| &events[*i])
+            .collect()
+    }
+}
@@ -2,35 +2,650 @@
 //!
 //! Resource logics communicate that generic or specific resources can be created, destroyed, converted, or transferred between abstract or concrete locations. They create, destroy, and exchange (usually) discrete quantities of generic or specific resources in or between abstract or concrete locations on demand or over time, and trigger other actions when these transactions take place.
 
+use crate::snapshot::{snapshot_fields, Snapshot};
 use crate::{Event, EventType, LendingIterator, Logic, Reaction};
 use num_traits::{Num, Signed};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, VecDeque};
 use std::fmt::Debug;
 
+mod fixed;
+pub mod inventory;
+pub use fixed::Fixed;
+
+/// A value that can be stored in a resource pool, with arithmetic that can't overflow by surprise. Implemented for the signed integers, `f32`, `f64`, and [Fixed].
+pub trait PoolValue: Num + Signed + Copy + PartialOrd {
+    /// `self + rhs`, or `None` if it would overflow.
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    /// `self - rhs`, or `None` if it would overflow.
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    /// `self + rhs`, stopping at the largest or smallest value instead of overflowing.
+    fn saturating_add(self, rhs: Self) -> Self;
+    /// `self - rhs`, stopping at the largest or smallest value instead of overflowing.
+    fn saturating_sub(self, rhs: Self) -> Self;
+    /// `self + rhs`, wrapped around into `min..=max`: going past `max` starts over from `min`, and going past `min` starts over from `max`.
+    fn wrapped_add(self, rhs: Self, min: Self, max: Self) -> Self;
+}
+
+macro_rules! int_pool_value {
+    ($($t:ty),*) => {
+        $(impl PoolValue for $t {
+            fn checked_add(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_add(self, rhs)
+            }
+            fn checked_sub(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_sub(self, rhs)
+            }
+            fn saturating_add(self, rhs: Self) -> Self {
+                <$t>::saturating_add(self, rhs)
+            }
+            fn saturating_sub(self, rhs: Self) -> Self {
+                <$t>::saturating_sub(self, rhs)
+            }
+            fn wrapped_add(self, rhs: Self, min: Self, max: Self) -> Self {
+                // widened so neither the sum nor the size of the range overflows (short of i128 pools spanning most of the type)
+                let (val, min, max) = (self as i128, min as i128, max as i128);
+                let span = max.checked_sub(min).and_then(|span| span.checked_add(1));
+                match (val.checked_add(rhs as i128), span) {
+                    (Some(sum), Some(span)) if span > 0 => {
+                        (min + (sum - min).rem_euclid(span)) as $t
+                    }
+                    _ => self.wrapping_add(rhs),
+                }
+            }
+        })*
+    };
+}
+int_pool_value!(i8, i16, i32, i64, i128, isize);
+
+// floats don't panic, but they do turn into infinity, which is close enough to overflowing
+macro_rules! float_pool_value {
+    ($($t:ty),*) => {
+        $(impl PoolValue for $t {
+            fn checked_add(self, rhs: Self) -> Option<Self> {
+                Some(self + rhs).filter(|sum| sum.is_finite())
+            }
+            fn checked_sub(self, rhs: Self) -> Option<Self> {
+                Some(self - rhs).filter(|diff| diff.is_finite())
+            }
+            fn saturating_add(self, rhs: Self) -> Self {
+                (self + rhs).clamp(<$t>::MIN, <$t>::MAX)
+            }
+            fn saturating_sub(self, rhs: Self) -> Self {
+                (self - rhs).clamp(<$t>::MIN, <$t>::MAX)
+            }
+            // floats are continuous, so max and min are the same point and anything past max starts over right after min
+            fn wrapped_add(self, rhs: Self, min: Self, max: Self) -> Self {
+                let (sum, span) = (self + rhs, max - min);
+                if sum >= min && sum <= max {
+                    return sum;
+                }
+                if !(span.is_finite() && span > 0.0) {
+                    return if sum > max { max } else { min };
+                }
+                // how far past min each part is, added without going past the span, so nothing turns into infinity
+                let (past, by) = ((self - min).rem_euclid(span), rhs.rem_euclid(span));
+                if past >= span - by {
+                    min + (past - (span - by))
+                } else {
+                    min + (past + by)
+                }
+            }
+        })*
+    };
+}
+float_pool_value!(f32, f64);
+
+/// What a pool does with a transaction that would take its value past its min or max.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Overflow {
+    /// The transaction fails with [ResourceError::TooBig] or [ResourceError::TooSmall] (or [ResourceError::Overflow], if the value itself can't hold the result).
+    Checked,
+    /// The pool's value stops at its min or max, ex. a health bar that can't be healed past full.
+    Saturating,
+    /// The pool's value wraps around to its other end, ex. an angle or the hour on a clock. For integers, one past max is min; for fractional values, max and min are the same point, so a quarter past max is a quarter past min.
+    Wrapping,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PoolValues<Value> {
     pub val: Value,
     pub min: Value,
     pub max: Value,
+    /// What happens when a change or set would go past `min` or `max`. Trades are always checked, so they never create or destroy resources.
+    pub overflow: Overflow,
+}
+
+impl<Value: PoolValue> PoolValues<Value> {
+    /// Makes a pool with a [checked](Overflow::Checked) overflow policy.
+    pub fn new(val: Value, min: Value, max: Value) -> Self {
+        Self {
+            val,
+            min,
+            max,
+            overflow: Overflow::Checked,
+        }
+    }
+
+    pub fn with_overflow(mut self, overflow: Overflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
+    /// What the pool's value would be after changing it by `amt` under the given overflow policy.
+    fn changed_by(&self, amt: Value, overflow: Overflow) -> Result<Value, ResourceError> {
+        let val = match (self.val.checked_add(amt), overflow) {
+            (_, Overflow::Wrapping) => return Ok(self.val.wrapped_add(amt, self.min, self.max)),
+            (Some(val), _) => val,
+            (None, Overflow::Saturating) => self.val.saturating_add(amt),
+            (None, Overflow::Checked) => return Err(ResourceError::Overflow),
+        };
+        self.bounded(val, overflow)
+    }
+
+    fn bounded(&self, val: Value, overflow: Overflow) -> Result<Value, ResourceError> {
+        match overflow {
+            Overflow::Checked if val > self.max => Err(ResourceError::TooBig),
+            Overflow::Checked if val < self.min => Err(ResourceError::TooSmall),
+            Overflow::Saturating if val > self.max => Ok(self.max),
+            Overflow::Saturating if val < self.min => Ok(self.min),
+            Overflow::Wrapping if val > self.max || val < self.min => {
+                Ok(val.wrapped_add(num_traits::identities::zero(), self.min, self.max))
+            }
+            _ => Ok(val),
+        }
+    }
+
+    /// The pool after applying the transaction, or why it can't be applied. For trades, this is the pool giving the amount away.
+    ///
+    /// ```
+    /// use asterism::resources::*;
+    /// let health = PoolValues::new(0.75_f32, 0.0, 1.0).with_overflow(Overflow::Saturating);
+    /// let healed = health.after::<()>(&Transaction::Change(0.5)).unwrap();
+    /// assert_eq!(healed.val, 1.0);
+    ///
+    /// let score = PoolValues::new(i16::MAX - 1, 0, i16::MAX);
+    /// assert_eq!(score.after::<()>(&Transaction::Change(2)), Err(ResourceError::Overflow));
+    /// ```
+    pub fn after<ID: Ord + Debug>(
+        &self,
+        transaction: &Transaction<Value, ID>,
+    ) -> Result<Self, ResourceError> {
+        let mut pool = *self;
+        match transaction {
+            Transaction::Change(amt) => pool.val = self.changed_by(*amt, self.overflow)?,
+            Transaction::Set(val) => pool.val = self.bounded(*val, self.overflow)?,
+            Transaction::SetMax(max) => pool.max = *max,
+            Transaction::SetMin(min) => pool.min = *min,
+            Transaction::Trade(amt, _) => {
+                let zero: Value = num_traits::identities::zero();
+                let amt = zero.checked_sub(*amt).ok_or(ResourceError::Overflow)?;
+                pool.val = self.changed_by(amt, Overflow::Checked)?;
+            }
+        }
+        Ok(pool)
+    }
+}
+
+/// The pools on both sides of a trade after it goes through.
+fn trade<ID, Value>(
+    items: &BTreeMap<ID, PoolValues<Value>>,
+    from: &ID,
+    to: &ID,
+    amt: Value,
+) -> Result<(PoolValues<Value>, PoolValues<Value>), ResourceError>
+where
+    ID: Clone + Ord + Debug,
+    Value: PoolValue,
+{
+    let from_pool = items.get(from).ok_or(ResourceError::PoolNotFound)?;
+    let mut to_pool = *items.get(to).ok_or(ResourceError::PoolNotFound)?;
+    let from_pool = from_pool.after(&Transaction::Trade(amt, to.clone()))?;
+    to_pool.val = to_pool.changed_by(amt, Overflow::Checked)?;
+    Ok((from_pool, to_pool))
+}
+
+/// The pool after a (non-trade) transaction.
+fn transact<ID, Value>(
+    items: &BTreeMap<ID, PoolValues<Value>>,
+    id: &ID,
+    transaction: &Transaction<Value, ID>,
+) -> Result<PoolValues<Value>, ResourceError>
+where
+    ID: Clone + Ord + Debug,
+    Value: PoolValue,
+{
+    items
+        .get(id)
+        .ok_or(ResourceError::PoolNotFound)?
+        .after(transaction)
+}
+
+/// A rule for changing a pool's value over time, rather than on demand.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RateRule<Value> {
+    /// Adds the amount to the pool (or removes it, if negative), stopping at the pool's min or max.
+    Gain(Value),
+    /// Moves the pool's value toward `target` by `amount`, stopping once it gets there.
+    DecayToward { target: Value, amount: Value },
+}
+
+/// Applies a [RateRule] to a pool every `period` ticks.
+///
+/// If the pool is changed by a transaction, the generator waits an extra `cooldown` ticks before applying its rule again, ex. health that only regenerates if you haven't been hit recently.
+///
+/// # Example
+///
+/// ```
+/// use asterism::resources::{Generator, PoolValues, QueuedResources, RateRule};
+///
+/// let mut resources: QueuedResources<&str, i16> = QueuedResources::new();
+/// resources.items.insert("combo", PoolValues::new(3, 0, 10));
+/// let decay = RateRule::DecayToward { target: 0, amount: 1 };
+/// resources.add_generator("combo", Generator::new(decay, 2));
+///
+/// for _ in 0..4 {
+///     resources.update();
+/// }
+/// assert_eq!(resources.get_value_by_itemtype(&"combo"), Some(1));
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Generator<Value> {
+    pub rule: RateRule<Value>,
+    /// How many ticks between each time the rule is applied.
+    pub period: usize,
+    /// How many extra ticks to wait after the pool is changed by something else.
+    pub cooldown: usize,
+    /// ticks left before the rule is applied
+    wait: usize,
+}
+
+impl<Value> Generator<Value>
+where
+    Value: PoolValue,
+{
+    pub fn new(rule: RateRule<Value>, period: usize) -> Self {
+        Self {
+            rule,
+            period,
+            cooldown: 0,
+            wait: period,
+        }
+    }
+
+    pub fn with_cooldown(mut self, cooldown: usize) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+
+    /// Restarts the generator's timer, including its cooldown.
+    pub fn reset(&mut self) {
+        self.wait = self.period + self.cooldown;
+    }
+
+    /// Advances the generator by a tick. If the rule applies this tick, returns how much it changes the given pool by.
+    pub fn tick(&mut self, pool: &PoolValues<Value>) -> Option<Value> {
+        if self.wait > 1 {
+            self.wait -= 1;
+            return None;
+        }
+        self.wait = self.period.max(1);
+
+        let zero: Value = num_traits::identities::zero();
+        let amt = match self.rule {
+            RateRule::Gain(amt) => {
+                let to_max = pool.max.saturating_sub(pool.val);
+                let to_min = pool.min.saturating_sub(pool.val);
+                if amt > zero && to_max < amt {
+                    to_max
+                } else if amt < zero && to_min > amt {
+                    to_min
+                } else {
+                    amt
+                }
+            }
+            RateRule::DecayToward { target, amount } => {
+                // saturating so huge distances (or an amount of MIN) can't overflow
+                let amount = if amount < zero {
+                    zero.saturating_sub(amount)
+                } else {
+                    amount
+                };
+                let diff = target.saturating_sub(pool.val);
+                if diff > zero {
+                    if diff < amount {
+                        diff
+                    } else {
+                        amount
+                    }
+                } else if diff > zero - amount {
+                    diff
+                } else {
+                    zero - amount
+                }
+            }
+        };
+
+        if amt == zero {
+            None
+        } else {
+            Some(amt)
+        }
+    }
+}
+
+/// Ticks every generator, applying the changes directly to the pools and logging them as completed transactions. Changes follow the pool's bounds and overflow policy the same way a [Transaction::Change] would.
+fn run_generators<ID, Value>(
+    items: &mut BTreeMap<ID, PoolValues<Value>>,
+    generators: &mut [(ID, Generator<Value>)],
+    thresholds: &[(ID, Value)],
+    ledger: &mut Option<Ledger<ID, Value>>,
+    completed: &mut Vec<ResourceEvent<ID, Value>>,
+) where
+    ID: Clone + Ord + Debug,
+    Value: PoolValue,
+{
+    for (id, generator) in generators.iter_mut() {
+        let pool = match items.get_mut(id) {
+            Some(pool) => pool,
+            None => continue,
+        };
+        if let Some(amt) = generator.tick(pool) {
+            let transaction = Transaction::Change(amt);
+            let val = match pool.changed_by(amt, pool.overflow) {
+                Ok(val) => val,
+                Err(err) => {
+                    completed.push(ResourceEvent {
+                        pool: id.clone(),
+                        transaction,
+                        event_type: ResourceEventType::TransactionUnsuccessful(err),
+                    });
+                    continue;
+                }
+            };
+            let before = *pool;
+            let old = pool.val;
+            pool.val = val;
+            if let Some(ledger) = ledger {
+                ledger.record(
+                    TransactionOrigin::Generator,
+                    id,
+                    &transaction,
+                    vec![(id.clone(), before)],
+                );
+            }
+            completed.push(ResourceEvent {
+                pool: id.clone(),
+                transaction: transaction.clone(),
+                event_type: ResourceEventType::PoolUpdated,
+            });
+            log_boundaries(completed, thresholds, id, pool, old, &transaction);
+        }
+    }
+}
+
+/// Logs the boundaries a pool crossed when its value changed from `old` to its current value: reaching its min or max, or crossing any of its thresholds.
+fn log_boundaries<ID, Value>(
+    completed: &mut Vec<ResourceEvent<ID, Value>>,
+    thresholds: &[(ID, Value)],
+    id: &ID,
+    pool: &PoolValues<Value>,
+    old: Value,
+    transaction: &Transaction<Value, ID>,
+) where
+    ID: Clone + Ord + Debug,
+    Value: PoolValue,
+{
+    let mut log = |event_type| {
+        completed.push(ResourceEvent {
+            pool: id.clone(),
+            transaction: transaction.clone(),
+            event_type,
+        })
+    };
+
+    if pool.val == pool.max && old != pool.max {
+        log(ResourceEventType::ReachedMax);
+    }
+    if pool.val == pool.min && old != pool.min {
+        log(ResourceEventType::ReachedMin);
+    }
+    for (_, threshold) in thresholds.iter().filter(|(pool_id, _)| pool_id == id) {
+        if old < *threshold && pool.val >= *threshold {
+            log(ResourceEventType::CrossedThreshold(
+                *threshold,
+                ThresholdDirection::Rising,
+            ));
+        } else if old >= *threshold && pool.val < *threshold {
+            log(ResourceEventType::CrossedThreshold(
+                *threshold,
+                ThresholdDirection::Falling,
+            ));
+        }
+    }
+}
+
+/// Restarts the generators attached to the pools changed by a transaction.
+fn reset_generators<ID, Value>(
+    generators: &mut [(ID, Generator<Value>)],
+    pool: &ID,
+    transaction: &Transaction<Value, ID>,
+) where
+    ID: Clone + Ord + Debug,
+    Value: PoolValue,
+{
+    for (id, generator) in generators.iter_mut() {
+        let traded_with = matches!(transaction, Transaction::Trade(_, other) if other == id);
+        if id == pool || traded_with {
+            generator.reset();
+        }
+    }
+}
+
+/// Where a transaction recorded in a [Ledger] came from.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TransactionOrigin {
+    /// a reaction handled by the logic
+    Reaction,
+    /// one of the logic's [Generator]s
+    Generator,
+}
+
+/// A transaction that was applied to a resource logic.
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LedgerEntry<ID, Value>
+where
+    Value: PoolValue,
+    ID: Clone + Ord + Debug,
+{
+    /// how many times the logic had been updated when the transaction was applied
+    pub frame: usize,
+    pub origin: TransactionOrigin,
+    pub pool: ID,
+    pub transaction: Transaction<Value, ID>,
+    /// the values of every pool the transaction changed, from before it was applied
+    pub before: Vec<(ID, PoolValues<Value>)>,
+}
+
+/// A record of the transactions applied to a resource logic, for auditing a pool's history or undoing transactions. Only the newest `capacity` transactions are kept.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ledger<ID, Value>
+where
+    Value: PoolValue,
+    ID: Clone + Ord + Debug,
+{
+    pub entries: VecDeque<LedgerEntry<ID, Value>>,
+    /// how many entries to keep before forgetting the oldest ones
+    pub capacity: usize,
+    /// the current frame, i.e. how many times the logic has been updated since the ledger was enabled
+    pub frame: usize,
+}
+
+impl<ID, Value> Ledger<ID, Value>
+where
+    Value: PoolValue,
+    ID: Clone + Ord + Debug,
+{
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(capacity),
+            capacity,
+            frame: 0,
+        }
+    }
+
+    fn record(
+        &mut self,
+        origin: TransactionOrigin,
+        pool: &ID,
+        transaction: &Transaction<Value, ID>,
+        before: Vec<(ID, PoolValues<Value>)>,
+    ) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(LedgerEntry {
+            frame: self.frame,
+            origin,
+            pool: pool.clone(),
+            transaction: transaction.clone(),
+            before,
+        });
+    }
+
+    /// Every transaction that changed the given pool, oldest first.
+    pub fn history<'a>(&'a self, pool: &'a ID) -> impl Iterator<Item = &'a LedgerEntry<ID, Value>> {
+        self.entries
+            .iter()
+            .filter(move |entry| entry.before.iter().any(|(id, _)| id == pool))
+    }
+
+    /// Undoes the last `n` transactions (from `origin`, if given) by restoring the pools they changed, newest first. Returns the entries that were undone.
+    ///
+    /// If a pool was changed again by a transaction that isn't undone, the pool is restored and then the later transactions are applied to it again, so what they did is kept: a later change still changes it by the same amount, and a later set (or set max or min) still sets the same value. A later transaction that can't be applied to the restored pool is skipped.
+    fn rollback(
+        &mut self,
+        items: &mut BTreeMap<ID, PoolValues<Value>>,
+        n: usize,
+        origin: Option<TransactionOrigin>,
+    ) -> Vec<LedgerEntry<ID, Value>> {
+        let mut undone = Vec::new();
+        let mut idx = self.entries.len();
+        while undone.len() < n && idx > 0 {
+            idx -= 1;
+            if matches!(origin, Some(origin) if origin != self.entries[idx].origin) {
+                continue;
+            }
+            let entry = self.entries.remove(idx).unwrap();
+            for (id, before) in entry.before.iter() {
+                let pool = match items.get_mut(id) {
+                    Some(pool) => pool,
+                    None => continue,
+                };
+                let mut values = *before;
+                for later in self.entries.iter_mut().skip(idx) {
+                    let LedgerEntry {
+                        pool: from,
+                        transaction,
+                        before,
+                        ..
+                    } = later;
+                    if let Some((_, before)) = before.iter_mut().find(|(later_id, _)| later_id == id) {
+                        *before = values;
+                        values = replay(&values, id, from, transaction).unwrap_or(values);
+                    }
+                }
+                *pool = values;
+            }
+            undone.push(entry);
+        }
+        undone
+    }
+}
+
+/// Applies a transaction from the ledger to one of the pools it changed again. `from` is the pool the transaction was made on; for trades, `id` might be the pool on the receiving end.
+fn replay<ID, Value>(
+    values: &PoolValues<Value>,
+    id: &ID,
+    from: &ID,
+    transaction: &Transaction<Value, ID>,
+) -> Result<PoolValues<Value>, ResourceError>
+where
+    ID: Clone + Ord + Debug,
+    Value: PoolValue,
+{
+    match transaction {
+        Transaction::Trade(amt, _) if id != from => Ok(PoolValues {
+            val: values.changed_by(*amt, Overflow::Checked)?,
+            ..*values
+        }),
+        transaction => values.after(transaction),
+    }
+}
+
+/// Logs an event for each pool restored by undoing transactions.
+fn log_rollback<ID, Value>(
+    completed: &mut Vec<ResourceEvent<ID, Value>>,
+    undone: &[LedgerEntry<ID, Value>],
+) where
+    ID: Clone + Ord + Debug,
+    Value: PoolValue,
+{
+    for entry in undone.iter() {
+        for (id, _) in entry.before.iter() {
+            completed.push(ResourceEvent {
+                pool: id.clone(),
+                transaction: entry.transaction.clone(),
+                event_type: ResourceEventType::RolledBack,
+            });
+        }
+    }
 }
 
 /// A resource logic that queues transactions, then applies them all at once when updating.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct QueuedResources<ID, Value>
 where
     ID: Clone + Ord + Debug,
-    Value: Num + Signed + Copy + PartialOrd,
+    Value: PoolValue,
 {
     /// The items involved, and their values, as a tuple of (actual value, minimum value, maximum value).
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_map"))]
     pub items: BTreeMap<ID, PoolValues<Value>>,
     /// Each transaction is a list of items involved in the transaction and the amount they're being changed.
     pub transactions: Vec<(ID, Transaction<Value, ID>)>,
     /// A Vec of all transactions and if they were able to be completed or not. If not, also report an error (see [ResourceEvent] and [ResourceError]).
     pub completed: Vec<ResourceEvent<ID, Value>>,
+    /// Rules that change pools over time, see [Generator].
+    pub generators: Vec<(ID, Generator<Value>)>,
+    /// Values that produce a [ResourceEventType::CrossedThreshold] event when a pool's value passes them.
+    pub thresholds: Vec<(ID, Value)>,
+    /// A record of applied transactions, if enabled with `enable_ledger`.
+    pub ledger: Option<Ledger<ID, Value>>,
+}
+
+impl<ID, Value> Snapshot for QueuedResources<ID, Value>
+where
+    ID: Clone + Ord + Debug,
+    Value: PoolValue,
+{
+    snapshot_fields!(items, transactions, completed, generators, thresholds, ledger);
 }
 
 impl<ID, Value> Logic for QueuedResources<ID, Value>
 where
     ID: Clone + Ord + Debug,
-    Value: Num + Signed + Copy + PartialOrd,
+    Value: PoolValue,
 {
     type Event = ResourceEvent<ID, Value>;
     type Reaction = ResourceReaction<ID, Value>;
@@ -70,19 +685,27 @@ where
 impl<ID, Value> QueuedResources<ID, Value>
 where
     ID: Clone + Ord + Debug,
-    Value: Num + Signed + Copy + PartialOrd,
+    Value: PoolValue,
 {
     pub fn new() -> Self {
         Self {
             items: BTreeMap::new(),
             transactions: Vec::new(),
             completed: Vec::new(),
+            generators: Vec::new(),
+            thresholds: Vec::new(),
+            ledger: None,
         }
     }
 
     /// Updates the values of resources based on the queued transactions. If a transaction cannot be completed (if the value goes below its min or max), a snapshot of the resources before the transaction occurred is restored, and the transaction is marked as incomplete, and we continue to process the remaining transactions.
+    ///
+    /// Afterwards, any [Generator]s are run.
     pub fn update(&mut self) {
         self.completed.clear();
+        if let Some(ledger) = &mut self.ledger {
+            ledger.frame += 1;
+        }
 
         for (id, transaction) in self.transactions.iter() {
             let err_event = |pool, transaction, err| ResourceEvent {
@@ -91,85 +714,160 @@ where
                 event_type: ResourceEventType::TransactionUnsuccessful(err),
             };
             if let Transaction::Trade(amt, other) = transaction {
-                let zero: Value = num_traits::identities::zero();
-                // check if first transaction is possible
-                if let Err(err) = self.is_possible(id, &Transaction::Change(zero - *amt)) {
-                    self.completed
-                        .push(err_event(id.clone(), transaction.clone(), err));
-                    continue;
-                }
-                // check if second is possible
-                if let Err(err) = self.is_possible(other, &Transaction::Change(*amt)) {
-                    self.completed
-                        .push(err_event(id.clone(), transaction.clone(), err));
-                    continue;
+                let (new_i, new_j) = match trade(&self.items, id, other, *amt) {
+                    Ok(pools) => pools,
+                    Err(err) => {
+                        self.completed
+                            .push(err_event(id.clone(), transaction.clone(), err));
+                        continue;
+                    }
+                };
+                let (old_i, old_j) = (self.items[id], self.items[other]);
+                self.items.insert(id.clone(), new_i);
+                self.items.insert(other.clone(), new_j);
+                if let Some(ledger) = &mut self.ledger {
+                    let before = vec![(id.clone(), old_i), (other.clone(), old_j)];
+                    ledger.record(TransactionOrigin::Reaction, id, transaction, before);
                 }
-
-                let PoolValues { val: val_i, .. } = self.items.get_mut(id).unwrap();
-                *val_i = *val_i - *amt;
-                let PoolValues { val: val_j, .. } = self.items.get_mut(other).unwrap();
-                *val_j = *val_j + *amt;
                 self.completed.push(ResourceEvent {
                     pool: id.clone(),
                     transaction: transaction.clone(),
                     event_type: ResourceEventType::PoolUpdated,
                 });
+                log_boundaries(
+                    &mut self.completed,
+                    &self.thresholds,
+                    id,
+                    &new_i,
+                    old_i.val,
+                    transaction,
+                );
+                log_boundaries(
+                    &mut self.completed,
+                    &self.thresholds,
+                    other,
+                    &new_j,
+                    old_j.val,
+                    transaction,
+                );
                 continue;
             }
 
-            if let Err(err) = self.is_possible(id, transaction) {
-                self.completed
-                    .push(err_event(id.clone(), transaction.clone(), err));
-                continue;
-            }
-
-            let PoolValues { val, min, max } = self.items.get_mut(id).unwrap();
-            match transaction {
-                Transaction::Change(amt) => {
-                    *val = *val + *amt;
-                }
-                Transaction::Set(amt) => {
-                    *val = *val + *amt;
-                }
-                Transaction::SetMax(new_max) => {
-                    *max = *new_max;
-                }
-                Transaction::SetMin(new_min) => {
-                    *min = *new_min;
+            let new = match transact(&self.items, id, transaction) {
+                Ok(pool) => pool,
+                Err(err) => {
+                    self.completed
+                        .push(err_event(id.clone(), transaction.clone(), err));
+                    continue;
                 }
-                _ => {}
+            };
+            let old = self.items[id];
+            self.items.insert(id.clone(), new);
+            if let Some(ledger) = &mut self.ledger {
+                ledger.record(
+                    TransactionOrigin::Reaction,
+                    id,
+                    transaction,
+                    vec![(id.clone(), old)],
+                );
             }
             self.completed.push(ResourceEvent {
                 pool: id.clone(),
                 transaction: transaction.clone(),
                 event_type: ResourceEventType::PoolUpdated,
             });
+            log_boundaries(
+                &mut self.completed,
+                &self.thresholds,
+                id,
+                &new,
+                old.val,
+                transaction,
+            );
         }
         self.transactions.clear();
-    }
 
-    /// Checks if the transaction is possible or not
-    fn is_possible(
-        &self,
-        item_type: &ID,
-        transaction: &Transaction<Value, ID>,
-    ) -> Result<(), ResourceError> {
-        if let Some(PoolValues { val, min, max }) = self.items.get(item_type) {
-            match transaction {
-                Transaction::Change(amt) => {
-                    if *val + *amt > *max {
-                        Err(ResourceError::TooBig)
-                    } else if *val + *amt < *min {
-                        Err(ResourceError::TooSmall)
-                    } else {
-                        Ok(())
-                    }
-                }
-                _ => Ok(()),
+        for event in self.completed.iter() {
+            if event.event_type == ResourceEventType::PoolUpdated {
+                reset_generators(&mut self.generators, &event.pool, &event.transaction);
             }
-        } else {
-            Err(ResourceError::PoolNotFound)
         }
+        run_generators(
+            &mut self.items,
+            &mut self.generators,
+            &self.thresholds,
+            &mut self.ledger,
+            &mut self.completed,
+        );
+    }
+
+    /// Attaches a [Generator] to the given pool.
+    pub fn add_generator(&mut self, pool: ID, generator: Generator<Value>) {
+        self.generators.push((pool, generator));
+    }
+
+    /// Registers a threshold on the given pool. See [ResourceEventType::CrossedThreshold].
+    ///
+    /// ```
+    /// use asterism::resources::*;
+    /// use asterism::Logic;
+    ///
+    /// let mut resources: QueuedResources<&str, i16> = QueuedResources::new();
+    /// resources.items.insert("score", PoolValues::new(9, 0, 99));
+    /// resources.add_threshold("score", 10);
+    ///
+    /// resources.handle_predicate(&("score", Transaction::Change(1)));
+    /// resources.update();
+    /// let wins = ResourceEventType::CrossedThreshold(10, ThresholdDirection::Rising);
+    /// assert!(resources.events().iter().any(|e| e.pool == "score" && e.event_type == wins));
+    /// ```
+    pub fn add_threshold(&mut self, pool: ID, value: Value) {
+        self.thresholds.push((pool, value));
+    }
+
+    /// Starts recording applied transactions in a [Ledger], keeping the last `capacity` of them.
+    pub fn enable_ledger(&mut self, capacity: usize) {
+        if self.ledger.is_none() {
+            self.ledger = Some(Ledger::new(capacity));
+        }
+    }
+
+    /// Undoes the last `n` applied transactions, newest first, if the ledger is enabled. Each pool that's restored gets a [ResourceEventType::RolledBack] event. Returns the entries that were undone.
+    ///
+    /// ```
+    /// use asterism::resources::*;
+    /// let mut rsrc = QueuedResources::<usize, i16>::new();
+    /// rsrc.items.insert(0, PoolValues::new(5, 0, 10));
+    /// rsrc.items.insert(1, PoolValues::new(0, 0, 10));
+    /// rsrc.enable_ledger(100);
+    ///
+    /// rsrc.transactions.push((0, Transaction::Trade(3, 1)));
+    /// rsrc.update();
+    /// assert_eq!(rsrc.get_value_by_itemtype(&1), Some(3));
+    /// assert_eq!(rsrc.ledger.as_ref().unwrap().history(&1).count(), 1);
+    ///
+    /// let undone = rsrc.undo(1);
+    /// assert_eq!(undone[0].origin, TransactionOrigin::Reaction);
+    /// assert_eq!(rsrc.get_value_by_itemtype(&0), Some(5));
+    /// assert_eq!(rsrc.get_value_by_itemtype(&1), Some(0));
+    /// ```
+    pub fn undo(&mut self, n: usize) -> Vec<LedgerEntry<ID, Value>> {
+        let undone = match &mut self.ledger {
+            Some(ledger) => ledger.rollback(&mut self.items, n, None),
+            None => Vec::new(),
+        };
+        log_rollback(&mut self.completed, &undone);
+        undone
+    }
+
+    /// Like `undo`, but only undoes transactions from reactions. What [Generator]s did in the meantime is kept.
+    pub fn undo_reactions(&mut self, n: usize) -> Vec<LedgerEntry<ID, Value>> {
+        let undone = match &mut self.ledger {
+            Some(ledger) => ledger.rollback(&mut self.items, n, Some(TransactionOrigin::Reaction)),
+            None => Vec::new(),
+        };
+        log_rollback(&mut self.completed, &undone);
+        undone
     }
 
     /// Gets the value of the item based on its ID.
@@ -180,13 +878,15 @@ where
 
 /// A transaction holding the amount the value should change by.
 #[derive(Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Transaction<Value, Ident>
 where
-    Value: Num + Signed + Copy + PartialOrd,
+    Value: PoolValue,
     Ident: Ord + Debug,
 {
     Trade(Value, Ident),
     Change(Value),
+    /// Sets the pool's value. A value outside the pool's min and max is handled by its [Overflow] policy, the same way as a change that would go past them.
     Set(Value),
     SetMax(Value),
     SetMin(Value),
@@ -194,46 +894,66 @@ where
 
 /// Errors possible when trying to complete a transaction.
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ResourceError {
     PoolNotFound,
     TooBig,
     TooSmall,
+    /// the value type itself couldn't hold the result, ex. going past `i16::MAX`
+    Overflow,
 }
 
 pub type ResourceReaction<ID, Value> = (ID, Transaction<Value, ID>);
 
 #[derive(PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ResourceEvent<ID, Value>
 where
-    Value: Num + Signed + Copy + PartialOrd,
+    Value: PoolValue,
     ID: Clone + Ord + Debug,
 {
     pub pool: ID,
     pub transaction: Transaction<Value, ID>,
-    pub event_type: ResourceEventType,
+    pub event_type: ResourceEventType<Value>,
 }
 
 #[derive(Eq, PartialEq, Debug, Clone, Copy)]
-pub enum ResourceEventType {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ResourceEventType<Value> {
     PoolUpdated,
     TransactionUnsuccessful(ResourceError),
+    /// the pool's value became its maximum
+    ReachedMax,
+    /// the pool's value became its minimum
+    ReachedMin,
+    /// the pool was restored to an earlier value by undoing a transaction
+    RolledBack,
+    /// the pool's value passed a threshold registered with `add_threshold`. Rising means the value went from below the threshold to at or above it, falling means the opposite.
+    CrossedThreshold(Value, ThresholdDirection),
+}
+
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ThresholdDirection {
+    Rising,
+    Falling,
 }
 
-impl EventType for ResourceEventType {}
+impl<Value> EventType for ResourceEventType<Value> {}
 
 impl<ID, Value> Reaction for ResourceReaction<ID, Value>
 where
     ID: Ord + Clone + Debug,
-    Value: Num + Signed + Copy + PartialOrd,
+    Value: PoolValue,
 {
 }
 
 impl<ID, Value> Event for ResourceEvent<ID, Value>
 where
     ID: Ord + Clone + Debug,
-    Value: Num + Signed + Copy + PartialOrd,
+    Value: PoolValue,
 {
-    type EventType = ResourceEventType;
+    type EventType = ResourceEventType<Value>;
     fn get_type(&self) -> &Self::EventType {
         &self.event_type
     }
@@ -250,12 +970,10 @@ where
 impl<'iter, ID, Value> LendingIterator for RsrcDataIter<'iter, ID, Value>
 where
     ID: Clone + Ord + Debug,
-    Value: Num + Signed + Copy + PartialOrd,
+    Value: PoolValue,
 {
-    type Item<'a> = (
-        <QueuedResources<ID, Value> as Logic>::Ident,
-        <QueuedResources<ID, Value> as Logic>::IdentDataMut<'a>,
-    ) where Self: 'a;
+    // lent for as long as the logic is borrowed, like a regular iterator, so the pools' types don't need to outlive every lifetime (see LendingIterator's docs)
+    type Item<'a> = (ID, &'iter mut PoolValues<Value>);
 
     fn next(&mut self) -> Option<Self::Item<'_>> {
         self.resources.next().map(|(id, vals)| (id.clone(), vals))
@@ -263,68 +981,111 @@ where
 }
 
 /// An instant resource logic updates as it receives reactions and produces events immediately, rather than at the end of each event loop.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct InstantResources<ID, Value>
 where
     ID: Clone + Ord + Debug,
-    Value: Num + Signed + Copy + PartialOrd,
+    Value: PoolValue,
 {
     /// The items involved and their values.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_map"))]
     pub items: BTreeMap<ID, PoolValues<Value>>,
     /// A Vec of all transactions and if they were able to be completed or not. If not, also report an error (see [ResourceEvent] and [ResourceError]).
     pub completed: Vec<ResourceEvent<ID, Value>>,
+    /// Rules that change pools over time, see [Generator].
+    pub generators: Vec<(ID, Generator<Value>)>,
+    /// Values that produce a [ResourceEventType::CrossedThreshold] event when a pool's value passes them.
+    pub thresholds: Vec<(ID, Value)>,
+    /// A record of applied transactions, if enabled with `enable_ledger`.
+    pub ledger: Option<Ledger<ID, Value>>,
 }
 
 impl<ID, Value> InstantResources<ID, Value>
 where
     ID: Clone + Ord + Debug,
-    Value: Num + Signed + PartialOrd + Copy + PartialOrd,
+    Value: PoolValue,
 {
     pub fn new() -> Self {
         Self {
             items: BTreeMap::new(),
             completed: Vec::new(),
+            generators: Vec::new(),
+            thresholds: Vec::new(),
+            ledger: None,
         }
     }
 
-    /// update function-- clears events once per game loop
+    /// update function-- clears events once per game loop, then runs any [Generator]s
     pub fn update(&mut self) {
         self.completed.clear();
+        if let Some(ledger) = &mut self.ledger {
+            ledger.frame += 1;
+        }
+        run_generators(
+            &mut self.items,
+            &mut self.generators,
+            &self.thresholds,
+            &mut self.ledger,
+            &mut self.completed,
+        );
     }
 
-    /// Checks if the transaction is possible or not
-    fn is_possible(
-        &self,
-        item_type: &ID,
-        transaction: &Transaction<Value, ID>,
-    ) -> Result<(), ResourceError> {
-        if let Some(PoolValues { val, min, max }) = self.items.get(item_type) {
-            match transaction {
-                Transaction::Change(amt) => {
-                    if *val + *amt > *max {
-                        Err(ResourceError::TooBig)
-                    } else if *val + *amt < *min {
-                        Err(ResourceError::TooSmall)
-                    } else {
-                        Ok(())
-                    }
-                }
-                _ => Ok(()),
-            }
-        } else {
-            Err(ResourceError::PoolNotFound)
+    /// Attaches a [Generator] to the given pool.
+    pub fn add_generator(&mut self, pool: ID, generator: Generator<Value>) {
+        self.generators.push((pool, generator));
+    }
+
+    /// Registers a threshold on the given pool. See [ResourceEventType::CrossedThreshold].
+    pub fn add_threshold(&mut self, pool: ID, value: Value) {
+        self.thresholds.push((pool, value));
+    }
+
+    /// Starts recording applied transactions in a [Ledger], keeping the last `capacity` of them.
+    pub fn enable_ledger(&mut self, capacity: usize) {
+        if self.ledger.is_none() {
+            self.ledger = Some(Ledger::new(capacity));
         }
     }
 
+    /// Undoes the last `n` applied transactions, newest first, if the ledger is enabled. Each pool that's restored gets a [ResourceEventType::RolledBack] event. Returns the entries that were undone.
+    pub fn undo(&mut self, n: usize) -> Vec<LedgerEntry<ID, Value>> {
+        let undone = match &mut self.ledger {
+            Some(ledger) => ledger.rollback(&mut self.items, n, None),
+            None => Vec::new(),
+        };
+        log_rollback(&mut self.completed, &undone);
+        undone
+    }
+
+    /// Like `undo`, but only undoes transactions from reactions. What [Generator]s did in the meantime is kept.
+    pub fn undo_reactions(&mut self, n: usize) -> Vec<LedgerEntry<ID, Value>> {
+        let undone = match &mut self.ledger {
+            Some(ledger) => ledger.rollback(&mut self.items, n, Some(TransactionOrigin::Reaction)),
+            None => Vec::new(),
+        };
+        log_rollback(&mut self.completed, &undone);
+        undone
+    }
+
     /// Gets the value of the item based on its ID.
     pub fn get_value_by_itemtype(&self, item_type: &ID) -> Option<Value> {
         self.items.get(item_type).map(|PoolValues { val, .. }| *val)
     }
 }
 
+impl<ID, Value> Snapshot for InstantResources<ID, Value>
+where
+    ID: Clone + Ord + Debug,
+    Value: PoolValue,
+{
+    snapshot_fields!(items, completed, generators, thresholds, ledger);
+}
+
 impl<ID, Value> Logic for InstantResources<ID, Value>
 where
     ID: Clone + Ord + Debug,
-    Value: Num + Signed + Copy + PartialOrd,
+    Value: PoolValue,
 {
     type Event = ResourceEvent<ID, Value>;
     type Reaction = ResourceReaction<ID, Value>;
@@ -344,59 +1105,78 @@ where
         };
 
         if let Transaction::Trade(amt, to) = change {
-            let zero: Value = num_traits::identities::zero();
-            // check if first transaction is possible
-            if let Err(err) = self.is_possible(item_type, &Transaction::Change(zero - *amt)) {
-                self.completed
-                    .push(err_event(item_type.clone(), change.clone(), err));
-                return;
-            }
-            // check if second is possible
-            if let Err(err) = self.is_possible(to, &Transaction::Change(*amt)) {
-                self.completed
-                    .push(err_event(item_type.clone(), change.clone(), err));
-                return;
+            let (new_i, new_j) = match trade(&self.items, item_type, to, *amt) {
+                Ok(pools) => pools,
+                Err(err) => {
+                    self.completed
+                        .push(err_event(item_type.clone(), change.clone(), err));
+                    return;
+                }
+            };
+            let (old_i, old_j) = (self.items[item_type], self.items[to]);
+            self.items.insert(item_type.clone(), new_i);
+            self.items.insert(to.clone(), new_j);
+            if let Some(ledger) = &mut self.ledger {
+                let before = vec![(item_type.clone(), old_i), (to.clone(), old_j)];
+                ledger.record(TransactionOrigin::Reaction, item_type, change, before);
             }
-
-            let PoolValues { val: val_i, .. } = self.items.get_mut(item_type).unwrap();
-            *val_i = *val_i - *amt;
-            let PoolValues { val: val_j, .. } = self.items.get_mut(to).unwrap();
-            *val_j = *val_j + *amt;
+            reset_generators(&mut self.generators, item_type, change);
             self.completed.push(ResourceEvent {
                 pool: item_type.clone(),
                 transaction: change.clone(),
                 event_type: ResourceEventType::PoolUpdated,
             });
+            log_boundaries(
+                &mut self.completed,
+                &self.thresholds,
+                item_type,
+                &new_i,
+                old_i.val,
+                change,
+            );
+            log_boundaries(
+                &mut self.completed,
+                &self.thresholds,
+                to,
+                &new_j,
+                old_j.val,
+                change,
+            );
             return;
         }
 
-        if let Err(err) = self.is_possible(item_type, change) {
-            self.completed
-                .push(err_event(item_type.clone(), change.clone(), err));
-            return;
-        }
-
-        let PoolValues { val, min, max } = self.items.get_mut(item_type).unwrap();
-        match change {
-            Transaction::Change(amt) => {
-                *val = *val + *amt;
-            }
-            Transaction::Set(amt) => {
-                *val = *amt;
-            }
-            Transaction::SetMax(new_max) => {
-                *max = *new_max;
-            }
-            Transaction::SetMin(new_min) => {
-                *min = *new_min;
+        let new = match transact(&self.items, item_type, change) {
+            Ok(pool) => pool,
+            Err(err) => {
+                self.completed
+                    .push(err_event(item_type.clone(), change.clone(), err));
+                return;
             }
-            Transaction::Trade(_, _) => {}
+        };
+        let old = self.items[item_type];
+        self.items.insert(item_type.clone(), new);
+        if let Some(ledger) = &mut self.ledger {
+            ledger.record(
+                TransactionOrigin::Reaction,
+                item_type,
+                change,
+                vec![(item_type.clone(), old)],
+            );
         }
+        reset_generators(&mut self.generators, item_type, change);
         self.completed.push(ResourceEvent {
             pool: item_type.clone(),
             transaction: change.clone(),
             event_type: ResourceEventType::PoolUpdated,
         });
+        log_boundaries(
+            &mut self.completed,
+            &self.thresholds,
+            item_type,
+            &new,
+            old.val,
+            change,
+        );
     }
 
     // dislike this panic. is it reasonable to put an option on the type? oh ugh i don't like the way these tables work
@@ -433,12 +1213,10 @@ where
 impl<'iter, ID, Value> LendingIterator for InstRsrcDataIter<'iter, ID, Value>
 where
     ID: Clone + Ord + Debug,
-    Value: Num + Signed + Copy + PartialOrd,
+    Value: PoolValue,
 {
-    type Item<'a> = (
-        <InstantResources<ID, Value> as Logic>::Ident,
-        <InstantResources<ID, Value> as Logic>::IdentDataMut<'a>,
-    ) where Self: 'a;
+    // see RsrcDataIter
+    type Item<'a> = (ID, &'iter mut PoolValues<Value>);
 
     fn next(&mut self) -> Option<Self::Item<'_>> {
         self.resources.next().map(|(id, vals)| (id.clone(), vals))
@@ -0,0 +1,213 @@
+//! A fixed-point number type for resource pools, for fractional values (health bars, percentages) that should add up the same way every time, unlike floats.
+
+use num_traits::{Num, One, Signed, Zero};
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+
+use super::PoolValue;
+
+/// A signed fixed-point number with 16 integer bits and 16 fractional bits, so it can hold values between about ±32768 with a precision of 1/65536.
+///
+/// Multiplication rounds to the nearest representable value; division rounds toward zero.
+///
+/// ```
+/// use asterism::resources::{Fixed, Overflow, PoolValues, QueuedResources, Transaction};
+///
+/// let mut rsrc = QueuedResources::<&str, Fixed>::new();
+/// let (empty, full) = (Fixed::from_int(0), Fixed::from_int(1));
+/// rsrc.items.insert(
+///     "health",
+///     PoolValues::new(full, empty, full).with_overflow(Overflow::Saturating),
+/// );
+/// for _ in 0..10 {
+///     rsrc.transactions
+///         .push(("health", Transaction::Change(Fixed::from_f32(-0.1))));
+/// }
+/// rsrc.update();
+/// // 0.1 rounds up a tiny bit, so the last hit would go just past empty, but the pool stops at its min
+/// assert_eq!(rsrc.get_value_by_itemtype(&"health"), Some(empty));
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Fixed(i32);
+
+impl Fixed {
+    pub const FRAC_BITS: u32 = 16;
+    pub const MAX: Self = Self(i32::MAX);
+    pub const MIN: Self = Self(i32::MIN);
+    const ONE: i32 = 1 << Self::FRAC_BITS;
+
+    /// Makes a fixed-point number from its raw representation.
+    pub const fn from_bits(bits: i32) -> Self {
+        Self(bits)
+    }
+
+    /// The raw representation of the number.
+    pub const fn to_bits(self) -> i32 {
+        self.0
+    }
+
+    pub const fn from_int(n: i16) -> Self {
+        Self((n as i32) << Self::FRAC_BITS)
+    }
+
+    /// Rounds to the nearest representable value, saturating if `f` is out of range.
+    pub fn from_f32(f: f32) -> Self {
+        Self((f as f64 * Self::ONE as f64).round() as i32)
+    }
+
+    pub fn to_f32(self) -> f32 {
+        self.0 as f32 / Self::ONE as f32
+    }
+
+    /// Rounds down to the nearest integer.
+    pub fn floor(self) -> i16 {
+        (self.0 >> Self::FRAC_BITS) as i16
+    }
+
+    /// Rounds to the nearest integer, with halves rounding up.
+    pub fn round(self) -> i16 {
+        ((self.0 as i64 + (Self::ONE as i64 >> 1)) >> Self::FRAC_BITS) as i16
+    }
+}
+
+impl fmt::Debug for Fixed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Fixed({})", self.to_f32())
+    }
+}
+
+impl fmt::Display for Fixed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_f32())
+    }
+}
+
+impl Add for Fixed {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0 + rhs.0)
+    }
+}
+
+impl Sub for Fixed {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Self(self.0 - rhs.0)
+    }
+}
+
+impl Mul for Fixed {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        let product = self.0 as i64 * rhs.0 as i64 + (Self::ONE as i64 >> 1);
+        Self(
+            i32::try_from(product >> Self::FRAC_BITS)
+                .expect("fixed-point multiplication overflowed"),
+        )
+    }
+}
+
+impl Div for Fixed {
+    type Output = Self;
+    fn div(self, rhs: Self) -> Self {
+        let quotient = ((self.0 as i64) << Self::FRAC_BITS) / rhs.0 as i64;
+        Self(i32::try_from(quotient).expect("fixed-point division overflowed"))
+    }
+}
+
+impl Rem for Fixed {
+    type Output = Self;
+    fn rem(self, rhs: Self) -> Self {
+        Self(self.0 % rhs.0)
+    }
+}
+
+impl Neg for Fixed {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self(-self.0)
+    }
+}
+
+impl Zero for Fixed {
+    fn zero() -> Self {
+        Self(0)
+    }
+    fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl One for Fixed {
+    fn one() -> Self {
+        Self(Self::ONE)
+    }
+}
+
+impl Num for Fixed {
+    type FromStrRadixErr = String;
+
+    /// Only decimal numbers can have a fractional part; other radixes are parsed as integers.
+    fn from_str_radix(s: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+        if radix == 10 {
+            s.parse::<f32>()
+                .map(Self::from_f32)
+                .map_err(|e| format!("couldn't parse {} as a fixed-point number: {}", s, e))
+        } else {
+            i16::from_str_radix(s, radix)
+                .map(Self::from_int)
+                .map_err(|e| format!("couldn't parse {} as a fixed-point number: {}", s, e))
+        }
+    }
+}
+
+impl Signed for Fixed {
+    fn abs(&self) -> Self {
+        Self(self.0.abs())
+    }
+    fn abs_sub(&self, other: &Self) -> Self {
+        if self <= other {
+            Self::zero()
+        } else {
+            *self - *other
+        }
+    }
+    fn signum(&self) -> Self {
+        Self(self.0.signum() << Self::FRAC_BITS)
+    }
+    fn is_positive(&self) -> bool {
+        self.0 > 0
+    }
+    fn is_negative(&self) -> bool {
+        self.0 < 0
+    }
+}
+
+impl PoolValue for Fixed {
+    fn checked_add(self, rhs: Self) -> Option<Self> {
+        self.0.checked_add(rhs.0).map(Self)
+    }
+    fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.0.checked_sub(rhs.0).map(Self)
+    }
+    fn saturating_add(self, rhs: Self) -> Self {
+        Self(self.0.saturating_add(rhs.0))
+    }
+    fn saturating_sub(self, rhs: Self) -> Self {
+        Self(self.0.saturating_sub(rhs.0))
+    }
+    // wraps like a float, since it's fractional too
+    fn wrapped_add(self, rhs: Self, min: Self, max: Self) -> Self {
+        let (sum, min, max) = (self.0 as i64 + rhs.0 as i64, min.0 as i64, max.0 as i64);
+        let span = max - min;
+        if sum >= min && sum <= max {
+            Self(sum as i32)
+        } else if span <= 0 {
+            Self(if sum > max { max } else { min } as i32)
+        } else {
+            Self((min + (sum - min).rem_euclid(span)) as i32)
+        }
+    }
+}
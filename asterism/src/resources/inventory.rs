@@ -0,0 +1,489 @@
+//! # Inventory logics
+//!
+//! Where the resource logics in [super] count fungible resources, an inventory logic keeps track of individual items: each one has its own name, durability, and stack size, and lives in a container (a player's bag, a chest, a shop) that can only hold so many stacks.
+
+use crate::snapshot::{snapshot_fields, Snapshot};
+use crate::{Event, EventType, LendingIterator, Logic, Reaction};
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+
+/// Identifies a single item instance (or stack of items) in an [InventoryLogic].
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ItemID(usize);
+
+impl ItemID {
+    pub fn idx(&self) -> usize {
+        self.0
+    }
+}
+
+/// An item instance. Items of the same kind stack together if neither of them can wear out.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Item<Kind> {
+    pub kind: Kind,
+    pub name: String,
+    /// How many more times the item can be damaged before it breaks, if it can wear out.
+    pub durability: Option<u16>,
+    /// How many items are in this stack.
+    pub count: u16,
+    /// The most items that can be in one stack.
+    pub stack_limit: u16,
+}
+
+impl<Kind> Item<Kind> {
+    /// A single item that doesn't wear out and doesn't stack.
+    pub fn new(kind: Kind, name: impl Into<String>) -> Self {
+        Self {
+            kind,
+            name: name.into(),
+            durability: None,
+            count: 1,
+            stack_limit: 1,
+        }
+    }
+
+    pub fn with_durability(mut self, durability: u16) -> Self {
+        self.durability = Some(durability);
+        self
+    }
+
+    pub fn with_count(mut self, count: u16) -> Self {
+        self.count = count;
+        self
+    }
+
+    pub fn with_stack_limit(mut self, stack_limit: u16) -> Self {
+        self.stack_limit = stack_limit;
+        self
+    }
+}
+
+impl<Kind: PartialEq> Item<Kind> {
+    /// How many more of `other` can be stacked onto this item.
+    fn room_for(&self, other: &Self) -> u16 {
+        if self.kind == other.kind
+            && self.name == other.name
+            && self.durability.is_none()
+            && other.durability.is_none()
+        {
+            self.stack_limit.saturating_sub(self.count)
+        } else {
+            0
+        }
+    }
+}
+
+/// Holds up to `capacity` stacks of items.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Container {
+    pub capacity: usize,
+    pub items: Vec<ItemID>,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum InventoryReaction<ContainerID, Kind> {
+    /// creates a new item in a container
+    Add(ContainerID, Item<Kind>),
+    /// moves a whole stack to another container, stacking it onto matching items there first
+    Move(ItemID, ContainerID),
+    /// destroys an item
+    Remove(ItemID),
+    /// wears down an item's durability, destroying it once it runs out
+    Damage(ItemID, u16),
+}
+
+impl<ContainerID, Kind> Reaction for InventoryReaction<ContainerID, Kind> {}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum InventoryError {
+    ItemNotFound,
+    ContainerNotFound,
+    /// the container doesn't have room for another stack
+    ContainerFull,
+    /// the item's count is 0, so there's nothing to put anywhere
+    Empty,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum InventoryEventType<ContainerID> {
+    Added,
+    /// moved from one container to another
+    Moved(ContainerID, ContainerID),
+    /// some or all of the item was stacked onto another item
+    Stacked(ItemID),
+    Removed,
+    Damaged,
+    /// the item's durability ran out and it was removed
+    Broke,
+    Unsuccessful(InventoryError),
+}
+
+impl<ContainerID> EventType for InventoryEventType<ContainerID> {}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InventoryEvent<ContainerID, Kind> {
+    pub item: ItemID,
+    /// the kind of the item, so events can still be matched on after the item is removed
+    pub kind: Kind,
+    pub event_type: InventoryEventType<ContainerID>,
+}
+
+impl<ContainerID, Kind> Event for InventoryEvent<ContainerID, Kind> {
+    type EventType = InventoryEventType<ContainerID>;
+    fn get_type(&self) -> &Self::EventType {
+        &self.event_type
+    }
+}
+
+/// An inventory logic that queues reactions, then applies them all at once when updating.
+///
+/// ```
+/// use asterism::resources::inventory::*;
+/// use asterism::Logic;
+///
+/// let mut inv = InventoryLogic::<&str, &str>::new();
+/// inv.add_container("bag", 2);
+/// inv.add_container("chest", 10);
+/// let sword = inv.add_item("bag", Item::new("sword", "Rusty Sword").with_durability(2)).unwrap();
+/// let arrows = inv.add_item("chest", Item::new("arrow", "Arrow").with_count(5).with_stack_limit(20)).unwrap();
+/// inv.add_item("bag", Item::new("arrow", "Arrow").with_count(10).with_stack_limit(20)).unwrap();
+///
+/// // the arrows get stacked onto the ones already in the bag, so they fit
+/// inv.handle_predicate(&InventoryReaction::Move(arrows, "bag"));
+/// inv.handle_predicate(&InventoryReaction::Damage(sword, 2));
+/// inv.update();
+///
+/// assert!(inv.events().iter().any(|e| e.event_type == InventoryEventType::Broke));
+/// assert_eq!(inv.count_kind(&"bag", &"arrow"), 15);
+/// assert_eq!(inv.containers[&"bag"].items.len(), 1);
+/// ```
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InventoryLogic<ContainerID, Kind>
+where
+    ContainerID: Clone + Ord + Debug,
+    Kind: Clone + PartialEq,
+{
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_map"))]
+    pub items: BTreeMap<ItemID, Item<Kind>>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_map"))]
+    pub containers: BTreeMap<ContainerID, Container>,
+    /// Reactions to apply on the next update.
+    pub reactions: Vec<InventoryReaction<ContainerID, Kind>>,
+    pub completed: Vec<InventoryEvent<ContainerID, Kind>>,
+    next_id: usize,
+}
+
+impl<ContainerID, Kind> InventoryLogic<ContainerID, Kind>
+where
+    ContainerID: Clone + Ord + Debug,
+    Kind: Clone + PartialEq,
+{
+    pub fn new() -> Self {
+        Self {
+            items: BTreeMap::new(),
+            containers: BTreeMap::new(),
+            reactions: Vec::new(),
+            completed: Vec::new(),
+            next_id: 0,
+        }
+    }
+
+    pub fn add_container(&mut self, id: ContainerID, capacity: usize) {
+        self.containers.insert(
+            id,
+            Container {
+                capacity,
+                items: Vec::new(),
+            },
+        );
+    }
+
+    fn new_id(&mut self) -> ItemID {
+        let id = ItemID(self.next_id);
+        self.next_id += 1;
+        id
+    }
+
+    /// Puts an item in a container immediately, stacking it onto matching items first. Returns the item's ID. If the whole item was stacked onto other items, the ID no longer refers to anything. If there's more than fits in one stack, the rest go in new stacks with their own IDs.
+    ///
+    /// If the item doesn't fit or its count is 0, the ID it would've had comes back with the error.
+    pub fn add_item(
+        &mut self,
+        container: ContainerID,
+        item: Item<Kind>,
+    ) -> Result<ItemID, (ItemID, InventoryError)> {
+        let id = self.new_id();
+        self.items.insert(id, item);
+        match self.place(id, &container) {
+            Ok(()) => Ok(id),
+            Err(err) => {
+                self.items.remove(&id);
+                Err((id, err))
+            }
+        }
+    }
+
+    /// The container holding the item.
+    pub fn container_of(&self, item: ItemID) -> Option<&ContainerID> {
+        self.containers
+            .iter()
+            .find(|(_, container)| container.items.contains(&item))
+            .map(|(id, _)| id)
+    }
+
+    /// How many items of the given kind are in the container, across all stacks.
+    pub fn count_kind(&self, container: &ContainerID, kind: &Kind) -> u16 {
+        self.containers
+            .get(container)
+            .map(|container| {
+                container
+                    .items
+                    .iter()
+                    .filter_map(|id| self.items.get(id))
+                    .filter(|item| item.kind == *kind)
+                    .map(|item| item.count)
+                    .sum()
+            })
+            .unwrap_or(0)
+    }
+
+    /// Stacks the item onto matching items in the container, then puts whatever is left in as many slots as it needs. Nothing changes if the leftovers don't fit.
+    fn place(&mut self, id: ItemID, to: &ContainerID) -> Result<(), InventoryError> {
+        let item = self.items.get(&id).ok_or(InventoryError::ItemNotFound)?;
+        if item.count == 0 {
+            return Err(InventoryError::Empty);
+        }
+        let container = self
+            .containers
+            .get(to)
+            .ok_or(InventoryError::ContainerNotFound)?;
+
+        let room: u16 = container
+            .items
+            .iter()
+            .filter(|other| **other != id)
+            .filter_map(|other| self.items.get(other))
+            .map(|other| other.room_for(item))
+            .fold(0, |sum, room| sum.saturating_add(room));
+        let limit = item.stack_limit.max(1);
+        let stacks_needed = match item.count.saturating_sub(room) {
+            0 => 0,
+            left => (left as usize - 1) / limit as usize + 1,
+        };
+        let free = container.capacity.saturating_sub(container.items.len());
+        if stacks_needed > free {
+            return Err(InventoryError::ContainerFull);
+        }
+
+        let kind = item.kind.clone();
+        let mut left = item.count;
+        let stacks = container.items.clone();
+        for other in stacks.into_iter().filter(|other| *other != id) {
+            if left == 0 {
+                break;
+            }
+            let moved = left.min(self.items[&other].room_for(&self.items[&id]));
+            if moved > 0 {
+                self.items.get_mut(&other).unwrap().count += moved;
+                left -= moved;
+                self.completed.push(InventoryEvent {
+                    item: id,
+                    kind: kind.clone(),
+                    event_type: InventoryEventType::Stacked(other),
+                });
+            }
+        }
+
+        if left == 0 {
+            self.items.remove(&id);
+            return Ok(());
+        }
+        let first = left.min(limit);
+        self.items.get_mut(&id).unwrap().count = first;
+        self.containers.get_mut(to).unwrap().items.push(id);
+        left -= first;
+        // anything past the stack limit gets split off into new stacks
+        while left > 0 {
+            let extra = self.new_id();
+            let mut split = self.items[&id].clone();
+            split.count = left.min(limit);
+            left -= split.count;
+            self.items.insert(extra, split);
+            self.containers.get_mut(to).unwrap().items.push(extra);
+        }
+        Ok(())
+    }
+
+    fn take_out(&mut self, id: ItemID) {
+        for container in self.containers.values_mut() {
+            container.items.retain(|item| *item != id);
+        }
+    }
+
+    fn apply(
+        &mut self,
+        reaction: &InventoryReaction<ContainerID, Kind>,
+    ) -> Result<(ItemID, Kind, InventoryEventType<ContainerID>), (ItemID, InventoryError)> {
+        match reaction {
+            InventoryReaction::Add(container, item) => self
+                .add_item(container.clone(), item.clone())
+                .map(|id| (id, item.kind.clone(), InventoryEventType::Added)),
+            InventoryReaction::Move(id, to) => {
+                let from = self
+                    .container_of(*id)
+                    .cloned()
+                    .ok_or((*id, InventoryError::ItemNotFound))?;
+                let kind = self.items[id].kind.clone();
+                let idx = self.containers[&from]
+                    .items
+                    .iter()
+                    .position(|item| item == id)
+                    .unwrap();
+                self.take_out(*id);
+                if let Err(err) = self.place(*id, to) {
+                    // put it back where it was so the container's order doesn't change
+                    self.containers
+                        .get_mut(&from)
+                        .unwrap()
+                        .items
+                        .insert(idx, *id);
+                    return Err((*id, err));
+                }
+                Ok((*id, kind, InventoryEventType::Moved(from, to.clone())))
+            }
+            InventoryReaction::Remove(id) => {
+                let item = self
+                    .items
+                    .remove(id)
+                    .ok_or((*id, InventoryError::ItemNotFound))?;
+                self.take_out(*id);
+                Ok((*id, item.kind, InventoryEventType::Removed))
+            }
+            InventoryReaction::Damage(id, amt) => {
+                let item = self
+                    .items
+                    .get_mut(id)
+                    .ok_or((*id, InventoryError::ItemNotFound))?;
+                let kind = item.kind.clone();
+                match &mut item.durability {
+                    Some(durability) if *durability <= *amt => {
+                        self.items.remove(id);
+                        self.take_out(*id);
+                        Ok((*id, kind, InventoryEventType::Broke))
+                    }
+                    Some(durability) => {
+                        *durability -= *amt;
+                        Ok((*id, kind, InventoryEventType::Damaged))
+                    }
+                    None => Ok((*id, kind, InventoryEventType::Damaged)),
+                }
+            }
+        }
+    }
+
+    /// Applies the queued reactions in order. A reaction that can't be completed produces an [InventoryEventType::Unsuccessful] event and changes nothing.
+    pub fn update(&mut self) {
+        self.completed.clear();
+        let reactions = std::mem::take(&mut self.reactions);
+        for reaction in reactions.iter() {
+            match self.apply(reaction) {
+                Ok((item, kind, event_type)) => self.completed.push(InventoryEvent {
+                    item,
+                    kind,
+                    event_type,
+                }),
+                Err((item, err)) => {
+                    let kind = match reaction {
+                        InventoryReaction::Add(_, new) => Some(new.kind.clone()),
+                        _ => self.items.get(&item).map(|item| item.kind.clone()),
+                    };
+                    // a missing item has no kind to report, so there's nothing to match the event against
+                    if let Some(kind) = kind {
+                        self.completed.push(InventoryEvent {
+                            item,
+                            kind,
+                            event_type: InventoryEventType::Unsuccessful(err),
+                        });
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<ContainerID, Kind> Snapshot for InventoryLogic<ContainerID, Kind>
+where
+    ContainerID: Clone + Ord + Debug,
+    Kind: Clone + PartialEq,
+{
+    snapshot_fields!(items, containers, reactions, completed, next_id);
+}
+
+impl<ContainerID, Kind> Logic for InventoryLogic<ContainerID, Kind>
+where
+    ContainerID: Clone + Ord + Debug,
+    Kind: Clone + PartialEq,
+{
+    type Event = InventoryEvent<ContainerID, Kind>;
+    type Reaction = InventoryReaction<ContainerID, Kind>;
+
+    type Ident = ItemID;
+    type IdentData<'a> = &'a Item<Kind> where Self: 'a;
+    type IdentDataMut<'a> = &'a mut Item<Kind> where Self: 'a;
+
+    type DataIter<'a> = InventoryDataIter<'a, Kind> where Self: 'a;
+
+    /// queues the reaction to be applied on the next update
+    fn handle_predicate(&mut self, reaction: &Self::Reaction) {
+        self.reactions.push(reaction.clone());
+    }
+
+    fn get_ident_data(&self, ident: Self::Ident) -> Self::IdentData<'_> {
+        self.items.get(&ident).unwrap_or_else(|| {
+            panic!(
+                "requested item {:?} doesn't exist in inventory logic",
+                ident
+            )
+        })
+    }
+
+    fn get_ident_data_mut(&mut self, ident: Self::Ident) -> Self::IdentDataMut<'_> {
+        self.items.get_mut(&ident).unwrap_or_else(|| {
+            panic!(
+                "requested item {:?} doesn't exist in inventory logic",
+                ident
+            )
+        })
+    }
+
+    fn data_iter(&mut self) -> Self::DataIter<'_> {
+        InventoryDataIter {
+            items: self.items.iter_mut(),
+        }
+    }
+
+    fn events(&self) -> &[Self::Event] {
+        &self.completed
+    }
+}
+
+pub struct InventoryDataIter<'iter, Kind> {
+    items: std::collections::btree_map::IterMut<'iter, ItemID, Item<Kind>>,
+}
+
+impl<'iter, Kind> LendingIterator for InventoryDataIter<'iter, Kind> {
+    // lent for as long as the logic is borrowed, see RsrcDataIter
+    type Item<'a> = (ItemID, &'iter mut Item<Kind>);
+
+    fn next(&mut self) -> Option<Self::Item<'_>> {
+        self.items.next().map(|(id, item)| (*id, item))
+    }
+}
@@ -0,0 +1,198 @@
+//! # Scheduling logics
+//!
+//! Engines mostly do the same thing every frame: update each logic in some order, then check its events against what the game cares about and react to them. A [Scheduler] holds onto that order, along with bindings from one logic's events to another logic's reactions, so an engine can be put together by configuring one instead of writing its own loop.
+//!
+//! The logics themselves stay in the engine's own struct (`C` below, ex. a `Logics` or `Game` struct), so they can still be saved, snapshotted, and used directly. The scheduler only needs to know how to find each one.
+
+use std::marker::PhantomData;
+
+use crate::Logic;
+
+/// A handle to one of the logics in `C` that remembers what type of logic it is and how to find it.
+pub struct LogicID<C, L> {
+    idx: usize,
+    get: fn(&C) -> &L,
+    get_mut: fn(&mut C) -> &mut L,
+    _logic: PhantomData<fn() -> L>,
+}
+
+impl<C, L> LogicID<C, L> {
+    /// where the logic is in the update order
+    pub fn idx(&self) -> usize {
+        self.idx
+    }
+
+    pub fn get<'a>(&self, logics: &'a C) -> &'a L {
+        (self.get)(logics)
+    }
+
+    pub fn get_mut<'a>(&self, logics: &'a mut C) -> &'a mut L {
+        (self.get_mut)(logics)
+    }
+}
+
+impl<C, L> Clone for LogicID<C, L> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<C, L> Copy for LogicID<C, L> {}
+
+impl<C, L> PartialEq for LogicID<C, L> {
+    fn eq(&self, other: &Self) -> bool {
+        self.idx == other.idx
+    }
+}
+impl<C, L> Eq for LogicID<C, L> {}
+
+impl<C, L> std::fmt::Debug for LogicID<C, L> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "LogicID({})", self.idx)
+    }
+}
+
+/// Runs with all of the logics, when the logic it's attached to updates.
+type Step<C> = Box<dyn FnMut(&mut C)>;
+
+/// Updates logics in the order they were added. Right after each one updates, its bindings are checked against its new events.
+///
+/// ```
+/// use asterism::physics::{PhysicsEventType, PointPhysics};
+/// use asterism::resources::{PoolValues, QueuedResources, Transaction};
+/// use asterism::scheduler::Scheduler;
+/// use asterism::Event;
+/// use macroquad::math::Vec2;
+///
+/// struct Logics {
+///     physics: PointPhysics,
+///     resources: QueuedResources<&'static str, i32>,
+/// }
+///
+/// let mut logics = Logics {
+///     physics: PointPhysics::new(),
+///     resources: QueuedResources::new(),
+/// };
+/// logics.physics.add_physics_entity(Vec2::ZERO, Vec2::ONE, Vec2::ZERO);
+/// logics.resources.items.insert("distance", PoolValues::new(0, 0, 100));
+///
+/// let mut scheduler = Scheduler::new();
+/// let physics = scheduler.add_logic(
+///     "physics",
+///     |logics: &Logics| &logics.physics,
+///     |logics| &mut logics.physics,
+///     |logics| logics.physics.update(),
+/// );
+/// let resources = scheduler.add_logic(
+///     "resources",
+///     |logics: &Logics| &logics.resources,
+///     |logics| &mut logics.resources,
+///     |logics| logics.resources.update(),
+/// );
+///
+/// // every time something moves, it's traveled a little further
+/// scheduler.bind(physics, resources, |event| {
+///     (*event.get_type() == PhysicsEventType::PosChange)
+///         .then(|| ("distance", Transaction::Change(1)))
+/// });
+///
+/// for _ in 0..3 {
+///     scheduler.update(&mut logics);
+/// }
+/// // physics' bindings run before resources update, so each move is counted the same frame
+/// assert_eq!(logics.resources.get_value_by_itemtype(&"distance"), Some(3));
+/// assert_eq!(logics.physics.positions[0], Vec2::splat(3.0));
+/// ```
+pub struct Scheduler<C> {
+    /// each logic's name and how it's updated, in update order
+    logics: Vec<(String, Step<C>)>,
+    /// each binding and the logic whose update it runs after
+    bindings: Vec<(usize, Step<C>)>,
+}
+
+impl<C: 'static> Scheduler<C> {
+    pub fn new() -> Self {
+        Self {
+            logics: Vec::new(),
+            bindings: Vec::new(),
+        }
+    }
+
+    /// Adds a logic after the ones already added. `get` and `get_mut` find it in `C`, and `update` is called every frame with all of `C`, so it can do what the engine needs around the logic's own update, ex. syncing positions from one logic to another afterwards.
+    pub fn add_logic<L: Logic + 'static>(
+        &mut self,
+        name: impl Into<String>,
+        get: fn(&C) -> &L,
+        get_mut: fn(&mut C) -> &mut L,
+        update: impl FnMut(&mut C) + 'static,
+    ) -> LogicID<C, L> {
+        self.logics.push((name.into(), Box::new(update)));
+        LogicID {
+            idx: self.logics.len() - 1,
+            get,
+            get_mut,
+            _logic: PhantomData,
+        }
+    }
+
+    /// Every logic's name, in update order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.logics.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// After `from` updates, each of its events that `reaction` returns something for is handled by `to`. `from` and `to` can be the same logic.
+    pub fn bind<A, B>(
+        &mut self,
+        from: LogicID<C, A>,
+        to: LogicID<C, B>,
+        mut reaction: impl FnMut(&A::Event) -> Option<B::Reaction> + 'static,
+    ) where
+        A: Logic + 'static,
+        B: Logic + 'static,
+    {
+        self.after(from, move |logics: &mut C| {
+            let reactions: Vec<_> = from
+                .get(logics)
+                .events()
+                .iter()
+                .filter_map(&mut reaction)
+                .collect();
+            let to = to.get_mut(logics);
+            for reaction in reactions.iter() {
+                to.handle_predicate(reaction);
+            }
+        });
+    }
+
+    /// After `from` updates, calls `handler` with each of its events and all of the logics, for reactions that touch more than one logic or anything outside of them.
+    pub fn on_event<A: Logic + 'static>(
+        &mut self,
+        from: LogicID<C, A>,
+        mut handler: impl FnMut(&A::Event, &mut C) + 'static,
+    ) {
+        self.after(from, move |logics: &mut C| {
+            let events = from.get(logics).events().to_vec();
+            for event in events.iter() {
+                handler(event, logics);
+            }
+        });
+    }
+
+    /// After `from` updates, calls `handler` with all of the logics once, ex. to check a [PredicateTable](crate::predicates::PredicateTable) against its events.
+    pub fn after<A>(&mut self, from: LogicID<C, A>, handler: impl FnMut(&mut C) + 'static) {
+        self.bindings.push((from.idx, Box::new(handler)));
+    }
+
+    /// Runs one frame: updates every logic in order, running each one's bindings right after it updates.
+    pub fn update(&mut self, logics: &mut C) {
+        let Self {
+            logics: steps,
+            bindings,
+        } = self;
+        for (idx, (_, update)) in steps.iter_mut().enumerate() {
+            update(logics);
+            for (_, binding) in bindings.iter_mut().filter(|(from, _)| *from == idx) {
+                binding(logics);
+            }
+        }
+    }
+}
@@ -0,0 +1,75 @@
+//! Saves macroquad's vectors as arrays, ex. `[1.0, 2.0]`, so saves don't depend on which version of glam macroquad happens to use. Used with `#[serde(with = "asterism::serde_math")]` on vectors, lists of vectors, and lists of (id, vector) pairs.
+//!
+//! ```
+//! use macroquad::math::IVec2;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Room {
+//!     #[serde(with = "asterism::serde_math")]
+//!     chars: Vec<(usize, IVec2)>,
+//! }
+//! ```
+
+use macroquad::math::{IVec2, Vec2};
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
+
+/// Something that can be saved as plain arrays instead of glam's types.
+pub trait AsArray: Sized {
+    type Array: Serialize + DeserializeOwned;
+
+    fn to_array(&self) -> Self::Array;
+    fn from_array(array: Self::Array) -> Self;
+}
+
+impl AsArray for Vec2 {
+    type Array = [f32; 2];
+
+    fn to_array(&self) -> Self::Array {
+        Vec2::to_array(self)
+    }
+    fn from_array(array: Self::Array) -> Self {
+        Vec2::from(array)
+    }
+}
+
+impl AsArray for IVec2 {
+    type Array = [i32; 2];
+
+    fn to_array(&self) -> Self::Array {
+        IVec2::to_array(self)
+    }
+    fn from_array(array: Self::Array) -> Self {
+        IVec2::from(array)
+    }
+}
+
+impl<T: AsArray> AsArray for Vec<T> {
+    type Array = Vec<T::Array>;
+
+    fn to_array(&self) -> Self::Array {
+        self.iter().map(T::to_array).collect()
+    }
+    fn from_array(array: Self::Array) -> Self {
+        array.into_iter().map(T::from_array).collect()
+    }
+}
+
+impl<ID: Clone + Serialize + DeserializeOwned, T: AsArray> AsArray for (ID, T) {
+    type Array = (ID, T::Array);
+
+    fn to_array(&self) -> Self::Array {
+        (self.0.clone(), self.1.to_array())
+    }
+    fn from_array(array: Self::Array) -> Self {
+        (array.0, T::from_array(array.1))
+    }
+}
+
+pub fn serialize<T: AsArray, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    value.to_array().serialize(serializer)
+}
+
+pub fn deserialize<'de, T: AsArray, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+    T::Array::deserialize(deserializer).map(T::from_array)
+}
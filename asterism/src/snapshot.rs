@@ -0,0 +1,132 @@
+//! # Snapshots
+//!
+//! Saving what a logic looks like on a frame and putting it back later, for rewinding time or rolling back to a frame when a networked player's inputs arrive late. Snapshots are kept in memory and copied field by field, so they're cheap enough to take every frame.
+
+use std::collections::VecDeque;
+
+/// A logic whose state can be copied out and put back.
+///
+/// Logics' configuration (like which keys are bound) is part of their state too. Input wrappers aren't, since they're tracking the actual keyboard.
+pub trait Snapshot {
+    type State;
+
+    fn snapshot(&self) -> Self::State;
+
+    /// Saves the logic over an old snapshot, so its memory can be reused.
+    fn snapshot_into(&self, state: &mut Self::State) {
+        *state = self.snapshot();
+    }
+
+    /// Puts the logic back the way it was when the snapshot was taken.
+    fn restore(&mut self, state: &Self::State);
+}
+
+/// Implements [Snapshot] for a logic that's `Clone` by copying each of the given fields in place. Every field that changes while the game runs has to be listed.
+macro_rules! snapshot_fields {
+    ($($field:ident),* $(,)?) => {
+        type State = Self;
+
+        fn snapshot(&self) -> Self {
+            self.clone()
+        }
+
+        fn snapshot_into(&self, state: &mut Self) {
+            $(state.$field.clone_from(&self.$field);)*
+        }
+
+        fn restore(&mut self, state: &Self) {
+            $(self.$field.clone_from(&state.$field);)*
+        }
+    };
+}
+pub(crate) use snapshot_fields;
+
+/// The last few snapshots of a logic, oldest first. Once it's full, recording a new snapshot writes over the oldest one.
+///
+/// ```
+/// use asterism::physics::PointPhysics;
+/// use asterism::snapshot::History;
+/// use macroquad::math::Vec2;
+///
+/// let mut physics = PointPhysics::new();
+/// physics.add_physics_entity(Vec2::ZERO, Vec2::ONE, Vec2::ZERO);
+///
+/// let mut history = History::new(3);
+/// for _ in 0..5 {
+///     physics.update();
+///     history.record(&physics);
+/// }
+/// // only the last three frames are kept
+/// assert_eq!(history.len(), 3);
+/// assert!(!history.rewind(&mut physics, 3));
+///
+/// assert!(history.rewind(&mut physics, 2));
+/// assert_eq!(physics.positions[0], Vec2::splat(3.0));
+/// // the frames after the one rewound to are forgotten
+/// assert_eq!(history.len(), 1);
+/// ```
+pub struct History<S> {
+    snapshots: VecDeque<S>,
+    capacity: usize,
+}
+
+impl<S> History<S> {
+    /// Keeps up to `capacity` snapshots. A history with a capacity of 0 doesn't record anything.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            snapshots: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.snapshots.clear();
+    }
+
+    /// Takes a snapshot of the logic.
+    pub fn record<L: Snapshot<State = S>>(&mut self, logic: &L) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.snapshots.len() == self.capacity {
+            let mut oldest = self.snapshots.pop_front().unwrap();
+            logic.snapshot_into(&mut oldest);
+            self.snapshots.push_back(oldest);
+        } else {
+            self.snapshots.push_back(logic.snapshot());
+        }
+    }
+
+    /// The snapshot recorded `frames_ago` recordings before the latest one, so 0 is the latest.
+    pub fn get(&self, frames_ago: usize) -> Option<&S> {
+        let len = self.snapshots.len();
+        if frames_ago >= len {
+            None
+        } else {
+            self.snapshots.get(len - 1 - frames_ago)
+        }
+    }
+
+    /// Restores the logic to the snapshot `frames_ago` recordings before the latest one, and forgets everything recorded after it. Returns false, without changing anything, if the history doesn't go back that far.
+    pub fn rewind<L: Snapshot<State = S>>(&mut self, logic: &mut L, frames_ago: usize) -> bool {
+        let len = self.snapshots.len();
+        if frames_ago >= len {
+            return false;
+        }
+        self.snapshots.truncate(len - frames_ago);
+        logic.restore(self.snapshots.back().unwrap());
+        true
+    }
+}
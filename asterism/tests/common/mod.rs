@@ -0,0 +1,6 @@
+//! Helpers shared by the integration tests.
+
+/// The same input wrapper and key the control doctests use, where the input helper is the keys that are down this frame.
+// each test only uses some of these
+#[allow(unused_imports)]
+pub use asterism::control::testing::{Key, TestWrapper};
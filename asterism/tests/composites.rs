@@ -0,0 +1,109 @@
+//! Sequences should only fire when their presses are close enough together, and buffered presses should last until they expire or are used up.
+
+mod common;
+
+use asterism::control::{Composite, ControlEventType, ControlReaction, KeyboardControl};
+use asterism::Logic;
+use common::TestWrapper;
+
+type Control = KeyboardControl<&'static str, TestWrapper>;
+
+/// Presses each of the keys for a frame, with a frame of nothing after each one, returning the frames `id` was pressed on.
+fn tap(control: &mut Control, keys: &[char], gap: usize, id: &str) -> Vec<usize> {
+    let mut pressed = Vec::new();
+    let mut frame = 0;
+    for key in keys.iter() {
+        control.update(&vec![*key]);
+        if has(control, id, ControlEventType::KeyPressed) {
+            pressed.push(frame);
+        }
+        frame += 1;
+        for _ in 0..gap {
+            control.update(&vec![]);
+            frame += 1;
+        }
+    }
+    pressed
+}
+
+fn has(control: &Control, id: &str, event_type: ControlEventType) -> bool {
+    control
+        .events()
+        .iter()
+        .any(|e| e.action_id == id && e.event_type == event_type)
+}
+
+fn fighter() -> Control {
+    let mut control = Control::new();
+    control.add_key_map(0, 's', "down", true);
+    control.add_key_map(0, ' ', "serve", true);
+    control.add_key_map(0, 'w', "up", true);
+    control.add_composite(
+        0,
+        "special",
+        Composite::Sequence(vec!["down", "down", "serve"], 3),
+    );
+    control
+}
+
+#[test]
+fn sequence_in_window() {
+    let mut control = fighter();
+    // down, down, serve, each two frames after the one before
+    assert_eq!(tap(&mut control, &['s', 's', ' '], 1, "special"), vec![4]);
+}
+
+#[test]
+fn sequence_out_of_window() {
+    let mut control = fighter();
+    // the serve comes five frames after the second down
+    assert!(tap(&mut control, &['s', 's', ' '], 4, "special").is_empty());
+
+    // another action pressed in between breaks it up, too
+    let mut control = fighter();
+    for keys in [vec!['s'], vec![], vec!['s'], vec!['w'], vec![' ']] {
+        control.update(&keys);
+    }
+    assert!(!has(&control, "special", ControlEventType::KeyPressed));
+}
+
+fn buffered() -> Control {
+    let mut control = Control::new();
+    control.add_key_map(0, ' ', "jump", true);
+    control.add_composite(0, "buffered jump", Composite::Buffered("jump", 3));
+    control
+}
+
+#[test]
+fn buffered_expires() {
+    let mut control = buffered();
+    control.update(&vec![' ']);
+    assert!(has(&control, "buffered jump", ControlEventType::KeyPressed));
+
+    // held for the frame it's pressed and the two after, even though the key's let go
+    let mut held = vec![has(&control, "buffered jump", ControlEventType::KeyHeld)];
+    for _ in 0..3 {
+        control.update(&vec![]);
+        held.push(has(&control, "buffered jump", ControlEventType::KeyHeld));
+    }
+    assert_eq!(held, vec![true, true, true, false]);
+}
+
+#[test]
+fn consume_buffered() {
+    let mut control = buffered();
+    assert!(!control.consume_buffered(0, "buffered jump"));
+    control.update(&vec![' ']);
+
+    // the press can only be used once
+    assert!(control.consume_buffered(0, "buffered jump"));
+    assert!(!control.consume_buffered(0, "buffered jump"));
+    control.update(&vec![]);
+    assert!(!has(&control, "buffered jump", ControlEventType::KeyHeld));
+
+    // the same goes for the reaction
+    control.update(&vec![' ']);
+    control.handle_predicate(&ControlReaction::ConsumeBuffered(0, "buffered jump"));
+    control.update(&vec![]);
+    assert!(!has(&control, "buffered jump", ControlEventType::KeyHeld));
+}
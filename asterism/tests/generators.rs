@@ -0,0 +1,54 @@
+//! Generators should follow their pools' bounds and overflow policies, and shouldn't panic on extreme values.
+
+use asterism::resources::*;
+use asterism::Logic;
+
+#[test]
+fn extreme_decay() {
+    let mut rsrc = QueuedResources::<&str, i16>::new();
+    rsrc.items
+        .insert("a", PoolValues::new(i16::MAX, i16::MIN, i16::MAX));
+    rsrc.items
+        .insert("b", PoolValues::new(i16::MIN, i16::MIN, i16::MAX));
+    let down = RateRule::DecayToward {
+        target: i16::MIN,
+        amount: i16::MIN,
+    };
+    let up = RateRule::DecayToward {
+        target: i16::MAX,
+        amount: 100,
+    };
+    rsrc.add_generator("a", Generator::new(down, 1));
+    rsrc.add_generator("b", Generator::new(up, 1));
+    rsrc.update();
+
+    assert_eq!(rsrc.get_value_by_itemtype(&"a"), Some(0));
+    assert_eq!(rsrc.get_value_by_itemtype(&"b"), Some(i16::MIN + 100));
+}
+
+#[test]
+fn bounds() {
+    let decay = RateRule::DecayToward {
+        target: -10,
+        amount: 5,
+    };
+    let mut rsrc = QueuedResources::<&str, i16>::new();
+    rsrc.items.insert("checked", PoolValues::new(2, 0, 10));
+    rsrc.items.insert(
+        "saturating",
+        PoolValues::new(2, 0, 10).with_overflow(Overflow::Saturating),
+    );
+    rsrc.add_generator("checked", Generator::new(decay, 1));
+    rsrc.add_generator("saturating", Generator::new(decay, 1));
+    rsrc.update();
+
+    // the target is below the pool's min, so the checked pool refuses and the saturating one stops at 0
+    assert_eq!(rsrc.get_value_by_itemtype(&"checked"), Some(2));
+    assert!(rsrc.events().iter().any(|e| e.pool == "checked"
+        && e.event_type == ResourceEventType::TransactionUnsuccessful(ResourceError::TooSmall)));
+    assert_eq!(rsrc.get_value_by_itemtype(&"saturating"), Some(0));
+    assert!(rsrc
+        .events()
+        .iter()
+        .any(|e| e.pool == "saturating" && e.event_type == ResourceEventType::ReachedMin));
+}
@@ -0,0 +1,117 @@
+//! Items should only stack with identical items, split when they're too big for one stack, and stay put when they can't be moved.
+
+use asterism::resources::inventory::*;
+use asterism::Logic;
+
+#[test]
+fn stacks_by_name() {
+    let mut inv = InventoryLogic::<&str, &str>::new();
+    inv.add_container("bag", 5);
+    inv.add_item(
+        "bag",
+        Item::new("potion", "Red Potion").with_stack_limit(10),
+    )
+    .unwrap();
+    inv.add_item(
+        "bag",
+        Item::new("potion", "Blue Potion").with_stack_limit(10),
+    )
+    .unwrap();
+    inv.add_item(
+        "bag",
+        Item::new("potion", "Red Potion").with_stack_limit(10),
+    )
+    .unwrap();
+
+    // the blue potion has a different name, so it gets its own stack
+    assert_eq!(inv.containers[&"bag"].items.len(), 2);
+    assert_eq!(inv.count_kind(&"bag", &"potion"), 3);
+}
+
+#[test]
+fn splits_big_stacks() {
+    let mut inv = InventoryLogic::<&str, &str>::new();
+    inv.add_container("bag", 3);
+    let arrows = Item::new("arrow", "Arrow").with_stack_limit(20);
+    let id = inv.add_item("bag", arrows.clone().with_count(45)).unwrap();
+
+    let counts: Vec<u16> = inv.containers[&"bag"]
+        .items
+        .iter()
+        .map(|id| inv.items[id].count)
+        .collect();
+    assert_eq!(counts, vec![20, 20, 5]);
+    assert_eq!(inv.containers[&"bag"].items[0], id);
+
+    // no stack has room for 20 more and there's no free slot, so nothing changes
+    assert!(inv.add_item("bag", arrows.with_count(20)).is_err());
+    assert_eq!(inv.count_kind(&"bag", &"arrow"), 45);
+}
+
+#[test]
+fn failed_add_reports_its_id() {
+    let mut inv = InventoryLogic::<&str, &str>::new();
+    inv.add_container("bag", 1);
+    inv.add_item("bag", Item::new("sword", "Sword")).unwrap();
+    inv.handle_predicate(&InventoryReaction::Add(
+        "bag",
+        Item::new("shield", "Shield"),
+    ));
+    inv.update();
+    // the failed item's id is skipped, so the next item doesn't reuse it
+    let next = inv
+        .add_item("chest", Item::new("axe", "Axe"))
+        .unwrap_err()
+        .0;
+
+    let event = &inv.events()[0];
+    assert_eq!(
+        event.event_type,
+        InventoryEventType::Unsuccessful(InventoryError::ContainerFull)
+    );
+    assert_eq!(event.kind, "shield");
+    assert_eq!(event.item.idx() + 1, next.idx());
+}
+
+#[test]
+fn failed_move_keeps_order() {
+    let mut inv = InventoryLogic::<&str, &str>::new();
+    inv.add_container("bag", 3);
+    inv.add_container("chest", 0);
+    let items: Vec<ItemID> = ["rope", "torch", "map"]
+        .iter()
+        .map(|kind| inv.add_item("bag", Item::new(*kind, *kind)).unwrap())
+        .collect();
+
+    inv.handle_predicate(&InventoryReaction::Move(items[0], "chest"));
+    inv.update();
+
+    assert_eq!(
+        inv.events()[0].event_type,
+        InventoryEventType::Unsuccessful(InventoryError::ContainerFull)
+    );
+    assert_eq!(inv.containers[&"bag"].items, items);
+}
+
+#[test]
+fn empty_add_fails() {
+    let mut inv = InventoryLogic::<&str, &str>::new();
+    inv.add_container("bag", 2);
+    let (id, err) = inv
+        .add_item("bag", Item::new("arrow", "Arrow").with_count(0))
+        .unwrap_err();
+    assert_eq!(err, InventoryError::Empty);
+    assert!(!inv.items.contains_key(&id));
+    assert!(inv.containers[&"bag"].items.is_empty());
+
+    inv.handle_predicate(&InventoryReaction::Add(
+        "bag",
+        Item::new("arrow", "Arrow").with_count(0),
+    ));
+    inv.update();
+    assert_eq!(
+        inv.events()[0].event_type,
+        InventoryEventType::Unsuccessful(InventoryError::Empty)
+    );
+    assert_eq!(inv.count_kind(&"bag", &"arrow"), 0);
+}
@@ -0,0 +1,206 @@
+//! Every logic's `data_iter` should visit each of its entities once, in order, and `events_iter` should visit each of its events once, in order.
+
+mod common;
+
+use asterism::collision::AabbCollision;
+use asterism::control::{ControlEventType, KeyboardControl};
+use asterism::entity_state::{EntityEventType, EntityReaction, FlatEntityState};
+use asterism::linking::{GraphedLinking, LinkingEventType, LinkingReaction};
+use asterism::physics::{PhysicsEventType, PointPhysics};
+use asterism::resources::inventory::{InventoryEventType, InventoryLogic, InventoryReaction, Item};
+use asterism::resources::{
+    InstantResources, PoolValues, QueuedResources, ResourceEventType, Transaction,
+};
+use asterism::{Event, LendingIterator, Logic};
+use macroquad::math::Vec2;
+use common::TestWrapper;
+
+/// Collects the idents from a logic's data iterator. A macro rather than a function since `Logic` only says what a data iterator's items are for one lifetime, which isn't enough to call `next` in a loop on a generic logic.
+macro_rules! collect_idents {
+    ($logic:expr) => {{
+        let mut idents = Vec::new();
+        let mut iter = $logic.data_iter();
+        while let Some((ident, _)) = iter.next() {
+            idents.push(ident);
+        }
+        idents
+    }};
+}
+
+/// Collects the events from a logic's event iterator, checking that none were skipped.
+macro_rules! collect_events {
+    ($logic:expr) => {{
+        let mut events = Vec::new();
+        let mut iter = $logic.events_iter();
+        while let Some(event) = iter.next() {
+            events.push(event.clone());
+        }
+        assert_eq!(events.len(), $logic.events().len());
+        events
+    }};
+}
+
+#[test]
+fn empty_logics() {
+    let mut physics = PointPhysics::new();
+    assert!(collect_idents!(physics).is_empty());
+    assert!(collect_events!(physics).is_empty());
+
+    let mut control = KeyboardControl::<&str, TestWrapper>::new();
+    assert!(collect_idents!(control).is_empty());
+    assert!(collect_events!(control).is_empty());
+}
+
+#[test]
+fn collision() {
+    let mut collision = AabbCollision::new();
+    collision.add_collision_entity(Vec2::ZERO, Vec2::ONE, Vec2::ZERO, true, false, 'a');
+    collision.add_collision_entity(Vec2::X, Vec2::ONE, Vec2::ZERO, true, true, 'b');
+    collision.add_collision_entity(Vec2::splat(10.0), Vec2::ONE, Vec2::ZERO, true, true, 'c');
+    collision.update();
+
+    assert_eq!(collect_idents!(collision), vec![0, 1, 2]);
+    let contacts = collect_events!(collision);
+    assert_eq!(contacts.len(), 1);
+    assert_eq!(collision.get_ids(&contacts[0]), ('a', 'b'));
+}
+
+#[test]
+fn physics() {
+    let mut physics = PointPhysics::new();
+    physics.add_physics_entity(Vec2::ZERO, Vec2::ONE, Vec2::ZERO);
+    physics.add_physics_entity(Vec2::ZERO, Vec2::ONE, Vec2::ONE);
+    physics.update();
+
+    assert_eq!(collect_idents!(physics), vec![0, 1]);
+    let types: Vec<_> = collect_events!(physics)
+        .iter()
+        .map(|e| *e.get_type())
+        .collect();
+    assert_eq!(
+        types,
+        vec![
+            PhysicsEventType::PosChange,
+            PhysicsEventType::VelChange,
+            PhysicsEventType::PosChange
+        ]
+    );
+}
+
+#[test]
+fn entity_state() {
+    let mut ent_state = FlatEntityState::new();
+    ent_state.add_graph(0, [("idle", &["walking"][..]), ("walking", &["idle"])]);
+    ent_state.add_graph(0, [("closed", &["open"][..]), ("open", &[])]);
+    ent_state.handle_predicate(&EntityReaction::Activate(1, 1));
+    ent_state.update();
+
+    assert_eq!(collect_idents!(ent_state), vec![0, 1]);
+    let events = collect_events!(ent_state);
+    assert_eq!(events.len(), 2);
+    assert!(matches!(events[0].get_type(), EntityEventType::Activated));
+    assert!(matches!(
+        events[1].get_type(),
+        EntityEventType::Traversed(0)
+    ));
+    assert_eq!(ent_state.get_id_for_entity(1), "open");
+}
+
+#[test]
+fn linking() {
+    let mut linking = GraphedLinking::new();
+    linking.add_graph(0, [("hall", &["kitchen"][..]), ("kitchen", &["hall"])]);
+    linking.add_graph(0, [("start", &["end"][..]), ("end", &[])]);
+    linking.handle_predicate(&LinkingReaction::Activate(1, 1));
+    linking.update();
+
+    assert_eq!(collect_idents!(linking), vec![0, 1]);
+    let types: Vec<_> = collect_events!(linking)
+        .iter()
+        .map(|e| *e.get_type())
+        .collect();
+    assert_eq!(
+        types,
+        vec![LinkingEventType::Activated, LinkingEventType::Traversed(0)]
+    );
+    assert_eq!(linking.graphs[1].get_current_node(), "end");
+    assert_eq!(linking.graphs[0].get_current_node(), "hall");
+
+    // events from the last update don't stick around
+    linking.update();
+    assert_eq!(collect_events!(linking).len(), 1);
+}
+
+#[test]
+fn control() {
+    let mut control = KeyboardControl::<&str, TestWrapper>::new();
+    control.add_key_map(0, 'w', "up", true);
+    control.add_key_map(1, 'o', "up", true);
+    control.add_key_map(2, 'i', "up", true);
+    control.update(&vec!['i']);
+
+    assert_eq!(collect_idents!(control), vec![0, 1, 2]);
+    let events = collect_events!(control);
+    let pressed: Vec<_> = events
+        .iter()
+        .filter(|e| e.event_type == ControlEventType::KeyPressed)
+        .map(|e| e.set)
+        .collect();
+    assert_eq!(pressed, vec![2]);
+    assert_eq!(
+        events
+            .iter()
+            .filter(|e| e.event_type == ControlEventType::KeyUnheld)
+            .count(),
+        2
+    );
+}
+
+#[test]
+fn resources() {
+    let mut queued = QueuedResources::<&str, i32>::new();
+    queued.items.insert("gold", PoolValues::new(0, 0, 100));
+    queued.items.insert("wood", PoolValues::new(5, 0, 10));
+    queued.transactions.push(("gold", Transaction::Change(10)));
+    queued.transactions.push(("wood", Transaction::Change(-10)));
+    queued.update();
+
+    assert_eq!(collect_idents!(queued), vec!["gold", "wood"]);
+    let events = collect_events!(queued);
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].event_type, ResourceEventType::PoolUpdated);
+    assert!(matches!(
+        events[1].event_type,
+        ResourceEventType::TransactionUnsuccessful(_)
+    ));
+
+    let mut instant = InstantResources::<&str, i32>::new();
+    instant.items.insert("gold", PoolValues::new(0, 0, 100));
+    instant.items.insert("wood", PoolValues::new(5, 0, 10));
+    instant.handle_predicate(&("wood", Transaction::Change(5)));
+    instant.handle_predicate(&("gold", Transaction::Change(1)));
+
+    assert_eq!(collect_idents!(instant), vec!["gold", "wood"]);
+    let pools: Vec<_> = collect_events!(instant).iter().map(|e| e.pool).collect();
+    assert!(pools.starts_with(&["wood"]));
+    assert_eq!(pools.last(), Some(&"gold"));
+}
+
+#[test]
+fn inventory() {
+    let mut inv = InventoryLogic::<&str, &str>::new();
+    inv.add_container("bag", 5);
+    let rope = inv.add_item("bag", Item::new("rope", "Rope")).unwrap();
+    let torch = inv.add_item("bag", Item::new("torch", "Torch")).unwrap();
+    inv.handle_predicate(&InventoryReaction::Remove(rope));
+    inv.handle_predicate(&InventoryReaction::Remove(torch));
+    inv.update();
+
+    let events = collect_events!(inv);
+    assert_eq!(events.len(), 2);
+    assert!(events
+        .iter()
+        .all(|e| e.event_type == InventoryEventType::Removed));
+    assert_eq!((events[0].item, events[1].item), (rope, torch));
+    assert!(collect_idents!(inv).is_empty());
+}
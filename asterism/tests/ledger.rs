@@ -0,0 +1,83 @@
+//! Ledgers should only remember so much, and undoing reactions should leave what generators did alone, even when the reaction changed a pool's bounds.
+
+use asterism::resources::*;
+
+#[test]
+fn capacity() {
+    let mut rsrc = QueuedResources::<&str, i32>::new();
+    rsrc.items.insert("gold", PoolValues::new(0, 0, 100));
+    rsrc.enable_ledger(3);
+    for _ in 0..5 {
+        rsrc.transactions.push(("gold", Transaction::Change(1)));
+        rsrc.update();
+    }
+
+    // only the newest three are kept
+    let ledger = rsrc.ledger.as_ref().unwrap();
+    assert_eq!(ledger.entries.len(), 3);
+    assert_eq!(ledger.entries[0].frame, 3);
+    assert_eq!(rsrc.undo(5).len(), 3);
+    assert_eq!(rsrc.get_value_by_itemtype(&"gold"), Some(2));
+}
+
+#[test]
+fn undo_reactions() {
+    let mut rsrc = QueuedResources::<&str, i32>::new();
+    rsrc.items.insert("gold", PoolValues::new(10, 0, 100));
+    rsrc.items.insert("wood", PoolValues::new(0, 0, 100));
+    rsrc.add_generator("gold", Generator::new(RateRule::Gain(1), 1));
+    rsrc.enable_ledger(100);
+
+    rsrc.transactions
+        .push(("gold", Transaction::Trade(5, "wood")));
+    rsrc.update();
+    rsrc.update();
+    rsrc.update();
+    // 10 - 5, then the generator adds 1 each frame
+    assert_eq!(rsrc.get_value_by_itemtype(&"gold"), Some(8));
+
+    // the trade is taken back, but not the gold the generator made since
+    let undone = rsrc.undo_reactions(1);
+    assert_eq!(undone.len(), 1);
+    assert_eq!(undone[0].origin, TransactionOrigin::Reaction);
+    assert_eq!(rsrc.get_value_by_itemtype(&"gold"), Some(13));
+    assert_eq!(rsrc.get_value_by_itemtype(&"wood"), Some(0));
+
+    // the generator ticks are still in the ledger, and undoing them afterwards still works
+    let ledger = rsrc.ledger.as_ref().unwrap();
+    assert_eq!(ledger.entries.len(), 3);
+    assert!(ledger
+        .entries
+        .iter()
+        .all(|entry| entry.origin == TransactionOrigin::Generator));
+    rsrc.undo(3);
+    assert_eq!(rsrc.get_value_by_itemtype(&"gold"), Some(10));
+}
+
+#[test]
+fn undo_set_max() {
+    let mut rsrc = QueuedResources::<&str, i32>::new();
+    rsrc.items.insert(
+        "health",
+        PoolValues::new(5, 0, 10).with_overflow(Overflow::Saturating),
+    );
+    rsrc.add_generator("health", Generator::new(RateRule::Gain(10), 2));
+    rsrc.enable_ledger(100);
+
+    rsrc.transactions.push(("health", Transaction::SetMax(20)));
+    rsrc.update();
+    rsrc.update();
+    assert_eq!(rsrc.get_value_by_itemtype(&"health"), Some(15));
+
+    // the max goes back to 10, and the generator's change is applied again under it
+    rsrc.undo_reactions(1);
+    let health = rsrc.items["health"];
+    assert_eq!((health.val, health.max), (10, 10));
+
+    // undoing the generator's change afterwards gets back to where it started
+    rsrc.undo(1);
+    assert_eq!(
+        rsrc.items["health"],
+        PoolValues::new(5, 0, 10).with_overflow(Overflow::Saturating)
+    );
+}
@@ -0,0 +1,195 @@
+//! The lending iterator adapters, on a plain lending iterator and on logics' data and events.
+
+mod common;
+
+use asterism::control::{ControlEventType, KeyboardControl};
+use asterism::physics::PointPhysics;
+use asterism::resources::inventory::{InventoryLogic, Item};
+use asterism::resources::{PoolValues, QueuedResources};
+use asterism::{LendingIterator, Logic};
+use macroquad::math::Vec2;
+use common::TestWrapper;
+
+/// Lends out each number in a Vec mutably, along with its index.
+struct Numbers<'v> {
+    numbers: &'v mut Vec<i32>,
+    count: usize,
+}
+
+impl<'v> LendingIterator for Numbers<'v> {
+    type Item<'a> = (usize, &'a mut i32);
+
+    fn next(&mut self) -> Option<Self::Item<'_>> {
+        let number = self.numbers.get_mut(self.count)?;
+        self.count += 1;
+        Some((self.count - 1, number))
+    }
+}
+
+fn numbers(numbers: &mut Vec<i32>) -> Numbers<'_> {
+    Numbers { numbers, count: 0 }
+}
+
+#[test]
+fn filter() {
+    let mut v = vec![1, 2, 3, 4, 5, 6];
+    let mut evens = numbers(&mut v).filter(|(_, n)| **n % 2 == 0);
+    while let Some((_, n)) = evens.next() {
+        *n *= 10;
+    }
+    assert_eq!(v, vec![1, 20, 3, 40, 5, 60]);
+
+    // closures can borrow things from around them
+    let limit = 30;
+    let small: Vec<_> = numbers(&mut v)
+        .filter(|(_, n)| **n < limit)
+        .map(|(_, n)| *n)
+        .collect();
+    assert_eq!(small, vec![1, 20, 3, 5]);
+}
+
+#[test]
+fn map() {
+    let mut v = vec![3, 1, 2];
+    let doubled: Vec<_> = numbers(&mut v).map(|(_, n)| *n * 2).collect();
+    assert_eq!(doubled, vec![6, 2, 4]);
+
+    // the mapping function can still change the items
+    let idxs: Vec<_> = numbers(&mut v)
+        .map(|(i, n)| {
+            *n += 1;
+            i
+        })
+        .collect();
+    assert_eq!(idxs, vec![0, 1, 2]);
+    assert_eq!(v, vec![4, 2, 3]);
+}
+
+#[test]
+fn for_each() {
+    let mut v = vec![1, 2, 3];
+    let mut seen = Vec::new();
+    numbers(&mut v).for_each(|(i, n)| {
+        seen.push(i);
+        *n = 0;
+    });
+    assert_eq!(seen, vec![0, 1, 2]);
+    assert_eq!(v, vec![0, 0, 0]);
+}
+
+#[test]
+fn find() {
+    let mut v = vec![5, 8, 13, 21];
+    let mut iter = numbers(&mut v);
+    if let Some((i, n)) = iter.find(|(_, n)| **n > 10) {
+        assert_eq!(i, 2);
+        *n = 0;
+    }
+    // find leaves the iterator after the item it found
+    assert_eq!(iter.next().map(|(i, _)| i), Some(3));
+    assert!(iter.find(|_| true).is_none());
+    assert_eq!(v, vec![5, 8, 0, 21]);
+}
+
+#[test]
+fn take_and_skip() {
+    let mut v = vec![0, 1, 2, 3, 4, 5];
+    let taken: Vec<_> = numbers(&mut v).take(2).map(|(i, _)| i).collect();
+    assert_eq!(taken, vec![0, 1]);
+
+    let skipped: Vec<_> = numbers(&mut v).skip(4).map(|(i, _)| i).collect();
+    assert_eq!(skipped, vec![4, 5]);
+
+    let middle: Vec<_> = numbers(&mut v).skip(1).take(3).map(|(i, _)| i).collect();
+    assert_eq!(middle, vec![1, 2, 3]);
+
+    assert_eq!(numbers(&mut v).skip(10).map(|(i, _)| i).count(), 0);
+    assert_eq!(numbers(&mut v).take(10).map(|(i, _)| i).count(), 6);
+}
+
+#[test]
+fn fold() {
+    let mut v = vec![1, 2, 3, 4];
+    let sum = numbers(&mut v).fold(0, |sum, (_, n)| sum + *n);
+    assert_eq!(sum, 10);
+
+    let odd_sum = numbers(&mut v)
+        .filter(|(i, _)| i % 2 == 1)
+        .fold(0, |sum, (_, n)| sum + *n);
+    assert_eq!(odd_sum, 6);
+}
+
+#[test]
+fn logic_data() {
+    let mut physics = PointPhysics::new();
+    for i in 0..4 {
+        physics.add_physics_entity(Vec2::ZERO, Vec2::new(i as f32, 0.0), Vec2::ZERO);
+    }
+
+    physics
+        .data_iter()
+        .skip(1)
+        .filter(|(idx, _)| idx % 2 == 1)
+        .for_each(|(_, data)| *data.pos = Vec2::ONE);
+    assert_eq!(
+        physics.positions,
+        vec![Vec2::ZERO, Vec2::ONE, Vec2::ZERO, Vec2::ONE]
+    );
+
+    let fastest = physics
+        .data_iter()
+        .fold((0, 0.0), |(fastest, speed), (idx, data)| {
+            if data.vel.length() > speed {
+                (idx, data.vel.length())
+            } else {
+                (fastest, speed)
+            }
+        });
+    assert_eq!(fastest, (3, 3.0));
+
+    let mut rsrc = QueuedResources::<&str, i32>::new();
+    rsrc.items.insert("gold", PoolValues::new(10, 0, 100));
+    rsrc.items.insert("wood", PoolValues::new(0, 0, 100));
+    let empty: Vec<_> = rsrc
+        .data_iter()
+        .filter(|(_, pool)| pool.val == 0)
+        .map(|(id, _)| id)
+        .collect();
+    assert_eq!(empty, vec!["wood"]);
+
+    // pools and items can be keyed by things that borrow from local variables
+    let names = ["gold".to_string(), "wood".to_string()];
+    let mut rsrc = QueuedResources::<&String, i32>::new();
+    rsrc.items.insert(&names[0], PoolValues::new(10, 0, 100));
+    rsrc.items.insert(&names[1], PoolValues::new(0, 0, 100));
+    rsrc.data_iter()
+        .filter(|(_, pool)| pool.val == 0)
+        .for_each(|(_, pool)| pool.val = 5);
+    assert_eq!(rsrc.get_value_by_itemtype(&&names[1]), Some(5));
+
+    let mut inv = InventoryLogic::<&str, &String>::new();
+    inv.add_container("bag", 5);
+    inv.add_item("bag", Item::new(&names[1], "Plank")).unwrap();
+    let found = inv.data_iter().find(|(_, item)| item.kind == &names[1]);
+    assert!(found.is_some());
+}
+
+#[test]
+fn logic_events() {
+    let mut control = KeyboardControl::<&str, TestWrapper>::new();
+    control.add_key_map(0, 'w', "up", true);
+    control.add_key_map(0, 's', "down", true);
+    control.add_key_map(1, 'o', "up", true);
+    control.update(&vec!['w', 'o']);
+
+    let pressed: Vec<_> = control
+        .events_iter()
+        .filter(|e| e.event_type == ControlEventType::KeyPressed)
+        .map(|e| (e.set, e.action_id))
+        .collect();
+    assert_eq!(pressed, vec![(0, "up"), (1, "up")]);
+
+    let mut events = control.events_iter();
+    let first_unheld = events.find(|e| e.event_type == ControlEventType::KeyUnheld);
+    assert_eq!(first_unheld.map(|e| e.action_id), Some("down"));
+}
@@ -0,0 +1,42 @@
+//! Graphs should follow the edge to whichever node was activated, no matter which graph it's in.
+
+use asterism::linking::{GraphedLinking, LinkingEvent, LinkingEventType, LinkingReaction};
+use asterism::Logic;
+
+#[test]
+fn traverse_edge() {
+    let mut linking = GraphedLinking::new();
+    linking.add_graph(0, [("hall", &["kitchen"][..]), ("kitchen", &["hall"])]);
+    linking.add_graph(
+        0,
+        [
+            ("door", &["garden", "street"][..]),
+            ("garden", &[]),
+            ("street", &[]),
+        ],
+    );
+
+    // the second graph goes to its third node, which isn't the graph's index or the edge's place in the list
+    linking.handle_predicate(&LinkingReaction::Activate(1, 2));
+    linking.update();
+    assert_eq!(linking.graphs[0].current_node, 0);
+    assert_eq!(linking.graphs[1].current_node, 2);
+    assert_eq!(*linking.get_ident_data(1), "street");
+    assert_eq!(linking.just_traversed, vec![false, true]);
+    assert_eq!(
+        linking.events().last(),
+        Some(&LinkingEvent {
+            graph: 1,
+            node: 2,
+            event_type: LinkingEventType::Traversed(0),
+        })
+    );
+
+    // last update's traversal isn't reported again
+    linking.update();
+    assert_eq!(linking.just_traversed, vec![false, false]);
+    assert!(linking
+        .events()
+        .iter()
+        .all(|event| event.event_type == LinkingEventType::Activated));
+}
@@ -0,0 +1,56 @@
+//! Lockstep peers should keep going when packets get lost along the way.
+
+mod common;
+
+use std::net::{SocketAddr, UdpSocket};
+use std::thread;
+use std::time::Duration;
+
+use asterism::control::NetPeer;
+use common::Key;
+
+/// Passes packets between two peers, dropping the first one that starts with `drop`.
+fn proxy(a: SocketAddr, b: SocketAddr, drop: &'static str) -> SocketAddr {
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    socket
+        .set_read_timeout(Some(Duration::from_secs(2)))
+        .unwrap();
+    let addr = socket.local_addr().unwrap();
+    thread::spawn(move || {
+        let mut dropped = false;
+        let mut buf = [0; 1024];
+        while let Ok((len, from)) = socket.recv_from(&mut buf) {
+            if !dropped && buf[..len].starts_with(drop.as_bytes()) {
+                dropped = true;
+                continue;
+            }
+            let to = if from == a { b } else { a };
+            socket.send_to(&buf[..len], to).unwrap();
+        }
+    });
+    addr
+}
+
+#[test]
+fn lost_packet() {
+    let mut p1 = NetPeer::bind("127.0.0.1:0").unwrap();
+    let mut p2 = NetPeer::bind("127.0.0.1:0").unwrap();
+    p1.retries = 20;
+    p2.retries = 20;
+    // player 1's message for frame 0 never makes it, so player 1 gets a frame ahead
+    let proxy = proxy(p1.local_addr().unwrap(), p2.local_addr().unwrap(), "0 w");
+    p1.connect(proxy).unwrap();
+    p2.connect(proxy).unwrap();
+
+    let other = thread::spawn(move || {
+        (0..3)
+            .map(|frame| p2.exchange(frame, &[Key('o')]).unwrap())
+            .collect::<Vec<_>>()
+    });
+    let mine = (0..3)
+        .map(|frame| p1.exchange(frame, &[Key('w')]).unwrap())
+        .collect::<Vec<_>>();
+
+    assert_eq!(mine, vec![vec![Key('o')]; 3]);
+    assert_eq!(other.join().unwrap(), vec![vec![Key('w')]; 3]);
+}
@@ -0,0 +1,124 @@
+//! Pools should follow their overflow policy at the limits of their value type, instead of panicking.
+
+use asterism::resources::*;
+use asterism::Logic;
+
+/// Changes a pool at `val` in `min..=max` by `amt` under each policy, returning the results in the order checked, saturating, wrapping.
+fn change<V: PoolValue>(val: V, min: V, max: V, amt: V) -> Vec<Result<V, ResourceError>> {
+    [Overflow::Checked, Overflow::Saturating, Overflow::Wrapping]
+        .iter()
+        .map(|overflow| {
+            PoolValues::new(val, min, max)
+                .with_overflow(*overflow)
+                .after::<()>(&Transaction::Change(amt))
+                .map(|pool| pool.val)
+        })
+        .collect()
+}
+
+#[test]
+fn i16_limits() {
+    // a score at i16::MAX
+    assert_eq!(
+        change(i16::MAX, 0, i16::MAX, 1),
+        vec![Err(ResourceError::Overflow), Ok(i16::MAX), Ok(0)]
+    );
+    assert_eq!(
+        change(i16::MIN, i16::MIN, i16::MAX, -1),
+        vec![Err(ResourceError::Overflow), Ok(i16::MIN), Ok(i16::MAX)]
+    );
+    // the whole range at once wraps back to where it started
+    assert_eq!(
+        change(i16::MAX, i16::MIN, i16::MAX, i16::MAX)[2],
+        Ok(i16::MAX.wrapping_add(i16::MAX))
+    );
+}
+
+#[test]
+fn f32_limits() {
+    assert_eq!(
+        change(f32::MAX, 0.0, f32::MAX, f32::MAX / 2.0),
+        vec![
+            Err(ResourceError::Overflow),
+            Ok(f32::MAX),
+            Ok(f32::MAX / 2.0)
+        ]
+    );
+    assert_eq!(
+        change(0.75, 0.0, 1.0, 0.5),
+        vec![Err(ResourceError::TooBig), Ok(1.0), Ok(0.25)]
+    );
+    assert_eq!(
+        change(0.25, 0.0, 1.0, -0.5),
+        vec![Err(ResourceError::TooSmall), Ok(0.0), Ok(0.75)]
+    );
+}
+
+#[test]
+fn fixed_limits() {
+    let (zero, half) = (Fixed::from_int(0), Fixed::from_f32(0.5));
+    assert_eq!(
+        change(Fixed::MAX, zero, Fixed::MAX, half),
+        vec![Err(ResourceError::Overflow), Ok(Fixed::MAX), Ok(half)]
+    );
+    assert_eq!(
+        change(Fixed::MIN, Fixed::MIN, zero, -half),
+        vec![Err(ResourceError::Overflow), Ok(Fixed::MIN), Ok(-half)]
+    );
+}
+
+#[test]
+fn queued_and_instant() {
+    let mut queued = QueuedResources::<&str, i16>::new();
+    let mut instant = InstantResources::<&str, i16>::new();
+    for (name, overflow) in [
+        ("checked", Overflow::Checked),
+        ("saturating", Overflow::Saturating),
+        ("wrapping", Overflow::Wrapping),
+    ] {
+        let pool = PoolValues::new(i16::MAX, 0, i16::MAX).with_overflow(overflow);
+        queued.items.insert(name, pool);
+        instant.items.insert(name, pool);
+        queued.transactions.push((name, Transaction::Change(1)));
+        instant.handle_predicate(&(name, Transaction::Change(1)));
+    }
+    queued.update();
+
+    for values in [&queued.items, &instant.items] {
+        assert_eq!(values["checked"].val, i16::MAX);
+        assert_eq!(values["saturating"].val, i16::MAX);
+        assert_eq!(values["wrapping"].val, 0);
+    }
+}
+
+#[test]
+fn set() {
+    let mut queued = QueuedResources::<&str, i16>::new();
+    let mut instant = InstantResources::<&str, i16>::new();
+    for (name, overflow) in [
+        ("checked", Overflow::Checked),
+        ("saturating", Overflow::Saturating),
+        ("wrapping", Overflow::Wrapping),
+    ] {
+        let pool = PoolValues::new(5, 0, 9).with_overflow(overflow);
+        queued.items.insert(name, pool);
+        instant.items.insert(name, pool);
+        for set in [Transaction::Set(3), Transaction::Set(12)] {
+            queued.transactions.push((name, set.clone()));
+            instant.handle_predicate(&(name, set));
+        }
+    }
+    queued.update();
+
+    // the value is set, not added to, and 12 is out of range
+    for (values, events) in [
+        (&queued.items, queued.events()),
+        (&instant.items, instant.events()),
+    ] {
+        assert_eq!(values["checked"].val, 3);
+        assert_eq!(values["saturating"].val, 9);
+        assert_eq!(values["wrapping"].val, 2);
+        assert!(events.iter().any(|e| e.pool == "checked"
+            && e.event_type == ResourceEventType::TransactionUnsuccessful(ResourceError::TooBig)));
+    }
+}
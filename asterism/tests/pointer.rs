@@ -0,0 +1,55 @@
+//! Mouse inputs should be saved and loaded by name, and the cursor and wheel should be read as analog inputs.
+
+use asterism::control::{
+    BindingName, InputType, InputWrapper, MacroquadPointerWrapper, PointerInput,
+};
+use macroquad::prelude::{KeyCode, MouseButton};
+
+#[test]
+fn binding_names() {
+    let inputs = [
+        PointerInput::Button(MouseButton::Left),
+        PointerInput::Button(MouseButton::Right),
+        PointerInput::Button(MouseButton::Middle),
+        PointerInput::Cursor,
+        PointerInput::Wheel,
+        PointerInput::Key(KeyCode::Space),
+    ];
+    let names: Vec<String> = inputs.iter().map(|input| input.binding_name()).collect();
+    assert_eq!(
+        names,
+        vec![
+            "Mouse:Left",
+            "Mouse:Right",
+            "Mouse:Middle",
+            "Cursor",
+            "Wheel",
+            "Space"
+        ]
+    );
+    for (input, name) in inputs.iter().zip(names.iter()) {
+        assert_eq!(PointerInput::from_binding_name(name), Some(*input));
+    }
+    assert_eq!(PointerInput::from_binding_name("Mouse:Unknown"), None);
+    assert_eq!(PointerInput::from_binding_name("Mouse:"), None);
+}
+
+#[test]
+fn input_types() {
+    assert_eq!(
+        MacroquadPointerWrapper::input_type(&PointerInput::Cursor),
+        InputType::Analog
+    );
+    assert_eq!(
+        MacroquadPointerWrapper::input_type(&PointerInput::Wheel),
+        InputType::Analog
+    );
+    assert_eq!(
+        MacroquadPointerWrapper::input_type(&PointerInput::Button(MouseButton::Left)),
+        InputType::Digital
+    );
+    assert_eq!(
+        MacroquadPointerWrapper::input_type(&PointerInput::Key(KeyCode::W)),
+        InputType::Digital
+    );
+}
@@ -0,0 +1,140 @@
+//! Predicate tables should only fire rules whose events all happened and whose state conditions all hold.
+
+use asterism::physics::{PhysicsEventType, PointPhysics};
+use asterism::predicates::{Fired, PredicateTable, Rule};
+use asterism::resources::{
+    PoolValues, QueuedResources, ResourceError, ResourceEventType, Transaction,
+};
+use asterism::scheduler::{LogicID, Scheduler};
+use macroquad::math::Vec2;
+
+struct Logics {
+    physics: PointPhysics,
+    resources: QueuedResources<&'static str, i32>,
+}
+
+type Ids = (
+    LogicID<Logics, PointPhysics>,
+    LogicID<Logics, QueuedResources<&'static str, i32>>,
+);
+
+fn actions<'a>(fired: Vec<Fired<'a, &'static str>>) -> Vec<&'static str> {
+    fired.iter().map(|fired| *fired.action).collect()
+}
+
+fn setup(gold: i32) -> (Logics, Scheduler<Logics>, Ids) {
+    let mut logics = Logics {
+        physics: PointPhysics::new(),
+        resources: QueuedResources::new(),
+    };
+    logics
+        .resources
+        .items
+        .insert("gold", PoolValues::new(gold, 0, 10));
+
+    let mut scheduler = Scheduler::new();
+    let physics = scheduler.add_logic(
+        "physics",
+        |logics: &Logics| &logics.physics,
+        |logics| &mut logics.physics,
+        |logics| logics.physics.update(),
+    );
+    let rsrc = scheduler.add_logic(
+        "resources",
+        |logics: &Logics| &logics.resources,
+        |logics| &mut logics.resources,
+        |logics| logics.resources.update(),
+    );
+    (logics, scheduler, (physics, rsrc))
+}
+
+#[test]
+fn events_across_logics() {
+    let (mut logics, mut scheduler, (physics, rsrc)) = setup(0);
+
+    let mut table = PredicateTable::new();
+    table.add_rule(Rule::new("moved").on_event(physics, PhysicsEventType::PosChange, |_, _| true));
+    table.add_rule(
+        Rule::new("moved and paid")
+            .on_event(physics, PhysicsEventType::PosChange, |_, _| true)
+            .on_event(rsrc, ResourceEventType::PoolUpdated, |event, _| {
+                event.pool == "gold"
+            }),
+    );
+
+    // nothing has happened yet
+    scheduler.update(&mut logics);
+    assert!(table.evaluate(&logics).is_empty());
+
+    logics
+        .physics
+        .add_physics_entity(Vec2::ZERO, Vec2::ONE, Vec2::ZERO);
+    scheduler.update(&mut logics);
+    assert_eq!(actions(table.evaluate(&logics)), vec!["moved"]);
+
+    logics
+        .resources
+        .transactions
+        .push(("gold", Transaction::Change(1)));
+    scheduler.update(&mut logics);
+    assert_eq!(
+        actions(table.evaluate(&logics)),
+        vec!["moved", "moved and paid"]
+    );
+
+    // each rule gets the events that satisfied it, from every logic it needed events from
+    let fired = table.evaluate(&logics);
+    assert_eq!(fired[0].events(physics, &logics).len(), 1);
+    assert!(fired[0].events(rsrc, &logics).is_empty());
+    let paid = fired[1].events(rsrc, &logics);
+    assert_eq!(paid.len(), 1);
+    assert!(paid[0].transaction == Transaction::Change(1));
+}
+
+#[test]
+fn event_data() {
+    let (mut logics, mut scheduler, (_, rsrc)) = setup(0);
+
+    // the data in the event type is ignored when looking rules up, so the pattern has to check it
+    let mut table = PredicateTable::new();
+    table.add_rule(Rule::new("too poor").on_event(
+        rsrc,
+        ResourceEventType::TransactionUnsuccessful(ResourceError::TooSmall),
+        |event, _| {
+            event.event_type == ResourceEventType::TransactionUnsuccessful(ResourceError::TooSmall)
+        },
+    ));
+
+    logics
+        .resources
+        .transactions
+        .push(("gold", Transaction::Change(20)));
+    scheduler.update(&mut logics);
+    assert!(table.evaluate(&logics).is_empty());
+
+    logics
+        .resources
+        .transactions
+        .push(("gold", Transaction::Change(-5)));
+    scheduler.update(&mut logics);
+    assert_eq!(actions(table.evaluate(&logics)), vec!["too poor"]);
+}
+
+#[test]
+fn state_only() {
+    let (logics, _, _) = setup(5);
+
+    let mut table = PredicateTable::new();
+    table.add_rule(Rule::new("always"));
+    table.add_rule(
+        Rule::new("rich")
+            .when(|logics: &Logics| logics.resources.get_value_by_itemtype(&"gold") > Some(3)),
+    );
+    table.add_rule(
+        Rule::new("broke")
+            .when(|logics: &Logics| logics.resources.get_value_by_itemtype(&"gold") == Some(0)),
+    );
+
+    // rules without events are checked even when nothing happened
+    assert_eq!(actions(table.evaluate(&logics)), vec!["always", "rich"]);
+}
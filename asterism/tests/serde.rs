@@ -0,0 +1,127 @@
+//! Logics should come back the same after being saved and loaded with the `serde` feature.
+#![cfg(feature = "serde")]
+
+mod common;
+
+use asterism::collision::AabbCollision;
+use asterism::control::KeyboardControl;
+use asterism::linking::{GraphedLinking, LinkingReaction};
+use asterism::physics::PointPhysics;
+use asterism::resources::inventory::{InventoryLogic, Item};
+use asterism::resources::{Fixed, Overflow, PoolValues, QueuedResources, Transaction};
+use asterism::Logic;
+use macroquad::math::Vec2;
+use serde::{de::DeserializeOwned, Serialize};
+use common::{Key, TestWrapper};
+
+fn round_trip<T: Serialize + DeserializeOwned>(logic: &T) -> T {
+    let json = serde_json::to_string(logic).unwrap();
+    serde_json::from_str(&json).unwrap()
+}
+
+#[test]
+fn physics_and_collision() {
+    let mut physics = PointPhysics::new();
+    physics.add_physics_entity(Vec2::ZERO, Vec2::ONE, Vec2::Y);
+    physics.update();
+    // vectors are saved as plain arrays, whatever glam version macroquad uses
+    let json = serde_json::to_string(&physics).unwrap();
+    assert!(json.contains(r#""velocities":[[1.0,2.0]]"#));
+    let loaded = round_trip(&physics);
+    assert_eq!(loaded.positions, physics.positions);
+    assert_eq!(loaded.velocities, physics.velocities);
+    assert_eq!(loaded.events(), physics.events());
+
+    let mut collision = AabbCollision::new();
+    collision.add_collision_entity(Vec2::ZERO, Vec2::ONE, Vec2::ZERO, true, false, 'a');
+    collision.add_collision_entity(Vec2::X, Vec2::ONE, Vec2::ZERO, true, true, 'b');
+    collision.update();
+    let loaded = round_trip(&collision);
+    assert_eq!(loaded.centers, collision.centers);
+    assert_eq!(loaded.events().len(), 1);
+    assert_eq!(loaded.get_ids(&loaded.events()[0]), ('a', 'b'));
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Serialize, serde::Deserialize)]
+enum Pool {
+    Gold(usize),
+    Wood,
+}
+
+#[test]
+fn resources() {
+    // pools that aren't strings can still be saved as JSON
+    let mut rsrc = QueuedResources::<Pool, i32>::new();
+    rsrc.enable_ledger(100);
+    rsrc.items.insert(Pool::Gold(0), PoolValues::new(0, 0, 100));
+    rsrc.items.insert(Pool::Wood, PoolValues::new(5, 0, 10));
+    rsrc.transactions
+        .push((Pool::Gold(0), Transaction::Change(10)));
+    rsrc.update();
+
+    let mut loaded = round_trip(&rsrc);
+    assert_eq!(loaded.items, rsrc.items);
+    assert_eq!(loaded.ledger.as_ref().unwrap().entries.len(), 1);
+
+    // the ledger still works after loading
+    loaded.undo(1);
+    assert_eq!(loaded.get_value_by_itemtype(&Pool::Gold(0)), Some(0));
+
+    let mut health = QueuedResources::<String, Fixed>::new();
+    let full = Fixed::from_int(1);
+    health.items.insert(
+        "health".to_string(),
+        PoolValues::new(Fixed::from_f32(0.25), Fixed::from_int(0), full)
+            .with_overflow(Overflow::Wrapping),
+    );
+    let loaded = round_trip(&health);
+    assert_eq!(loaded.items, health.items);
+
+    let mut inv = InventoryLogic::<&str, String>::new();
+    inv.add_container("bag", 5);
+    inv.add_item("bag", Item::new("rope".to_string(), "Rope"))
+        .unwrap();
+    let json = serde_json::to_string(&inv).unwrap();
+    let loaded: InventoryLogic<String, String> = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded.containers["bag"].items.len(), 1);
+    assert_eq!(loaded.items.values().next().unwrap().name, "Rope");
+}
+
+#[test]
+fn linking() {
+    let mut linking = GraphedLinking::new();
+    linking.add_graph(0, [("hall", &["kitchen"][..]), ("kitchen", &["hall"])]);
+    linking.handle_predicate(&LinkingReaction::Traverse(0, 1));
+    linking.update();
+
+    let json = serde_json::to_string(&linking).unwrap();
+    let loaded: GraphedLinking<&str> = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded.graphs[0].get_current_node(), "kitchen");
+    assert_eq!(loaded.graphs[0].graph.edges, linking.graphs[0].graph.edges);
+}
+
+#[test]
+fn control() {
+    let mut control = KeyboardControl::<u8, TestWrapper<Key>>::new();
+    control.add_key_map(0, Key('w'), 0, true);
+    control.add_key_map(1, Key('o'), 0, false);
+    control.update(&vec![Key('w')]);
+
+    let json = serde_json::to_string(&control).unwrap();
+    let mut loaded: KeyboardControl<u8, TestWrapper<Key>> = serde_json::from_str(&json).unwrap();
+    assert_eq!(*loaded.mapping[0][0].get_keycode(), Key('w'));
+    assert_eq!(loaded.values[0][0].value, control.values[0][0].value);
+    assert!(!loaded.mapping[1][0].is_valid);
+    assert_eq!(loaded.events(), control.events());
+
+    // a new input wrapper is made, and the loaded logic keeps working with it. The new wrapper doesn't remember last frame's keys, so w looks like it was just pressed again
+    let pressed = control.events().to_vec();
+    loaded.update(&vec![Key('w')]);
+    control.update(&vec![Key('w')]);
+    assert_eq!(loaded.values[0][0].value, control.values[0][0].value);
+    assert_eq!(loaded.events(), pressed);
+
+    // unknown keys are an error
+    let bad = json.replace("\"w\"", "\"\"");
+    assert!(serde_json::from_str::<KeyboardControl<u8, TestWrapper<Key>>>(&bad).is_err());
+}
@@ -0,0 +1,191 @@
+//! Rewinding logics and simulating the same frames again should end up exactly where they did the first time.
+
+mod common;
+
+use asterism::collision::{AabbCollision, CollisionReaction};
+use asterism::control::{ControlEventType, KeyboardControl};
+use asterism::physics::{PhysicsReaction, PointPhysics};
+use asterism::resources::{PoolValues, QueuedResources, Transaction};
+use asterism::snapshot::{History, Snapshot};
+use asterism::Logic;
+use macroquad::math::Vec2;
+use common::TestWrapper;
+
+struct Logics {
+    control: KeyboardControl<u8, TestWrapper>,
+    physics: PointPhysics,
+    collision: AabbCollision<usize>,
+    resources: QueuedResources<&'static str, i32>,
+}
+
+impl Logics {
+    fn new() -> Self {
+        let mut control = KeyboardControl::new();
+        control.add_key_map(0, 'a', 0, true);
+        let mut physics = PointPhysics::new();
+        physics.add_physics_entity(Vec2::ZERO, Vec2::X, Vec2::ZERO);
+        let mut collision = AabbCollision::new();
+        collision.add_collision_entity(Vec2::ZERO, Vec2::ONE, Vec2::ZERO, false, false, 0);
+        collision.add_collision_entity(Vec2::new(8.0, 0.0), Vec2::ONE, Vec2::ZERO, true, true, 1);
+        let mut resources = QueuedResources::new();
+        resources.enable_ledger(100);
+        resources
+            .items
+            .insert("bounces", PoolValues::new(0, 0, 100));
+        resources
+            .items
+            .insert("presses", PoolValues::new(0, 0, 100));
+        Self {
+            control,
+            physics,
+            collision,
+            resources,
+        }
+    }
+
+    /// a small game: the key is held for two frames out of every four, the ball speeds up while it is, bounces off a wall, and the bounces and presses are counted
+    fn update(&mut self, frame: usize) {
+        if frame % 4 < 2 {
+            self.control.press_synthetic(0, 0);
+        }
+        self.control.update(&vec![]);
+        let held = self.control.values[0][0].value;
+        let pressed = self
+            .control
+            .events()
+            .iter()
+            .any(|e| e.event_type == ControlEventType::KeyPressed);
+        if pressed {
+            self.resources
+                .transactions
+                .push(("presses", Transaction::Change(1)));
+        }
+
+        self.physics.accelerations[0] = Vec2::new(held * 0.1, 0.0);
+        self.physics.update();
+        let pos = self.physics.positions[0];
+        self.collision
+            .handle_predicate(&CollisionReaction::SetCenter(0, pos));
+
+        self.collision.update();
+        if !self.collision.events().is_empty() {
+            let vel = self.physics.velocities[0];
+            self.physics
+                .handle_predicate(&PhysicsReaction::SetVel(0, Vec2::new(-vel.x, vel.y)));
+            self.resources
+                .transactions
+                .push(("bounces", Transaction::Change(1)));
+        }
+
+        self.resources.update();
+    }
+}
+
+struct Histories {
+    control: History<<KeyboardControl<u8, TestWrapper> as Snapshot>::State>,
+    physics: History<PointPhysics>,
+    collision: History<AabbCollision<usize>>,
+    resources: History<QueuedResources<&'static str, i32>>,
+}
+
+impl Histories {
+    fn new(frames: usize) -> Self {
+        Self {
+            control: History::new(frames),
+            physics: History::new(frames),
+            collision: History::new(frames),
+            resources: History::new(frames),
+        }
+    }
+
+    fn record(&mut self, logics: &Logics) {
+        self.control.record(&logics.control);
+        self.physics.record(&logics.physics);
+        self.collision.record(&logics.collision);
+        self.resources.record(&logics.resources);
+    }
+
+    fn rewind(&mut self, logics: &mut Logics, frames: usize) -> bool {
+        self.control.rewind(&mut logics.control, frames)
+            && self.physics.rewind(&mut logics.physics, frames)
+            && self.collision.rewind(&mut logics.collision, frames)
+            && self.resources.rewind(&mut logics.resources, frames)
+    }
+}
+
+fn assert_same(a: &Logics, b: &Logics) {
+    assert_eq!(a.control.values[0][0].value, b.control.values[0][0].value);
+    assert_eq!(a.control.events(), b.control.events());
+    assert_eq!(a.physics.positions, b.physics.positions);
+    assert_eq!(a.physics.velocities, b.physics.velocities);
+    assert_eq!(a.physics.events(), b.physics.events());
+    assert_eq!(a.collision.centers, b.collision.centers);
+    // contacts and resource events can't be printed, so they're just compared
+    assert!(a.collision.events() == b.collision.events());
+    assert_eq!(a.resources.items, b.resources.items);
+    assert!(a.resources.events() == b.resources.events());
+    assert_eq!(
+        a.resources.ledger.as_ref().unwrap().entries.len(),
+        b.resources.ledger.as_ref().unwrap().entries.len()
+    );
+}
+
+#[test]
+fn rollback_and_resimulate() {
+    let mut logics = Logics::new();
+    let mut history = Histories::new(20);
+    for frame in 0..20 {
+        logics.update(frame);
+        history.record(&logics);
+    }
+    // the ball bounced and the key was pressed, so there's something to get wrong
+    assert!(logics.resources.get_value_by_itemtype(&"bounces") > Some(0));
+    assert!(logics.resources.get_value_by_itemtype(&"presses") > Some(0));
+
+    // a copy of the game that was never rewound
+    let mut expected = Logics::new();
+    for frame in 0..20 {
+        expected.update(frame);
+    }
+
+    // frame 19 was recorded last, so 15 frames before it is frame 4, just before the bounce
+    assert!(history.rewind(&mut logics, 15));
+    assert_eq!(history.physics.len(), 5);
+    for frame in 5..20 {
+        logics.update(frame);
+        history.record(&logics);
+    }
+    assert_same(&logics, &expected);
+
+    // keeps going the same way afterwards too
+    for frame in 20..30 {
+        logics.update(frame);
+        expected.update(frame);
+    }
+    assert_same(&logics, &expected);
+}
+
+#[test]
+fn rewind_too_far() {
+    let mut physics = PointPhysics::new();
+    physics.add_physics_entity(Vec2::ZERO, Vec2::ONE, Vec2::ZERO);
+    let mut history = History::new(4);
+    assert!(!history.rewind(&mut physics, 0));
+
+    for _ in 0..3 {
+        physics.update();
+        history.record(&physics);
+    }
+    assert!(!history.rewind(&mut physics, 3));
+    assert_eq!(physics.positions[0], Vec2::splat(3.0));
+
+    // rewinding 0 frames puts back the latest snapshot
+    physics.positions[0] = Vec2::ZERO;
+    assert!(history.rewind(&mut physics, 0));
+    assert_eq!(physics.positions[0], Vec2::splat(3.0));
+
+    // nothing is recorded without any capacity
+    let mut history = History::new(0);
+    history.record(&physics);
+    assert!(history.is_empty());
+}
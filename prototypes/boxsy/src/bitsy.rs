@@ -0,0 +1,644 @@
+//! # Importing Bitsy games
+//!
+//! Reads a game made in [Bitsy](https://bitsy.org), so boxsy can be tried out on real Bitsy games. Either the game data on its own or a whole exported HTML page can be read.
+//!
+//! What comes over:
+//!
+//! - rooms (`ROOM`), which are 16x16 and scroll around the player
+//! - tiles (`TIL`), with walls being solid. Tiles use the first room's palette
+//! - the player (`SPR A`) and the other sprites (`SPR`), which become characters
+//! - items (`ITM`), which become resources. Items placed in a room become characters holding one of them, which give it to the player and disappear when bumped into
+//! - exits (`EXT`), which become room links
+//! - dialogue (`DLG`), which sprites say when bumped into, and items say when picked up. Only the words come over, not scripts or text effects
+//!
+//! - the pixel art of tiles, sprites and items, with their animations
+//!
+//! Everything else (endings, variables, and sound) is left out, with a warning saying so.
+
+use std::collections::BTreeMap;
+
+use crate::*;
+
+impl Game {
+    /// Reads a Bitsy game, and returns it along with warnings about anything that couldn't be brought over.
+    pub fn from_bitsy(text: &str) -> Result<(Self, Vec<String>), String> {
+        let data = BitsyData::parse(game_data(text))?;
+        let mut import = Import::new(&data);
+        import.build()?;
+        Ok((import.game, import.warnings))
+    }
+}
+
+/// pulls the game data out of an exported Bitsy page. Anything else is assumed to be game data already.
+fn game_data(text: &str) -> &str {
+    let start = text
+        .find("id=\"exportedGameData\"")
+        .and_then(|i| text[i..].find('>').map(|j| i + j + 1));
+    match start {
+        Some(start) => {
+            let end = text[start..]
+                .find("</script>")
+                .map(|i| start + i)
+                .unwrap_or_else(|| text.len());
+            &text[start..end]
+        }
+        None => text,
+    }
+}
+
+#[derive(Default)]
+struct BitsyRoom<'a> {
+    id: &'a str,
+    /// tile ids, "0" being no tile
+    tiles: Vec<Vec<&'a str>>,
+    items: Vec<(&'a str, IVec2)>,
+    /// position, room it goes to, and position in that room
+    exits: Vec<(IVec2, &'a str, IVec2)>,
+    endings: usize,
+    palette: Option<&'a str>,
+}
+
+#[derive(Default)]
+struct BitsyThing<'a> {
+    id: &'a str,
+    name: Option<&'a str>,
+    wall: bool,
+    color: Option<usize>,
+    /// sprites only
+    pos: Option<(&'a str, IVec2)>,
+    dialogue: Option<&'a str>,
+    /// rows of `0`s and `1`s for each frame of the animation
+    frames: Vec<Vec<&'a str>>,
+}
+
+/// everything in a Bitsy game that's read
+#[derive(Default)]
+struct BitsyData<'a> {
+    palettes: Vec<(&'a str, Vec<Color>)>,
+    rooms: Vec<BitsyRoom<'a>>,
+    tiles: Vec<BitsyThing<'a>>,
+    sprites: Vec<BitsyThing<'a>>,
+    items: Vec<BitsyThing<'a>>,
+    /// dialogue ids and their text
+    dialogue: Vec<(&'a str, String)>,
+    endings: usize,
+    variables: usize,
+    /// other kinds of blocks, and how many of each
+    skipped: BTreeMap<&'a str, usize>,
+}
+
+impl<'a> BitsyData<'a> {
+    fn parse(text: &'a str) -> Result<Self, String> {
+        // blocks are separated by blank lines, except inside of """ quotes
+        let mut blocks = vec![Vec::new()];
+        let mut quoted = false;
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim_end();
+            if line.trim() == "\"\"\"" {
+                quoted = !quoted;
+            }
+            if line.is_empty() && !quoted {
+                blocks.push(Vec::new());
+            } else {
+                blocks.last_mut().unwrap().push((i + 1, line));
+            }
+        }
+
+        let mut data = Self::default();
+        for (i, block) in blocks.iter().filter(|block| !block.is_empty()).enumerate() {
+            let (_, header) = block[0];
+            let mut words = header.split_whitespace();
+            let kind = words.next().unwrap_or("");
+            let id = words.next().unwrap_or("");
+            let lines = &block[1..];
+            match kind {
+                "ROOM" | "SET" => data.rooms.push(parse_room(id, lines)?),
+                "TIL" => data.tiles.push(parse_thing(id, lines)?),
+                "SPR" => data.sprites.push(parse_thing(id, lines)?),
+                "ITM" => data.items.push(parse_thing(id, lines)?),
+                "PAL" => {
+                    let mut colors = Vec::new();
+                    for (line, text) in lines.iter() {
+                        if text.starts_with("NAME") {
+                            continue;
+                        }
+                        let rgb = text
+                            .split(',')
+                            .map(|c| c.trim().parse::<u8>())
+                            .collect::<Result<Vec<_>, _>>()
+                            .ok()
+                            .filter(|rgb| rgb.len() == 3)
+                            .ok_or_else(|| format!("line {}: {} isn't a color", line, text))?;
+                        colors.push(Color::from_rgba(rgb[0], rgb[1], rgb[2], 255));
+                    }
+                    data.palettes.push((id, colors));
+                }
+                "DLG" => data.dialogue.push((id, parse_dialogue(lines))),
+                "END" => data.endings += 1,
+                "VAR" => data.variables += 1,
+                // the version comment, flags, and the title
+                _ if kind.starts_with('#') || kind.starts_with('!') => {}
+                _ if i == 0 => {}
+                _ => *data.skipped.entry(kind).or_insert(0) += 1,
+            }
+        }
+        if data.rooms.is_empty() {
+            return Err("there aren't any rooms in this game".to_string());
+        }
+        Ok(data)
+    }
+}
+
+/// reads an `x,y` position
+fn parse_pos(line: usize, text: Option<&str>) -> Result<IVec2, String> {
+    text.and_then(|text| {
+        let (x, y) = text.split_once(',')?;
+        Some(IVec2::new(x.parse().ok()?, y.parse().ok()?))
+    })
+    .ok_or_else(|| format!("line {}: expected a position like 4,5", line))
+}
+
+fn parse_room<'a>(id: &'a str, lines: &[(usize, &'a str)]) -> Result<BitsyRoom<'a>, String> {
+    let mut room = BitsyRoom {
+        id,
+        ..Default::default()
+    };
+    for (line, text) in lines.iter() {
+        let mut words = text.split_whitespace();
+        match words.next() {
+            Some("ITM") => {
+                let item = words.next().unwrap_or("");
+                room.items.push((item, parse_pos(*line, words.next())?));
+            }
+            Some("EXT") => {
+                let pos = parse_pos(*line, words.next())?;
+                let to = words
+                    .next()
+                    .ok_or_else(|| format!("line {}: expected a room", line))?;
+                room.exits.push((pos, to, parse_pos(*line, words.next())?));
+            }
+            Some("END") => room.endings += 1,
+            Some("PAL") => room.palette = words.next(),
+            Some("NAME") | Some("TUN") => {}
+            // rows of tile ids, which are separated by commas in newer games, and single letters in older ones
+            _ if text.contains(',') => room.tiles.push(text.split(',').collect()),
+            _ => room.tiles.push(
+                text.char_indices()
+                    .map(|(i, c)| &text[i..i + c.len_utf8()])
+                    .collect(),
+            ),
+        }
+    }
+    Ok(room)
+}
+
+/// the text of a dialogue block, which is either on one line or between """ quotes
+fn parse_dialogue(lines: &[(usize, &str)]) -> String {
+    let mut text = Vec::new();
+    let mut quoted = false;
+    for (_, line) in lines.iter() {
+        if line.trim() == "\"\"\"" {
+            quoted = !quoted;
+        } else if quoted || !line.starts_with("NAME") {
+            text.push(*line);
+        }
+    }
+    text.join("\n")
+}
+
+/// splits dialogue text into the lines that are said, taking out any `{}` scripts and effects. Also says whether there was a script that did something, which can't be brought over
+fn dialogue_lines(text: &str) -> (Vec<String>, bool) {
+    let mut said = String::new();
+    let mut scripted = false;
+    let mut tag = String::new();
+    let mut depth = 0;
+    for c in text.chars() {
+        match c {
+            '{' => {
+                if depth > 0 {
+                    tag.push(c);
+                }
+                depth += 1;
+            }
+            '}' if depth > 0 => {
+                depth -= 1;
+                if depth > 0 {
+                    tag.push(c);
+                    continue;
+                }
+                let tag = std::mem::take(&mut tag);
+                let tag = tag.trim();
+                let effect = tag.trim_start_matches('/');
+                if tag == "br" {
+                    said.push('\n');
+                } else if !["wvy", "shk", "rbw"].contains(&effect) && !effect.starts_with("clr") {
+                    scripted = true;
+                }
+            }
+            c if depth > 0 => tag.push(c),
+            c => said.push(c),
+        }
+    }
+    let lines = said
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect();
+    (lines, scripted)
+}
+
+fn parse_thing<'a>(id: &'a str, lines: &[(usize, &'a str)]) -> Result<BitsyThing<'a>, String> {
+    let mut thing = BitsyThing {
+        id,
+        frames: vec![Vec::new()],
+        ..Default::default()
+    };
+    for (line, text) in lines.iter() {
+        let (key, rest) = text.split_once(' ').unwrap_or((text, ""));
+        match key {
+            ">" => thing.frames.push(Vec::new()),
+            _ if key.chars().all(|c| c == '0' || c == '1') => {
+                thing.frames.last_mut().unwrap().push(*text)
+            }
+            "NAME" => thing.name = Some(rest),
+            "WAL" => thing.wall = rest == "true",
+            "COL" => {
+                thing.color = Some(
+                    rest.parse()
+                        .map_err(|_| format!("line {}: {} isn't a color", line, rest))?,
+                )
+            }
+            "POS" => {
+                let mut words = rest.split_whitespace();
+                let room = words.next().unwrap_or("");
+                thing.pos = Some((room, parse_pos(*line, words.next())?));
+            }
+            "DLG" => thing.dialogue = Some(rest.trim()),
+            // sounds
+            _ => {}
+        }
+    }
+    thing.frames.retain(|frame| !frame.is_empty());
+    Ok(thing)
+}
+
+/// turns Bitsy's game data into a boxsy game
+struct Import<'a, 'data> {
+    data: &'data BitsyData<'a>,
+    game: Game,
+    warnings: Vec<String>,
+    palette: Vec<Color>,
+    rooms: BTreeMap<&'a str, usize>,
+    tiles: BTreeMap<&'a str, TileID>,
+    items: BTreeMap<&'a str, RsrcID>,
+    /// a tile that isn't drawn, for exits that aren't on top of a tile
+    exit_tile: Option<TileID>,
+}
+
+impl<'a, 'data> Import<'a, 'data> {
+    fn new(data: &'data BitsyData<'a>) -> Self {
+        Self {
+            data,
+            game: Game::new(),
+            warnings: Vec::new(),
+            palette: Vec::new(),
+            rooms: BTreeMap::new(),
+            tiles: BTreeMap::new(),
+            items: BTreeMap::new(),
+            exit_tile: None,
+        }
+    }
+
+    fn warn(&mut self, warning: String) {
+        self.warnings.push(warning);
+    }
+
+    fn build(&mut self) -> Result<(), String> {
+        self.choose_palette()?;
+        self.tiles();
+        self.rooms();
+        self.items();
+        self.player()?;
+        self.sprites();
+        self.placed_items();
+        self.exits();
+        self.leftovers();
+        Ok(())
+    }
+
+    /// what a sprite or item says. Older games don't say which dialogue goes with which sprite, and use the one with the same id
+    fn dialogue(&self, thing: &BitsyThing) -> Option<Dialogue> {
+        let id = thing.dialogue.unwrap_or(thing.id);
+        let (_, text) = self.data.dialogue.iter().find(|(dlg, _)| *dlg == id)?;
+        let (lines, _) = dialogue_lines(text);
+        if lines.is_empty() {
+            None
+        } else {
+            Some(Dialogue::lines(lines))
+        }
+    }
+
+    /// a thing's pixel art in one color, with the `0`s see-through. Bitsy animations go to the next frame every 400 milliseconds
+    fn sprite(&mut self, thing: &BitsyThing, color: Color) -> Option<Sprite> {
+        if thing.frames.is_empty() {
+            return None;
+        }
+        let frames: Vec<Vec<String>> = thing
+            .frames
+            .iter()
+            .map(|frame| {
+                frame
+                    .iter()
+                    .map(|row| row.replace('0', ".").replace('1', "0"))
+                    .collect()
+            })
+            .collect();
+        let frames: Vec<Vec<&str>> = frames
+            .iter()
+            .map(|frame| frame.iter().map(String::as_str).collect())
+            .collect();
+        match Sprite::from_rows(vec![color], &frames) {
+            Ok(mut sprite) => {
+                sprite.frame_time = 24;
+                Some(sprite)
+            }
+            Err(e) => {
+                self.warn(format!("the pixel art of {} was left out: {}", thing.id, e));
+                None
+            }
+        }
+    }
+
+    fn color(&self, idx: Option<usize>, default: usize) -> Color {
+        self.palette
+            .get(idx.unwrap_or(default))
+            .copied()
+            .unwrap_or(WHITE)
+    }
+
+    /// boxsy only has one set of colors, so everything uses the first room's palette
+    fn choose_palette(&mut self) -> Result<(), String> {
+        let palettes = &self.data.palettes;
+        let first_room = &self.data.rooms[0];
+        let palette = match first_room.palette {
+            Some(id) => palettes.iter().find(|(pal, _)| *pal == id),
+            None => palettes.first(),
+        };
+        self.palette = palette
+            .map(|(_, colors)| colors.clone())
+            .ok_or_else(|| "there aren't any palettes in this game".to_string())?;
+        self.game.set_background(self.color(Some(0), 0));
+
+        let mut used = self.data.rooms.iter().filter_map(|room| room.palette);
+        if let Some(first) = used.next() {
+            if used.any(|pal| pal != first) {
+                self.warn(
+                    "rooms use different palettes, but they all use the first room's now"
+                        .to_string(),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn tiles(&mut self) {
+        let data = self.data;
+        for til in data.tiles.iter() {
+            let mut tile = Tile::new();
+            tile.solid = til.wall;
+            tile.color = self.color(til.color, 1);
+            tile.sprite = self.sprite(til, tile.color);
+            let id = self.game.log_tile_info(tile);
+            self.tiles.insert(til.id, id);
+        }
+    }
+
+    fn rooms(&mut self) {
+        let mut unknown_tiles = 0;
+        for bitsy_room in self.data.rooms.iter() {
+            let width = bitsy_room
+                .tiles
+                .iter()
+                .map(|row| row.len())
+                .max()
+                .unwrap_or(0);
+            let room = self.game.add_room(width, bitsy_room.tiles.len());
+            self.rooms.insert(bitsy_room.id, room);
+            for (y, row) in bitsy_room.tiles.iter().enumerate() {
+                for (x, id) in row.iter().enumerate() {
+                    let id = id.trim();
+                    if id == "0" || id.is_empty() {
+                        continue;
+                    }
+                    match self.tiles.get(id) {
+                        Some(tile) => {
+                            self.game
+                                .add_tile_at_pos(*tile, room, IVec2::new(x as i32, y as i32));
+                        }
+                        None => unknown_tiles += 1,
+                    }
+                }
+            }
+        }
+        if unknown_tiles > 0 {
+            self.warn(format!(
+                "{} spots in rooms had tiles that don't exist",
+                unknown_tiles
+            ));
+        }
+    }
+
+    fn items(&mut self) {
+        for item in self.data.items.iter() {
+            // resource names can't have spaces in them, to fit in boxsy's format
+            let mut name = item
+                .name
+                .map(|name| name.split_whitespace().collect::<Vec<_>>().join("_"))
+                .filter(|name| !name.is_empty())
+                .unwrap_or_else(|| format!("item_{}", item.id));
+            if self.game.state.resources.iter().any(|r| r.name() == name) {
+                name = format!("{}_{}", name, item.id);
+            }
+            let id = self.game.log_rsrc(name);
+            self.items.insert(item.id, id);
+        }
+    }
+
+    fn player(&mut self) -> Result<(), String> {
+        let data = self.data;
+        let sprite = data
+            .sprites
+            .iter()
+            .find(|spr| spr.id == "A")
+            .ok_or_else(|| "there isn't a player (sprite A) in this game".to_string())?;
+        let (room, pos) = sprite
+            .pos
+            .ok_or_else(|| "the player (sprite A) isn't in a room".to_string())?;
+        let room = *self
+            .rooms
+            .get(room)
+            .ok_or_else(|| format!("the player is in room {}, which doesn't exist", room))?;
+
+        let mut player = Player::new();
+        player.pos = pos;
+        player.color = self.color(sprite.color, 2);
+        player.sprite = self.sprite(sprite, player.color);
+        for rsrc in self.game.state.resources.iter() {
+            player.add_inventory_item(rsrc.clone(), 0);
+        }
+        self.game.set_player(player);
+
+        let graph = &mut self.game.logics.linking.graphs[0];
+        let node = graph.graph.node_idx(&room).unwrap();
+        graph.set_current_node(node);
+        Ok(())
+    }
+
+    fn sprites(&mut self) {
+        let data = self.data;
+        for sprite in data.sprites.iter().filter(|spr| spr.id != "A") {
+            let (room, pos) = match sprite.pos {
+                Some(pos) => pos,
+                // sprites don't have to be put anywhere
+                None => continue,
+            };
+            let room = match self.rooms.get(room) {
+                Some(room) => *room,
+                None => {
+                    self.warn(format!(
+                        "sprite {} is in room {}, which doesn't exist",
+                        sprite.id, room
+                    ));
+                    continue;
+                }
+            };
+            let mut character = Character::new();
+            character.pos = pos;
+            character.color = self.color(sprite.color, 2);
+            character.sprite = self.sprite(sprite, character.color);
+            character.dialogue = self.dialogue(sprite);
+            self.game.add_character(character, room);
+        }
+    }
+
+    fn placed_items(&mut self) {
+        let data = self.data;
+        for bitsy_room in data.rooms.iter() {
+            let room = self.rooms[bitsy_room.id];
+            for (item_id, pos) in bitsy_room.items.iter() {
+                let (item, rsrc) = match data.items.iter().find(|item| item.id == *item_id) {
+                    Some(item) => (item, self.items[item.id].clone()),
+                    None => {
+                        self.warn(format!(
+                            "room {} has item {}, which doesn't exist",
+                            bitsy_room.id, item_id
+                        ));
+                        continue;
+                    }
+                };
+                let mut character = Character::new();
+                character.pos = *pos;
+                character.color = self.color(item.color, 2);
+                character.sprite = self.sprite(item, character.color);
+                character.add_inventory_item(rsrc.clone(), 1);
+                character.dialogue = self.dialogue(item);
+                let id = self.game.add_character(character, room);
+
+                // picked up when the player walks into it
+                let event = (room, CollisionEnt::Player, CollisionEnt::Character(id));
+                self.game.add_collision_predicate(
+                    event,
+                    EngineAction::ChangeResource(
+                        PoolID::new(EntID::Character(id), rsrc.clone()),
+                        Transaction::Trade(1, PoolID::new(EntID::Player, rsrc)),
+                    ),
+                );
+                self.game
+                    .add_collision_predicate(event, EngineAction::RemoveCharacter(id));
+            }
+        }
+    }
+
+    fn exits(&mut self) {
+        let data = self.data;
+        for bitsy_room in data.rooms.iter() {
+            let room = self.rooms[bitsy_room.id];
+            for (pos, to, to_pos) in bitsy_room.exits.iter() {
+                let to = match self.rooms.get(to) {
+                    Some(to) => *to,
+                    None => {
+                        self.warn(format!(
+                            "an exit in room {} goes to room {}, which doesn't exist",
+                            bitsy_room.id, to
+                        ));
+                        continue;
+                    }
+                };
+                let pos = *pos;
+                let tile = self.game.state.rooms[room]
+                    .map
+                    .get(pos.y as usize)
+                    .and_then(|row| row.get(pos.x as usize));
+                let empty = match tile {
+                    Some(tile) => tile.is_none(),
+                    None => {
+                        self.warn(format!(
+                            "an exit in room {} is outside of the room",
+                            bitsy_room.id
+                        ));
+                        continue;
+                    }
+                };
+                // links happen when the player runs into a tile, so exits need something to run into
+                if empty {
+                    let tile = match self.exit_tile {
+                        Some(tile) => tile,
+                        None => {
+                            let mut tile = Tile::new();
+                            tile.color = self.game.draw.background_color;
+                            let tile = self.game.log_tile_info(tile);
+                            self.exit_tile = Some(tile);
+                            tile
+                        }
+                    };
+                    self.game.add_tile_at_pos(tile, room, pos);
+                }
+                self.game
+                    .add_link((room, CollisionEnt::Tile(pos)), (to, *to_pos));
+            }
+        }
+    }
+
+    /// warns about everything that's left out
+    fn leftovers(&mut self) {
+        let data = self.data;
+        let scripted = data
+            .dialogue
+            .iter()
+            .filter(|(_, text)| dialogue_lines(text).1)
+            .count();
+        if scripted > 0 {
+            self.warn(format!(
+                "{} dialogues had scripts in them, which were left out",
+                scripted
+            ));
+        }
+        let endings = data.endings + data.rooms.iter().map(|room| room.endings).sum::<usize>();
+        if endings > 0 {
+            self.warn(format!(
+                "{} endings were left out, since boxsy games don't end",
+                endings
+            ));
+        }
+        if data.variables > 0 {
+            self.warn(format!(
+                "{} variables were left out, since they're only used in dialogue scripts",
+                data.variables
+            ));
+        }
+        for (kind, count) in data.skipped.iter() {
+            self.warn(format!("{} {} blocks were left out", count, kind));
+        }
+    }
+}
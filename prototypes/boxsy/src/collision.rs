@@ -6,6 +6,7 @@ use asterism::{Event, LendingIterator, Logic, Reaction};
 use macroquad::math::IVec2;
 
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CollisionData<ID: Copy> {
     pub solid: bool,
     pub fixed: bool,
@@ -18,19 +19,37 @@ impl<ID: Copy> CollisionData<ID> {
     }
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        deserialize = "TileID: serde::Deserialize<'de> + Ord, EntID: serde::Deserialize<'de>"
+    ))
+)]
 pub struct TileMapCollision<TileID: Debug, EntID: Copy> {
     pub map: Vec<Vec<Option<TileID>>>,
     pub tile_solid: BTreeMap<TileID, bool>,
+    #[cfg_attr(feature = "serde", serde(with = "asterism::serde_math"))]
     pub positions: Vec<IVec2>,
     pub metadata: Vec<CollisionData<EntID>>,
+    #[cfg_attr(feature = "serde", serde(with = "asterism::serde_math"))]
     pub amt_moved: Vec<IVec2>,
     pub contacts: Vec<Contact>,
 }
 
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Contact {
     Ent(usize, usize),
-    Tile(usize, IVec2),
+    Tile(
+        usize,
+        #[cfg_attr(feature = "serde", serde(with = "asterism::serde_math"))] IVec2,
+    ),
+    /// an entity tried to leave the map, in the given direction (ex. `(1, 0)` is off the right side). It's kept in bounds.
+    Edge(
+        usize,
+        #[cfg_attr(feature = "serde", serde(with = "asterism::serde_math"))] IVec2,
+    ),
 }
 
 use asterism::collision::CollisionEventType;
@@ -94,7 +113,7 @@ pub enum TileMapColDataMut<'logic, TileID, EntID> {
 
 impl<TileID, EntID> Reaction for CollisionReaction<TileID, EntID> {}
 
-impl<TileID: Copy + Eq + Ord + Debug, EntID: Eq + Copy + Debug> Logic
+impl<TileID: Copy + Eq + Ord + Debug + 'static, EntID: Eq + Copy + Debug + 'static> Logic
     for TileMapCollision<TileID, EntID>
 {
     type Event = Contact;
@@ -243,12 +262,18 @@ impl<TileID: Eq + Ord + Copy + Debug, EntID: Eq + Copy + Debug> TileMapCollision
         self.contacts.clear();
 
         // make sure everyone is IN BOUNDS
-        for (pos, amt_moved) in self.positions.iter_mut().zip(self.amt_moved.iter_mut()) {
+        for (i, (pos, amt_moved)) in self
+            .positions
+            .iter_mut()
+            .zip(self.amt_moved.iter_mut())
+            .enumerate()
+        {
             let width = self.map[0].len() as i32;
             let height = self.map.len() as i32;
             if let Some(oob_direction) =
                 TileMapCollision::<TileID, EntID>::in_bounds(width, height, *pos)
             {
+                self.contacts.push(Contact::Edge(i, oob_direction));
                 if oob_direction.x < 0 {
                     let delta = 0 - pos.x;
                     pos.x = 0;
@@ -308,13 +333,14 @@ impl<TileID: Eq + Ord + Copy + Debug, EntID: Eq + Copy + Debug> TileMapCollision
                     if self.positions[*i] != *pos {
                         continue;
                     }
-                    if !self.metadata[*i].solid || self.metadata[*i].fixed {
+                    // fixed entities can still walk into walls on their own
+                    if !self.metadata[*i].solid {
                         continue;
                     }
                     let pos = &mut self.positions[*i];
                     let moved = &mut self.amt_moved[*i];
                     if *moved == IVec2::ZERO {
-                        return;
+                        continue;
                     }
                     let norm_moved = normalize(*moved);
                     while let Some(tile) = self.map[pos.y as usize][pos.x as usize] {
@@ -327,10 +353,25 @@ impl<TileID: Eq + Ord + Copy + Debug, EntID: Eq + Copy + Debug> TileMapCollision
                     }
                 }
 
+                Contact::Edge(..) => {}
+
                 Contact::Ent(i, j) => {
                     if self.positions[*i] != self.positions[*j] {
                         continue;
                     }
+                    // fixed entities don't get pushed around, but they get pushed back out of whoever they walked into
+                    if self.metadata[*i].solid && self.metadata[*j].solid {
+                        let walked = [*j, *i]
+                            .iter()
+                            .copied()
+                            .find(|k| self.metadata[*k].fixed && self.amt_moved[*k] != IVec2::ZERO);
+                        if let Some(k) = walked {
+                            let norm_moved = normalize(self.amt_moved[k]);
+                            self.positions[k] -= norm_moved;
+                            self.amt_moved[k] -= norm_moved;
+                            continue;
+                        }
+                    }
                     if !self.metadata[*i].solid
                         || self.metadata[*i].fixed
                         || !self.metadata[*j].solid
@@ -375,6 +416,14 @@ impl<TileID: Eq + Ord + Copy + Debug, EntID: Eq + Copy + Debug> TileMapCollision
         self.amt_moved.resize(self.positions.len(), IVec2::ZERO);
     }
 
+    /// whether a position is outside of the map or has a solid tile on it
+    pub fn blocked(&self, pos: IVec2) -> bool {
+        let width = self.map.first().map_or(0, |row| row.len()) as i32;
+        let height = self.map.len() as i32;
+        Self::in_bounds(width, height, pos).is_some()
+            || matches!(self.tile_at_pos(&pos), Some(tile) if self.tile_solid.get(tile) == Some(&true))
+    }
+
     pub fn tile_at_pos(&self, pos: &IVec2) -> &Option<TileID> {
         &self.map[pos.y as usize][pos.x as usize]
     }
@@ -439,10 +488,10 @@ where
 
 impl<'logic, TileID, EntID> LendingIterator for ColDataIter<'logic, TileID, EntID>
 where
-    TileID: Copy + Eq + Ord + Debug,
-    EntID: Copy + Eq + Debug,
+    TileID: Copy + Eq + Ord + Debug + 'static,
+    EntID: Copy + Eq + Debug + 'static,
 {
-    type Item<'a> = (ColIdent, TileMapColDataMut<'a, TileID, EntID>) where Self: 'a;
+    type Item<'a> = (ColIdent, TileMapColDataMut<'a, TileID, EntID>);
 
     fn next(&mut self) -> Option<Self::Item<'_>> {
         let inc_pos = |pos: &mut IVec2, len: usize| {
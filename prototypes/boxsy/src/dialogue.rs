@@ -0,0 +1,178 @@
+use crate::*;
+
+/// What a character says when the player bumps into them.
+#[derive(Clone, Debug)]
+pub enum Dialogue {
+    /// one text box of talking
+    Line(String),
+    /// one thing after another
+    Sequence(Vec<Dialogue>),
+    /// a question, and the options the player picks from, each with what's said after picking it
+    Choice(String, Vec<(String, Dialogue)>),
+    /// does something in the game partway through talking (giving the player something, moving rooms...)
+    Action(Box<EngineAction>),
+}
+
+impl Dialogue {
+    /// Makes a sequence out of the lines, or just the line if there's only one.
+    pub fn lines<S: Into<String>>(lines: impl IntoIterator<Item = S>) -> Self {
+        let mut lines: Vec<Dialogue> = lines
+            .into_iter()
+            .map(|line| Dialogue::Line(line.into()))
+            .collect();
+        if lines.len() == 1 {
+            lines.pop().unwrap()
+        } else {
+            Dialogue::Sequence(lines)
+        }
+    }
+}
+
+/// A conversation that's happening.
+pub struct Talk {
+    /// what's still to come, with the next thing last
+    left: Vec<Dialogue>,
+    showing: Option<Dialogue>,
+    /// the option that's highlighted, when there's a choice showing
+    pub choice: usize,
+}
+
+impl Talk {
+    /// Starts a conversation. Nothing's showing until [Talk::next] is called.
+    pub fn new(dialogue: &Dialogue) -> Self {
+        Self {
+            left: vec![dialogue.clone()],
+            showing: None,
+            choice: 0,
+        }
+    }
+
+    /// The line or choice on screen. `None` once the conversation is over.
+    pub fn showing(&self) -> Option<&Dialogue> {
+        self.showing.as_ref()
+    }
+
+    /// Moves on to the next line or choice, doing any actions along the way.
+    pub fn next(&mut self, state: &mut State, logics: &mut Logics) {
+        self.showing = None;
+        self.choice = 0;
+        while let Some(dialogue) = self.left.pop() {
+            match dialogue {
+                Dialogue::Sequence(dialogue) => self.left.extend(dialogue.into_iter().rev()),
+                Dialogue::Action(action) => action.perform_action(state, logics),
+                dialogue => {
+                    self.showing = Some(dialogue);
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Picks one of the options of the choice showing, and moves on to what comes after it.
+    pub fn choose(&mut self, option: usize, state: &mut State, logics: &mut Logics) {
+        if let Some(Dialogue::Choice(_, mut options)) = self.showing.take() {
+            if option < options.len() {
+                self.left.push(options.swap_remove(option).1);
+            }
+        }
+        self.next(state, logics);
+    }
+}
+
+impl Game {
+    /// Gives a character something to say. It's said whenever the player bumps into them.
+    pub fn set_dialogue(&mut self, character: CharacterID, dialogue: Dialogue) {
+        self.events.dialogue.insert(character, dialogue);
+    }
+
+    /// Starts talking to a character, if they have anything to say.
+    pub(crate) fn talk_to(&mut self, character: CharacterID) {
+        if let Some(dialogue) = self.events.dialogue.get(&character) {
+            let mut talk = Talk::new(dialogue);
+            talk.next(&mut self.state, &mut self.logics);
+            if talk.showing.is_some() {
+                self.state.talking = Some(talk);
+            }
+        }
+    }
+}
+
+/// Moves the conversation along: space or enter go to the next line, and up and down pick between options.
+pub(crate) fn update(game: &mut Game) {
+    let mut talk = match game.state.talking.take() {
+        Some(talk) => talk,
+        None => return,
+    };
+    let next = is_key_pressed(KeyCode::Space) || is_key_pressed(KeyCode::Enter);
+    if let Some(Dialogue::Choice(_, options)) = &talk.showing {
+        let count = options.len().max(1);
+        if is_key_pressed(KeyCode::Up) {
+            talk.choice = (talk.choice + count - 1) % count;
+        }
+        if is_key_pressed(KeyCode::Down) {
+            talk.choice = (talk.choice + 1) % count;
+        }
+        if next {
+            talk.choose(talk.choice, &mut game.state, &mut game.logics);
+        }
+    } else if next {
+        talk.next(&mut game.state, &mut game.logics);
+    }
+    if talk.showing.is_some() {
+        game.state.talking = Some(talk);
+    }
+}
+
+/// Draws the text box along the bottom of the screen.
+pub(crate) fn draw(talk: &Talk) {
+    let size = (TILE_SIZE / 2) as f32;
+    let margin = size / 2.0;
+    let (x, width, height) = (margin, screen_width() - margin * 2.0, screen_height() / 3.0);
+    let y = screen_height() - height - margin;
+    draw_rectangle(x, y, width, height, BLACK);
+    draw_rectangle_lines(x, y, width, height, 2.0, WHITE);
+
+    let mut lines = Vec::new();
+    match &talk.showing {
+        Some(Dialogue::Line(text)) => lines.extend(wrap(text, width - margin * 2.0, size)),
+        Some(Dialogue::Choice(question, options)) => {
+            lines.extend(wrap(question, width - margin * 2.0, size));
+            for (i, (option, _)) in options.iter().enumerate() {
+                let marker = if i == talk.choice { ">" } else { " " };
+                lines.push(format!("{} {}", marker, option));
+            }
+        }
+        _ => {}
+    }
+    for (i, line) in lines.iter().enumerate() {
+        draw_text(
+            line,
+            x + margin,
+            y + margin + size * (i + 1) as f32,
+            size,
+            WHITE,
+        );
+    }
+}
+
+/// Splits text up into lines that fit in the text box.
+fn wrap(text: &str, width: f32, size: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let longer = if line.is_empty() {
+                word.to_string()
+            } else {
+                format!("{} {}", line, word)
+            };
+            if !line.is_empty() && measure_text(&longer, None, size as u16, 1.0).width > width {
+                lines.push(std::mem::replace(&mut line, word.to_string()));
+            } else {
+                line = longer;
+            }
+        }
+        lines.push(line);
+    }
+    lines
+}
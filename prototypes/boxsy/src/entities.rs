@@ -6,8 +6,26 @@ impl Game {
         self.draw.background_color = color;
     }
 
-    pub fn set_player(&mut self, player: Player) {
+    /// Gives a tile type, the player, or a character pixel art to be drawn with, or goes back to a square of their color with `None`.
+    pub fn set_sprite(&mut self, ent: EntID, sprite: Option<Sprite>) {
+        match sprite {
+            Some(sprite) => {
+                self.draw.sprites.insert(ent, sprite);
+            }
+            None => {
+                self.draw.sprites.remove(&ent);
+            }
+        }
+    }
+
+    /// The pixel art a tile type, the player, or a character is drawn with, if they have any.
+    pub fn get_sprite(&self, ent: EntID) -> Option<&Sprite> {
+        self.draw.sprites.get(&ent)
+    }
+
+    pub fn set_player(&mut self, mut player: Player) {
         self.draw.colors.insert(EntID::Player, player.color);
+        self.set_sprite(EntID::Player, player.sprite.take());
         self.logics.consume_player(player);
 
         self.state.player = true;
@@ -19,11 +37,7 @@ impl Game {
         for (rsrc_id, val) in character.inventory.iter() {
             self.logics.resources.items.insert(
                 PoolID::new(EntID::Character(id), rsrc_id.clone()),
-                asterism::resources::PoolValues {
-                    val: *val,
-                    min: 0,
-                    max: i16::MAX,
-                },
+                PoolValues::new(*val, 0, i16::MAX),
             );
         }
 
@@ -32,11 +46,72 @@ impl Game {
         self.draw
             .colors
             .insert(EntID::Character(id), character.color);
+        self.set_sprite(EntID::Character(id), character.sprite);
+        if let Some(dialogue) = character.dialogue {
+            self.set_dialogue(id, dialogue);
+        }
+        if let Some(behavior) = character.behavior {
+            self.events.behavior.insert(id, behavior);
+        }
 
         self.state.char_id_max += 1;
         id
     }
 
+    /// Takes a step with every character in the current room whose turn it is to move (see [Behavior]). Walls and anyone in the way are sorted out by the collision logic afterwards, so this has to come before it.
+    pub fn move_characters(&mut self) {
+        self.state.ticks += 1;
+        let room = self.get_current_room();
+        let player = self
+            .state
+            .player
+            .then(|| self.logics.collision.positions[0]);
+
+        for (i, (id, _)) in self.state.rooms[room].chars.iter().enumerate() {
+            let (behavior, every) = match self.events.behavior.get_mut(id) {
+                Some(behavior) => behavior,
+                None => continue,
+            };
+            if *every == 0 || !self.state.ticks.is_multiple_of(*every) {
+                continue;
+            }
+            let col_idx = i + 1;
+            let pos = self.logics.collision.positions[col_idx];
+            let steps = match behavior {
+                Behavior::Patrol(path) => {
+                    if path.first() == Some(&pos) {
+                        path.rotate_left(1);
+                    }
+                    path.first().map_or(Vec::new(), |to| steps_toward(pos, *to))
+                }
+                Behavior::Wander => {
+                    let directions = [IVec2::X, -IVec2::X, IVec2::Y, -IVec2::Y];
+                    vec![directions[macroquad::rand::gen_range(0, directions.len())]]
+                }
+                Behavior::Follow => player.map_or(Vec::new(), |player| steps_toward(pos, player)),
+                Behavior::Flee => player.map_or(Vec::new(), |player| {
+                    steps_toward(pos, player)
+                        .into_iter()
+                        .map(|step| -step)
+                        .collect()
+                }),
+            };
+            // go around walls if there's another way to go, and otherwise walk into them
+            let step = steps
+                .iter()
+                .find(|step| !self.logics.collision.blocked(pos + **step))
+                .or_else(|| steps.first());
+            if let Some(step) = step {
+                self.logics
+                    .collision
+                    .handle_predicate(&CollisionReaction::SetEntPos(col_idx, pos + *step));
+                self.logics
+                    .collision
+                    .handle_predicate(&CollisionReaction::SetEntVel(col_idx, *step));
+            }
+        }
+    }
+
     pub fn log_rsrc(&mut self, name: String) -> RsrcID {
         let id = RsrcID::new(self.state.rsrc_id_max, name);
         self.state.rsrc_id_max += 1;
@@ -61,18 +136,55 @@ impl Game {
                     EngineAction::MoveRoom(to.0, to.1),
                 );
             }
+            CollisionEnt::Edge(edge) => {
+                self.add_collision_predicate(
+                    (from.0, CollisionEnt::Player, CollisionEnt::Edge(edge)),
+                    EngineAction::MoveRoom(to.0, to.1),
+                );
+            }
         }
     }
 
+    /// Lets the player walk off an edge of a room (`from`) into another room (`to`), coming in the opposite side of it in the same place along the edge. Links only go one way, so the other room needs a link back.
+    ///
+    /// ```
+    /// # use boxsy::*;
+    /// let mut game = Game::new();
+    /// let west = game.add_room(8, 8);
+    /// let east = game.add_room(8, 8);
+    /// game.add_edge_link((west, Edge::East), east);
+    /// game.add_edge_link((east, Edge::West), west);
+    /// ```
+    pub fn add_edge_link(&mut self, from: (usize, Edge), to: usize) {
+        self.logics.linking.graphs[0].graph.add_edge(from.0, to);
+        self.add_collision_predicate(
+            (from.0, CollisionEnt::Player, CollisionEnt::Edge(from.1)),
+            EngineAction::MoveRoomAcross(to, from.1),
+        );
+    }
+
     pub fn set_num_rooms(&mut self, rooms: usize) {
         self.state.rooms.resize_with(rooms, Room::default);
     }
 
-    /// Loads a tilemap with maximum 10 different kinds of tiles (numbers 0-9). A space (' ') marks a place on the map without any tiles. The tile types are read from in the parameter `tiles`.
+    /// Adds a room without any tiles in it. Rooms bigger than the screen scroll to follow the player.
+    pub fn add_room(&mut self, width: usize, height: usize) -> usize {
+        let room = self.state.rooms.len();
+        self.state.rooms.push(Room::new(width, height));
+        self.logics.linking.graphs[0].add_node(room);
+        room
+    }
+
+    /// Loads a tilemap with maximum 10 different kinds of tiles (numbers 0-9). A space (' ') marks a place on the map without any tiles. The tile types are the ones logged with `log_tile_info`, in order. The room is as big as the map, which has to be a rectangle.
     ///
     /// # Example
     ///
     /// ```
+    /// # use boxsy::*;
+    /// # let mut game = Game::new();
+    /// # for _ in 0..3 {
+    /// #     game.log_tile_info(Tile::new());
+    /// # }
     /// let map = r#"0000000
     /// 0     0
     /// 0   1 0
@@ -82,49 +194,50 @@ impl Game {
     /// 0     0
     /// 0000000"#;
     ///
-    /// game.add_room_from_str(map);
+    /// let room = game.add_room_from_str(map).unwrap();
+    /// assert_eq!(game.state.rooms[room].width(), 7);
+    /// assert_eq!(game.state.rooms[room].height(), 8);
     /// ```
     pub fn add_room_from_str(&mut self, map: &str) -> Result<usize, String> {
-        let map_length = WORLD_SIZE * WORLD_SIZE + WORLD_SIZE - 1;
-        #[allow(clippy::comparison_chain)]
-        if map.len() > map_length {
-            return Err("map is too big".to_string());
-        } else if map.len() < map_length {
-            return Err("map is too small".to_string());
+        let rows: Vec<&str> = map.lines().collect();
+        let width = rows.first().map_or(0, |row| row.chars().count());
+        if width == 0 {
+            return Err("map is empty".to_string());
+        }
+        if rows.iter().any(|row| row.chars().count() != width) {
+            return Err("map rows aren't all the same length".to_string());
         }
-
-        let room = self.state.rooms.len();
-        self.state.rooms.push(Room::default());
-
-        let mut x = 0;
-        let mut y = 0;
-
         for ch in map.chars() {
-            if ch.is_ascii_digit() {
-                let tile_idx = ch.to_string().parse::<usize>().unwrap();
-                if tile_idx > self.state.tile_type_count {
+            if let Some(tile_idx) = ch.to_digit(10) {
+                if tile_idx as usize >= self.state.tile_type_count {
                     return Err(format!("tile {} not found", tile_idx));
                 }
-                self.add_tile_at_pos(TileID::new(tile_idx), room, IVec2::new(x, y));
-                x += 1;
-            } else if ch == ' ' {
-                x += 1;
-            } else if ch == '\n' {
-                y += 1;
-                x = 0;
-            } else {
+            } else if ch != ' ' && ch != '\n' {
                 return Err(format!("unrecognized character: '{}'", ch));
             }
         }
-        self.logics.linking.graphs[0].add_node(room);
 
-        Ok(self.state.rooms.len() - 1)
+        let room = self.add_room(width, rows.len());
+        for (y, row) in rows.iter().enumerate() {
+            for (x, ch) in row.chars().enumerate() {
+                if let Some(tile_idx) = ch.to_digit(10) {
+                    self.add_tile_at_pos(
+                        TileID::new(tile_idx as usize),
+                        room,
+                        IVec2::new(x as i32, y as i32),
+                    );
+                }
+            }
+        }
+
+        Ok(room)
     }
 
     pub fn log_tile_info(&mut self, tile: Tile) -> TileID {
         let id = TileID::new(self.state.tile_type_count);
         self.state.tile_type_count += 1;
         self.draw.colors.insert(EntID::Tile(id), tile.color);
+        self.set_sprite(EntID::Tile(id), tile.sprite);
 
         self.logics.collision.tile_solid.insert(id, tile.solid);
 
@@ -189,6 +302,8 @@ impl Game {
         for i in remove.iter().rev() {
             let _ = self.events.collision.remove(*i);
         }
+        self.events.dialogue.remove(&character);
+        self.events.behavior.remove(&character);
         self.state.rooms[room].chars.remove(ent_idx);
     }
 
@@ -265,20 +380,33 @@ impl Logics {
         for (id, rsrc) in player.inventory.into_iter() {
             self.resources.items.insert(
                 PoolID::new(EntID::Player, id),
-                PoolValues {
-                    val: rsrc,
-                    min: i16::MIN,
-                    max: i16::MAX,
-                },
+                PoolValues::new(rsrc, i16::MIN, i16::MAX),
             );
         }
     }
 }
 
+/// the ways to step from one place toward another, best first
+fn steps_toward(from: IVec2, to: IVec2) -> Vec<IVec2> {
+    let diff = to - from;
+    let x = IVec2::new(diff.x.signum(), 0);
+    let y = IVec2::new(0, diff.y.signum());
+    let steps = if diff.x.abs() >= diff.y.abs() {
+        [x, y]
+    } else {
+        [y, x]
+    };
+    steps
+        .iter()
+        .copied()
+        .filter(|step| *step != IVec2::ZERO)
+        .collect()
+}
+
 pub fn load_room(state: &mut State, logics: &mut Logics, room: usize) {
     logics
         .collision
-        .clear_and_resize_map(WORLD_SIZE, WORLD_SIZE);
+        .clear_and_resize_map(state.rooms[room].width(), state.rooms[room].height());
     logics.collision.clear_entities_except(ColEntType::Player);
 
     for (row, col_row) in state.rooms[room]
@@ -1,7 +1,9 @@
 #![allow(clippy::type_complexity)]
 use crate::types::*;
 use crate::*;
+use asterism::resources::TransactionOrigin;
 
+#[derive(Clone)]
 pub enum EngineAction {
     /// change resource
     ChangeResource(PoolID, Transaction<i16, PoolID>),
@@ -9,12 +11,20 @@ pub enum EngineAction {
     MoveCharacter(Option<CharacterID>, IVec2),
     /// move room-- original room, and position
     MoveRoom(usize, IVec2),
+    /// moves the player into a room (`usize`) through an edge of the current one, keeping their place along the edge
+    MoveRoomAcross(usize, Edge),
     /// adds a character in a room (`usize`)
     AddCharacter(Character, usize),
     /// adds a tile with a tile id, in a room (`usize`), and with a position
     AddTile(TileID, usize, IVec2),
+    /// removes a character from whatever room it's in
+    RemoveCharacter(CharacterID),
     MovePlayer(IVec2),
     MovePlayerBy(IVec2),
+    /// undoes the most recent resource trade, along with any reactions applied after it. What generators did in the meantime is kept
+    UndoLastTrade,
+    /// binds the player's action to the next key pressed
+    CaptureKey(ActionID),
 }
 
 impl std::fmt::Debug for EngineAction {
@@ -24,10 +34,14 @@ impl std::fmt::Debug for EngineAction {
             EngineAction::MoveTile(_, _) => write!(f, "EngineAction::MoveTile"),
             EngineAction::MoveCharacter(_, _) => write!(f, "EngineAction::MoveCharacter"),
             EngineAction::MoveRoom(_, _) => write!(f, "EngineAction::MoveRoom"),
+            EngineAction::MoveRoomAcross(_, _) => write!(f, "EngineAction::MoveRoomAcross"),
             EngineAction::AddCharacter(_, _) => write!(f, "EngineAction::AddCharacter"),
             EngineAction::AddTile(_, _, _) => write!(f, "EngineAction::AddTile"),
+            EngineAction::RemoveCharacter(_) => write!(f, "EngineAction::RemoveCharacter"),
             EngineAction::MovePlayer(_) => write!(f, "EngineAction::MovePlayer"),
             EngineAction::MovePlayerBy(_) => write!(f, "EngineAction::MovePlayerBy"),
+            EngineAction::UndoLastTrade => write!(f, "EngineAction::UndoLastTrade"),
+            EngineAction::CaptureKey(_) => write!(f, "EngineAction::CaptureKey"),
         }
     }
 }
@@ -69,12 +83,31 @@ impl EngineAction {
                     .collision
                     .handle_predicate(&CollisionReaction::SetEntPos(0, *pos));
             }
+            Self::MoveRoomAcross(to, edge) => {
+                let pos = match logics.collision.get_ident_data(ColIdent::EntIdx(0)) {
+                    TileMapColData::Ent { pos, .. } => *pos,
+                    _ => unreachable!(),
+                };
+                let width = state.rooms[*to].width() as i32;
+                let height = state.rooms[*to].height() as i32;
+                // comes in the opposite side of the next room
+                let pos = match edge {
+                    Edge::North => IVec2::new(pos.x.min(width - 1), height - 1),
+                    Edge::South => IVec2::new(pos.x.min(width - 1), 0),
+                    Edge::East => IVec2::new(0, pos.y.min(height - 1)),
+                    Edge::West => IVec2::new(width - 1, pos.y.min(height - 1)),
+                };
+                Self::MoveRoom(*to, pos).perform_action(state, logics);
+            }
             Self::AddCharacter(ch, room) => {
                 state.queue_add(Ent::Character(ch.clone(), *room));
             }
             Self::AddTile(tile_id, room, pos) => {
                 state.queue_add(Ent::TileID(*tile_id, *pos, *room))
             }
+            Self::RemoveCharacter(id) => {
+                state.queue_remove(EntID::Character(*id));
+            }
             Self::MovePlayer(pos) => {
                 logics
                     .collision
@@ -92,6 +125,25 @@ impl EngineAction {
                     .collision
                     .handle_predicate(&CollisionReaction::SetEntVel(0, *delta));
             }
+            Self::UndoLastTrade => {
+                // generators keep ticking, so only the reactions since the trade are undone
+                let since_trade = logics.resources.ledger.as_ref().and_then(|ledger| {
+                    ledger
+                        .entries
+                        .iter()
+                        .rev()
+                        .filter(|entry| entry.origin == TransactionOrigin::Reaction)
+                        .position(|entry| matches!(entry.transaction, Transaction::Trade(_, _)))
+                });
+                if let Some(n) = since_trade {
+                    logics.resources.undo_reactions(n + 1);
+                }
+            }
+            Self::CaptureKey(action) => {
+                logics
+                    .control
+                    .handle_predicate(&ControlReaction::CaptureNextKey(0, *action));
+            }
         }
     }
 }
@@ -101,6 +153,10 @@ pub struct Events {
     pub collision: Vec<(ColEvent, EngineAction)>,
     pub linking: Vec<(LinkingEvent, EngineAction)>,
     pub resource_event: Vec<(RsrcEvent, EngineAction)>,
+    /// what each character says when bumped into
+    pub dialogue: BTreeMap<CharacterID, Dialogue>,
+    /// how characters move on their own, and how many frames go by between steps. Patrols are turned as the character goes, so the position they're heading to is always first
+    pub behavior: BTreeMap<CharacterID, (Behavior, usize)>,
 }
 
 impl Events {
@@ -110,6 +166,8 @@ impl Events {
             collision: Vec::new(),
             linking: Vec::new(),
             resource_event: Vec::new(),
+            dialogue: BTreeMap::new(),
+            behavior: BTreeMap::new(),
         }
     }
 }
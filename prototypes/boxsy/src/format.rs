@@ -0,0 +1,1227 @@
+//! # The boxsy game format
+//!
+//! A whole game written out as text, so games can be made (and shared) without writing any Rust. A game is a list of blocks separated by blank lines. The first line of a block says what it is, and the lines after it fill it in. Lines starting with `#` are comments.
+//!
+//! ```text
+//! background #000000
+//!
+//! resource rock
+//!
+//! # tiles are named with a single letter or number, which is what's used in room maps
+//! tile 0
+//! solid
+//! color #e62937
+//!
+//! # tiles, the player, and characters can have pixel art at the end of their block, which is drawn instead of
+//! # their color: the palette, how many frames each frame of the animation lasts, and the pixels (see Sprite)
+//! # with a > between frames
+//! tile 1
+//! sprite #ffa100 #00000000 every 20
+//! 0101
+//! 1010
+//! 0101
+//! 1010
+//! >
+//! 1010
+//! 0101
+//! 1010
+//! 0101
+//!
+//! # a `.` is a spot in the room without a tile. Rooms can be any size, as long as they're rectangles
+//! room 0
+//! 00000000
+//! 0......0
+//! 0......0
+//! 0......0
+//! 0......0
+//! 0......0
+//! 0....0.0
+//! 00000000
+//!
+//! player
+//! room 0
+//! pos 3 3
+//! color #7026a3
+//! item rock 0
+//! key Up Up
+//! key Down Down
+//! key Left Left
+//! key Right Right
+//!
+//! character 0
+//! room 0
+//! pos 1 2
+//! color #7f6a4f
+//! item rock 2
+//!
+//! # what the character says when the player bumps into them. `ask` gives the player a choice, with
+//! # what comes after each `option` until the next one, or the `end`. Dialogue can `do` actions too
+//! character 1
+//! room 0
+//! pos 6 5
+//! # characters can move on their own, every so many frames: `patrol` between positions, `wander`, `follow` the player, or `flee` from them
+//! move every 30 patrol 6 5 6 1
+//! say hello!
+//! ask want a rock?
+//!   option yes
+//!     say here you go
+//!     do change character 0 rock trade 1 player rock
+//!   option no
+//!     say ok
+//! end
+//!
+//! # bumping into the character (or a tile, like `tile 5 6`) moves the player to room 0 at (3, 1)
+//! link 0 character 0 to 0 3 1
+//!
+//! # walking off the east side of the room comes back in the west side
+//! link 0 edge east to 0
+//!
+//! on collide 0 player character 0
+//! do change character 0 rock trade 1 player rock
+//!
+//! on key Up pressed
+//! do move_player_by 0 -1
+//! ```
+//!
+//! Rules are `on` an event and `do` one or more actions:
+//!
+//! - `on key <action> <pressed|released|held|unheld|rebound|axis_changed>`
+//! - `on collide <room> <thing> <thing>`, where a thing is `player`, `character <name>`, `tile <x> <y>`, or `edge <north|south|east|west>`
+//! - `on enter <room> from <room>`, or `on activate <room>`
+//! - `on resource <pool> <transaction> <updated|failed <why>|reached_max|reached_min|rolled_back|crossed <value> <rising|falling>>`, where a pool is `player <resource>` or `character <name> <resource>`, and a transaction is `change`, `set`, `set_max` or `set_min` and a value, or `trade <value> <pool>`
+//!
+//! And the actions are `change <pool> <transaction>`, `move_tile <x> <y> <x> <y>`, `move_character <name|none> <x> <y>`, `move_room <room> <x> <y>`, `move_room_across <room> <edge>`, `add_character <room> <x> <y> <color> <resource> <value>...`, `add_tile <tile> <room> <x> <y>`, `remove_character <name>`, `move_player <x> <y>`, `move_player_by <x> <y>`, `undo_last_trade`, and `capture_key <action>`.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::str::{FromStr, SplitWhitespace};
+
+use asterism::control::{BindingName, ControlEvent};
+use asterism::resources::{ResourceError, ThresholdDirection};
+
+use crate::*;
+
+/// the names tiles get when a game is written out, in order
+const TILE_NAMES: &str = "0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+impl Game {
+    /// Reads a game written in the boxsy format (see the [crate::format] module).
+    pub fn from_text(text: &str) -> Result<Self, String> {
+        Loader::new().load(text)
+    }
+
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path.as_ref())
+            .map_err(|e| format!("couldn't read game: {}", e))?;
+        Self::from_text(&text)
+    }
+
+    /// Writes the game out in the boxsy format. Rooms are written the way they're stored in `state`, so a game that's running is written out as of the last time the player left the room they're in.
+    pub fn to_text(&self) -> Result<String, String> {
+        let mut out = String::new();
+        writeln!(out, "background {}", color_text(self.draw.background_color)).unwrap();
+
+        if !self.state.resources.is_empty() {
+            out.push('\n');
+            for rsrc in self.state.resources.iter() {
+                writeln!(out, "resource {}", rsrc.name()).unwrap();
+            }
+        }
+
+        for idx in 0..self.state.tile_type_count {
+            let id = TileID::new(idx);
+            writeln!(out, "\ntile {}", tile_name(id)?).unwrap();
+            if self.logics.collision.tile_solid.get(&id) == Some(&true) {
+                out.push_str("solid\n");
+            }
+            if let Some(color) = self.draw.colors.get(&EntID::Tile(id)) {
+                writeln!(out, "color {}", color_text(*color)).unwrap();
+            }
+            self.write_sprite(&mut out, EntID::Tile(id))?;
+        }
+
+        for (i, room) in self.state.rooms.iter().enumerate() {
+            writeln!(out, "\nroom {}", i).unwrap();
+            for row in room.map.iter() {
+                for tile in row.iter() {
+                    match tile {
+                        Some(id) => out.push(tile_name(*id)?),
+                        None => out.push('.'),
+                    }
+                }
+                out.push('\n');
+            }
+        }
+
+        if self.state.player {
+            out.push_str("\nplayer\n");
+            writeln!(out, "room {}", self.get_current_room()).unwrap();
+            let pos = self.logics.collision.positions[0];
+            writeln!(out, "pos {} {}", pos.x, pos.y).unwrap();
+            if let Some(color) = self.draw.colors.get(&EntID::Player) {
+                writeln!(out, "color {}", color_text(*color)).unwrap();
+            }
+            self.write_items(&mut out, EntID::Player);
+            if let Some(map) = self.logics.control.mapping.first() {
+                for action in map.iter() {
+                    write!(
+                        out,
+                        "key {} {}",
+                        action.id.binding_name(),
+                        action.get_keycode().binding_name()
+                    )
+                    .unwrap();
+                    out.push_str(if action.is_valid { "\n" } else { " off\n" });
+                }
+            }
+            self.write_sprite(&mut out, EntID::Player)?;
+        }
+
+        let mut chars = Vec::new();
+        for (i, room) in self.state.rooms.iter().enumerate() {
+            chars.extend(room.chars.iter().map(|(id, pos)| (*id, i, *pos)));
+        }
+        chars.sort_by_key(|(id, ..)| *id);
+        for (id, room, pos) in chars {
+            writeln!(out, "\ncharacter {}", id.idx()).unwrap();
+            writeln!(out, "room {}", room).unwrap();
+            writeln!(out, "pos {} {}", pos.x, pos.y).unwrap();
+            if let Some(color) = self.draw.colors.get(&EntID::Character(id)) {
+                writeln!(out, "color {}", color_text(*color)).unwrap();
+            }
+            self.write_items(&mut out, EntID::Character(id));
+            if let Some((behavior, every)) = self.events.behavior.get(&id) {
+                write!(out, "move every {} ", every).unwrap();
+                match behavior {
+                    Behavior::Patrol(path) => {
+                        out.push_str("patrol");
+                        for pos in path.iter() {
+                            write!(out, " {} {}", pos.x, pos.y).unwrap();
+                        }
+                        out.push('\n');
+                    }
+                    Behavior::Wander => out.push_str("wander\n"),
+                    Behavior::Follow => out.push_str("follow\n"),
+                    Behavior::Flee => out.push_str("flee\n"),
+                }
+            }
+            if let Some(dialogue) = self.events.dialogue.get(&id) {
+                self.write_dialogue(&mut out, dialogue, 0)?;
+            }
+            self.write_sprite(&mut out, EntID::Character(id))?;
+        }
+
+        self.write_rules(&mut out, &self.events.control, |_, event| {
+            Ok(format!(
+                "key {} {}",
+                event.action_id.binding_name(),
+                ctrl_event_text(event.event_type)
+            ))
+        })?;
+
+        let graph = &self.logics.linking.graphs[0];
+        let mut last = None;
+        for (event, action) in self.events.collision.iter() {
+            // a link is a collision with the player that moves rooms along an edge in the room graph
+            let link = match (event, action) {
+                ((room, CollisionEnt::Player, ent), EngineAction::MoveRoom(to, pos))
+                    if *ent != CollisionEnt::Player =>
+                {
+                    Some((room, ent, to, format!(" {} {}", pos.x, pos.y)))
+                }
+                (
+                    (room, CollisionEnt::Player, ent @ CollisionEnt::Edge(edge)),
+                    EngineAction::MoveRoomAcross(to, across),
+                ) if edge == across => Some((room, ent, to, String::new())),
+                _ => None,
+            };
+            if let Some((room, ent, to, pos)) = link {
+                let is_edge = match (graph.graph.node_idx(room), graph.graph.node_idx(to)) {
+                    (Some(from), Some(to)) => graph.graph.edges[from][to],
+                    _ => false,
+                };
+                if is_edge {
+                    writeln!(out, "\nlink {} {} to {}{}", room, ent_text(*ent), to, pos).unwrap();
+                    last = None;
+                    continue;
+                }
+            }
+            if last != Some(event) {
+                writeln!(
+                    out,
+                    "\non collide {} {} {}",
+                    event.0,
+                    ent_text(event.1),
+                    ent_text(event.2)
+                )
+                .unwrap();
+                last = Some(event);
+            }
+            writeln!(out, "do {}", self.action_text(action)?).unwrap();
+        }
+
+        self.write_rules(&mut out, &self.events.linking, |game, event| {
+            let room = |node: usize| game.logics.linking.graphs[event.graph].graph.nodes[node];
+            Ok(match event.event_type {
+                LinkingEventType::Activated => format!("activate {}", room(event.node)),
+                LinkingEventType::Traversed(from) => {
+                    format!("enter {} from {}", room(event.node), room(from))
+                }
+            })
+        })?;
+
+        self.write_rules(&mut out, &self.events.resource_event, |game, event| {
+            Ok(format!(
+                "resource {} {} {}",
+                pool_text(&event.pool),
+                game.transaction_text(&event.transaction),
+                rsrc_event_text(&event.event_type)
+            ))
+        })?;
+
+        Ok(out)
+    }
+
+    fn write_items(&self, out: &mut String, ent: EntID) {
+        for (pool, values) in self.logics.resources.items.iter() {
+            if pool.attached_to == ent {
+                writeln!(out, "item {} {}", pool.rsrc.name(), values.val).unwrap();
+            }
+        }
+    }
+
+    fn write_sprite(&self, out: &mut String, ent: EntID) -> Result<(), String> {
+        let sprite = match self.draw.sprites.get(&ent) {
+            Some(sprite) => sprite,
+            None => return Ok(()),
+        };
+        out.push_str("sprite");
+        for color in sprite.palette.iter() {
+            write!(out, " {}", color_text(*color)).unwrap();
+        }
+        if sprite.frames.len() > 1 {
+            write!(out, " every {}", sprite.frame_time).unwrap();
+        }
+        out.push('\n');
+        for row in sprite.to_grid()?.lines() {
+            // frames are separated by a blank line, which would end the block
+            writeln!(out, "{}", if row.is_empty() { ">" } else { row }).unwrap();
+        }
+        Ok(())
+    }
+
+    fn write_dialogue(
+        &self,
+        out: &mut String,
+        dialogue: &Dialogue,
+        indent: usize,
+    ) -> Result<(), String> {
+        let pad = " ".repeat(indent);
+        match dialogue {
+            Dialogue::Line(text) => {
+                if text.is_empty() {
+                    writeln!(out, "{}say", pad).unwrap();
+                }
+                for line in text.lines() {
+                    writeln!(out, "{}say {}", pad, line).unwrap();
+                }
+            }
+            Dialogue::Sequence(dialogue) => {
+                for dialogue in dialogue.iter() {
+                    self.write_dialogue(out, dialogue, indent)?;
+                }
+            }
+            Dialogue::Choice(question, options) => {
+                writeln!(out, "{}ask {}", pad, question).unwrap();
+                for (option, dialogue) in options.iter() {
+                    writeln!(out, "{}  option {}", pad, option).unwrap();
+                    self.write_dialogue(out, dialogue, indent + 4)?;
+                }
+                writeln!(out, "{}end", pad).unwrap();
+            }
+            Dialogue::Action(action) => {
+                writeln!(out, "{}do {}", pad, self.action_text(action)?).unwrap();
+            }
+        }
+        Ok(())
+    }
+
+    /// writes out a list of predicates, putting consecutive actions for the same event under one `on`
+    fn write_rules<E: PartialEq>(
+        &self,
+        out: &mut String,
+        rules: &[(E, EngineAction)],
+        event_text: impl Fn(&Self, &E) -> Result<String, String>,
+    ) -> Result<(), String> {
+        let mut last = None;
+        for (event, action) in rules.iter() {
+            if last != Some(event) {
+                writeln!(out, "\non {}", event_text(self, event)?).unwrap();
+                last = Some(event);
+            }
+            writeln!(out, "do {}", self.action_text(action)?).unwrap();
+        }
+        Ok(())
+    }
+
+    fn action_text(&self, action: &EngineAction) -> Result<String, String> {
+        Ok(match action {
+            EngineAction::ChangeResource(pool, transaction) => format!(
+                "change {} {}",
+                pool_text(pool),
+                self.transaction_text(transaction)
+            ),
+            EngineAction::MoveTile(from, to) => {
+                format!("move_tile {} {} {} {}", from.x, from.y, to.x, to.y)
+            }
+            EngineAction::MoveCharacter(id, pos) => format!(
+                "move_character {} {} {}",
+                id.map(|id| id.idx().to_string())
+                    .unwrap_or_else(|| "none".to_string()),
+                pos.x,
+                pos.y
+            ),
+            EngineAction::MoveRoom(room, pos) => format!("move_room {} {} {}", room, pos.x, pos.y),
+            EngineAction::MoveRoomAcross(room, edge) => {
+                format!("move_room_across {} {}", room, edge_text(*edge))
+            }
+            EngineAction::AddCharacter(character, room) => {
+                let mut text = format!(
+                    "add_character {} {} {} {}",
+                    room,
+                    character.pos.x,
+                    character.pos.y,
+                    color_text(character.color)
+                );
+                for (rsrc, val) in character.inventory.iter() {
+                    write!(text, " {} {}", rsrc.name(), val).unwrap();
+                }
+                text
+            }
+            EngineAction::AddTile(tile, room, pos) => {
+                format!(
+                    "add_tile {} {} {} {}",
+                    tile_name(*tile)?,
+                    room,
+                    pos.x,
+                    pos.y
+                )
+            }
+            EngineAction::RemoveCharacter(id) => format!("remove_character {}", id.idx()),
+            EngineAction::MovePlayer(pos) => format!("move_player {} {}", pos.x, pos.y),
+            EngineAction::MovePlayerBy(delta) => format!("move_player_by {} {}", delta.x, delta.y),
+            EngineAction::UndoLastTrade => "undo_last_trade".to_string(),
+            EngineAction::CaptureKey(action) => format!("capture_key {}", action.binding_name()),
+        })
+    }
+
+    fn transaction_text(&self, transaction: &Transaction<i16, PoolID>) -> String {
+        match transaction {
+            Transaction::Change(val) => format!("change {}", val),
+            Transaction::Set(val) => format!("set {}", val),
+            Transaction::SetMax(val) => format!("set_max {}", val),
+            Transaction::SetMin(val) => format!("set_min {}", val),
+            Transaction::Trade(val, other) => format!("trade {} {}", val, pool_text(other)),
+        }
+    }
+}
+
+fn tile_name(id: TileID) -> Result<char, String> {
+    TILE_NAMES
+        .chars()
+        .nth(id.idx())
+        .ok_or_else(|| format!("only {} kinds of tiles can be written", TILE_NAMES.len()))
+}
+
+fn color_text(color: Color) -> String {
+    // rounded, so colors come back the same after being read
+    let [r, g, b, a] = [color.r, color.g, color.b, color.a].map(|c| (c * 255.0).round() as u8);
+    if a == 255 {
+        format!("#{:02x}{:02x}{:02x}", r, g, b)
+    } else {
+        format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, a)
+    }
+}
+
+fn ent_text(ent: CollisionEnt) -> String {
+    match ent {
+        CollisionEnt::Player => "player".to_string(),
+        CollisionEnt::Character(id) => format!("character {}", id.idx()),
+        CollisionEnt::Tile(pos) => format!("tile {} {}", pos.x, pos.y),
+        CollisionEnt::Edge(edge) => format!("edge {}", edge_text(edge)),
+    }
+}
+
+fn edge_text(edge: Edge) -> &'static str {
+    match edge {
+        Edge::North => "north",
+        Edge::South => "south",
+        Edge::East => "east",
+        Edge::West => "west",
+    }
+}
+
+fn pool_text(pool: &PoolID) -> String {
+    match pool.attached_to {
+        EntID::Player => format!("player {}", pool.rsrc.name()),
+        EntID::Character(id) => format!("character {} {}", id.idx(), pool.rsrc.name()),
+        // boxsy never gives tiles resources
+        EntID::Tile(id) => format!("tile {} {}", id.idx(), pool.rsrc.name()),
+    }
+}
+
+fn ctrl_event_text(event_type: ControlEventType) -> &'static str {
+    match event_type {
+        ControlEventType::KeyPressed => "pressed",
+        ControlEventType::KeyReleased => "released",
+        ControlEventType::KeyHeld => "held",
+        ControlEventType::KeyUnheld => "unheld",
+        ControlEventType::Rebound => "rebound",
+        ControlEventType::AxisChanged => "axis_changed",
+    }
+}
+
+fn rsrc_event_text(event_type: &ResourceEventType<i16>) -> String {
+    match event_type {
+        ResourceEventType::PoolUpdated => "updated".to_string(),
+        ResourceEventType::TransactionUnsuccessful(err) => format!(
+            "failed {}",
+            match err {
+                ResourceError::PoolNotFound => "not_found",
+                ResourceError::TooBig => "too_big",
+                ResourceError::TooSmall => "too_small",
+                ResourceError::Overflow => "overflow",
+            }
+        ),
+        ResourceEventType::ReachedMax => "reached_max".to_string(),
+        ResourceEventType::ReachedMin => "reached_min".to_string(),
+        ResourceEventType::RolledBack => "rolled_back".to_string(),
+        ResourceEventType::CrossedThreshold(val, dir) => format!(
+            "crossed {} {}",
+            val,
+            match dir {
+                ThresholdDirection::Rising => "rising",
+                ThresholdDirection::Falling => "falling",
+            }
+        ),
+    }
+}
+
+/// the words on one line of a game
+struct Words<'a> {
+    words: SplitWhitespace<'a>,
+    line: usize,
+}
+
+impl<'a> Words<'a> {
+    fn new(line: usize, text: &'a str) -> Self {
+        Self {
+            words: text.split_whitespace(),
+            line,
+        }
+    }
+
+    fn err(&self, what: impl std::fmt::Display) -> String {
+        format!("line {}: {}", self.line, what)
+    }
+
+    fn next(&mut self, what: &str) -> Result<&'a str, String> {
+        let line = self.line;
+        self.words
+            .next()
+            .ok_or_else(|| format!("line {}: expected {}", line, what))
+    }
+
+    fn num<T: FromStr>(&mut self, what: &str) -> Result<T, String> {
+        let word = self.next(what)?;
+        word.parse()
+            .map_err(|_| self.err(format!("{} isn't {}", word, what)))
+    }
+
+    fn pos(&mut self) -> Result<IVec2, String> {
+        Ok(IVec2::new(
+            self.num("an x position")?,
+            self.num("a y position")?,
+        ))
+    }
+
+    fn color(&mut self) -> Result<Color, String> {
+        let word = self.next("a color")?;
+        let hex = word
+            .strip_prefix('#')
+            .filter(|hex| hex.len() == 6 || hex.len() == 8)
+            .and_then(|hex| u32::from_str_radix(hex, 16).ok().map(|val| (hex, val)));
+        match hex {
+            Some((hex, val)) if hex.len() == 6 => Ok(Color::from_hex(val)),
+            Some((_, val)) => {
+                let [r, g, b, a] = val.to_be_bytes();
+                Ok(Color::from_rgba(r, g, b, a))
+            }
+            None => Err(self.err(format!("{} isn't a color like #ff0000", word))),
+        }
+    }
+
+    fn peek(&self) -> Option<&'a str> {
+        self.words.clone().next()
+    }
+
+    fn done(&self) -> bool {
+        self.peek().is_none()
+    }
+
+    fn end(&mut self) -> Result<(), String> {
+        match self.words.next() {
+            Some(word) => Err(self.err(format!("didn't expect {}", word))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// reads the pixel art at the end of a block, starting at its `sprite` line
+fn sprite(mut words: Words, rows: &[(usize, &str)]) -> Result<Sprite, String> {
+    let mut palette = Vec::new();
+    while words.peek().is_some_and(|word| word.starts_with('#')) {
+        palette.push(words.color()?);
+    }
+    let frame_time = match words.peek() {
+        Some("every") => {
+            words.next("every")?;
+            Some(words.num("a number of frames")?)
+        }
+        _ => None,
+    };
+    words.end()?;
+
+    let mut frames = vec![Vec::new()];
+    for (_, row) in rows.iter() {
+        if *row == ">" {
+            frames.push(Vec::new());
+        } else {
+            frames.last_mut().unwrap().push(*row);
+        }
+    }
+    let mut sprite = Sprite::from_rows(palette, &frames).map_err(|e| words.err(e))?;
+    if let Some(frame_time) = frame_time {
+        sprite.frame_time = frame_time;
+    }
+    Ok(sprite)
+}
+
+/// everything on a line after its first word
+fn rest_of_line(text: &str) -> &str {
+    text.split_once(char::is_whitespace)
+        .map_or("", |(_, rest)| rest.trim())
+}
+
+/// dialogue one after another, or just the one if there's only one
+fn sequence(mut dialogue: Vec<Dialogue>) -> Dialogue {
+    if dialogue.len() == 1 {
+        dialogue.pop().unwrap()
+    } else {
+        Dialogue::Sequence(dialogue)
+    }
+}
+
+fn edge(words: &mut Words) -> Result<Edge, String> {
+    match words.next("an edge")? {
+        "north" => Ok(Edge::North),
+        "south" => Ok(Edge::South),
+        "east" => Ok(Edge::East),
+        "west" => Ok(Edge::West),
+        word => Err(words.err(format!(
+            "expected north, south, east, or west, not {}",
+            word
+        ))),
+    }
+}
+
+enum RuleEvent {
+    Control(CtrlEvent),
+    Collision(ColEvent),
+    Linking(LinkingEvent),
+    Resource(RsrcEvent),
+}
+
+/// builds a game while reading it, keeping track of what everything's named
+struct Loader {
+    game: Game,
+    tiles: BTreeMap<char, TileID>,
+    rooms: BTreeMap<String, usize>,
+    chars: BTreeMap<String, CharacterID>,
+    rsrcs: BTreeMap<String, RsrcID>,
+    /// characters' dialogue lines, which are read once everyone's been named, since they can do things to characters further down
+    dialogue: Vec<(CharacterID, Vec<(usize, String)>)>,
+}
+
+impl Loader {
+    fn new() -> Self {
+        Self {
+            game: Game::new(),
+            tiles: BTreeMap::new(),
+            rooms: BTreeMap::new(),
+            chars: BTreeMap::new(),
+            rsrcs: BTreeMap::new(),
+            dialogue: Vec::new(),
+        }
+    }
+
+    fn load(mut self, text: &str) -> Result<Game, String> {
+        let mut blocks = vec![Vec::new()];
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.starts_with('#') {
+                continue;
+            }
+            if line.is_empty() {
+                blocks.push(Vec::new());
+            } else {
+                blocks.last_mut().unwrap().push((i + 1, line));
+            }
+        }
+
+        for block in blocks.iter().filter(|block| !block.is_empty()) {
+            let (line, header) = block[0];
+            let mut words = Words::new(line, header);
+            match words.next("something")? {
+                "tile" => self.tile(words, &block[1..])?,
+                "room" => self.room(words, &block[1..])?,
+                "player" => self.player(words, &block[1..])?,
+                "character" => self.character(words, &block[1..])?,
+                "on" => self.rule(words, &block[1..])?,
+                // everything else is one line each
+                _ => {
+                    for (line, text) in block.iter() {
+                        self.statement(Words::new(*line, text))?;
+                    }
+                }
+            }
+        }
+
+        for (id, lines) in std::mem::take(&mut self.dialogue) {
+            let lines: Vec<(usize, &str)> = lines
+                .iter()
+                .map(|(line, text)| (*line, text.as_str()))
+                .collect();
+            let mut rest = &lines[..];
+            let dialogue = self.dialogue(&mut rest)?;
+            if let Some((line, text)) = rest.first() {
+                let mut words = Words::new(*line, text);
+                let word = words.next("dialogue")?;
+                return Err(words.err(format!("{} without an ask", word)));
+            }
+            self.game.set_dialogue(id, sequence(dialogue));
+        }
+        Ok(self.game)
+    }
+
+    fn statement(&mut self, mut words: Words) -> Result<(), String> {
+        match words.next("something")? {
+            "background" => {
+                let color = words.color()?;
+                self.game.set_background(color);
+            }
+            "resource" => {
+                let name = words.next("a resource name")?;
+                if self.rsrcs.contains_key(name) {
+                    return Err(words.err(format!("there's already a resource named {}", name)));
+                }
+                let id = self.game.log_rsrc(name.to_string());
+                self.rsrcs.insert(name.to_string(), id);
+            }
+            "link" => {
+                let room = self.room_name(&mut words)?;
+                let ent = self.ent(&mut words)?;
+                if ent == CollisionEnt::Player {
+                    return Err(words.err("the player can't be linked"));
+                }
+                if words.next("to")? != "to" {
+                    return Err(words.err("expected to"));
+                }
+                let to = self.room_name(&mut words)?;
+                match ent {
+                    // without a position, the player keeps their place along the edge
+                    CollisionEnt::Edge(edge) if words.done() => {
+                        self.game.add_edge_link((room, edge), to)
+                    }
+                    _ => self.game.add_link((room, ent), (to, words.pos()?)),
+                }
+            }
+            word => return Err(words.err(format!("didn't expect {}", word))),
+        }
+        words.end()
+    }
+
+    fn tile(&mut self, mut words: Words, lines: &[(usize, &str)]) -> Result<(), String> {
+        let name = words.next("a tile name")?;
+        let mut chars = name.chars();
+        let name = match (chars.next(), chars.next()) {
+            (Some(ch), None) if ch.is_ascii_alphanumeric() => ch,
+            _ => return Err(words.err("tiles are named with one letter or number")),
+        };
+        if self.tiles.contains_key(&name) {
+            return Err(words.err(format!("there's already a tile named {}", name)));
+        }
+        words.end()?;
+
+        let mut tile = Tile::new();
+        for (i, (line, text)) in lines.iter().enumerate() {
+            let mut words = Words::new(*line, text);
+            match words.next("a tile property")? {
+                "sprite" => {
+                    tile.sprite = Some(sprite(words, &lines[i + 1..])?);
+                    break;
+                }
+                "solid" => tile.solid = true,
+                "color" => tile.color = words.color()?,
+                word => return Err(words.err(format!("tiles don't have a {}", word))),
+            }
+            words.end()?;
+        }
+        let id = self.game.log_tile_info(tile);
+        self.tiles.insert(name, id);
+        Ok(())
+    }
+
+    fn room(&mut self, mut words: Words, lines: &[(usize, &str)]) -> Result<(), String> {
+        let name = words.next("a room name")?;
+        if self.rooms.contains_key(name) {
+            return Err(words.err(format!("there's already a room named {}", name)));
+        }
+        words.end()?;
+        let width = match lines.first() {
+            Some((_, row)) => row.chars().count(),
+            None => return Err(words.err("rooms need at least one row of tiles")),
+        };
+
+        let room = self.game.add_room(width, lines.len());
+        for (y, (line, text)) in lines.iter().enumerate() {
+            if text.chars().count() != width {
+                return Err(format!(
+                    "line {}: every row of a room has to be {} tiles wide",
+                    line, width
+                ));
+            }
+            for (x, ch) in text.chars().enumerate() {
+                if ch == '.' {
+                    continue;
+                }
+                let tile = *self
+                    .tiles
+                    .get(&ch)
+                    .ok_or_else(|| format!("line {}: no tile named {}", line, ch))?;
+                self.game
+                    .add_tile_at_pos(tile, room, IVec2::new(x as i32, y as i32));
+            }
+        }
+        self.rooms.insert(name.to_string(), room);
+        Ok(())
+    }
+
+    fn player(&mut self, mut words: Words, lines: &[(usize, &str)]) -> Result<(), String> {
+        words.end()?;
+        if self.game.state.player {
+            return Err(words.err("there's already a player"));
+        }
+
+        let mut player = Player::new();
+        let mut room = None;
+        let mut controls = Vec::new();
+        for (i, (line, text)) in lines.iter().enumerate() {
+            let mut words = Words::new(*line, text);
+            match words.next("a player property")? {
+                "sprite" => {
+                    player.sprite = Some(sprite(words, &lines[i + 1..])?);
+                    break;
+                }
+                "room" => room = Some(self.room_name(&mut words)?),
+                "pos" => player.pos = words.pos()?,
+                "color" => player.color = words.color()?,
+                "item" => {
+                    let rsrc = self.rsrc(&mut words)?;
+                    player.add_inventory_item(rsrc, words.num("an amount")?);
+                }
+                "key" => {
+                    let action = words.next("an action")?;
+                    let action = ActionID::from_binding_name(action)
+                        .ok_or_else(|| words.err(format!("unknown action {}", action)))?;
+                    let key = words.next("a key")?;
+                    let key = KeyCode::from_binding_name(key)
+                        .ok_or_else(|| words.err(format!("unknown key {}", key)))?;
+                    let valid = match words.next("").ok() {
+                        None => true,
+                        Some("off") => false,
+                        Some(word) => return Err(words.err(format!("didn't expect {}", word))),
+                    };
+                    controls.push((action, key, valid));
+                }
+                word => return Err(words.err(format!("the player doesn't have a {}", word))),
+            }
+            words.end()?;
+        }
+        // without any keys the player keeps the arrow keys
+        if !controls.is_empty() {
+            player.controls = controls;
+        }
+        self.game.set_player(player);
+
+        if let Some(room) = room {
+            let graph = &mut self.game.logics.linking.graphs[0];
+            let node = graph.graph.node_idx(&room).unwrap();
+            graph.set_current_node(node);
+        }
+        Ok(())
+    }
+
+    fn character(&mut self, mut words: Words, lines: &[(usize, &str)]) -> Result<(), String> {
+        let name = words.next("a character name")?;
+        if self.chars.contains_key(name) {
+            return Err(words.err(format!("there's already a character named {}", name)));
+        }
+        words.end()?;
+
+        let mut character = Character::new();
+        let mut room = None;
+        let mut dialogue = Vec::new();
+        for (i, (line, text)) in lines.iter().enumerate() {
+            let mut words = Words::new(*line, text);
+            match words.next("a character property")? {
+                "sprite" => {
+                    character.sprite = Some(sprite(words, &lines[i + 1..])?);
+                    break;
+                }
+                "say" | "ask" | "option" | "end" | "do" => {
+                    dialogue.push((*line, text.to_string()));
+                    continue;
+                }
+                "room" => room = Some(self.room_name(&mut words)?),
+                "pos" => character.pos = words.pos()?,
+                "color" => character.color = words.color()?,
+                "item" => {
+                    let rsrc = self.rsrc(&mut words)?;
+                    character.add_inventory_item(rsrc, words.num("an amount")?);
+                }
+                "move" => {
+                    if words.next("every")? != "every" {
+                        return Err(words.err("expected every"));
+                    }
+                    let every = words.num("a number of frames")?;
+                    let behavior = match words.next("how they move")? {
+                        "patrol" => {
+                            let mut path = Vec::new();
+                            while !words.done() {
+                                path.push(words.pos()?);
+                            }
+                            Behavior::Patrol(path)
+                        }
+                        "wander" => Behavior::Wander,
+                        "follow" => Behavior::Follow,
+                        "flee" => Behavior::Flee,
+                        word => {
+                            return Err(words.err(format!(
+                                "expected patrol, wander, follow, or flee, not {}",
+                                word
+                            )))
+                        }
+                    };
+                    character.behavior = Some((behavior, every));
+                }
+                word => return Err(words.err(format!("characters don't have a {}", word))),
+            }
+            words.end()?;
+        }
+        let room = room.ok_or_else(|| words.err(format!("character {} isn't in a room", name)))?;
+        let id = self.game.add_character(character, room);
+        self.chars.insert(name.to_string(), id);
+        if !dialogue.is_empty() {
+            self.dialogue.push((id, dialogue));
+        }
+        Ok(())
+    }
+
+    /// reads dialogue lines until running out, or reaching an `option` or `end` that belongs to an `ask` further up
+    fn dialogue(&mut self, lines: &mut &[(usize, &str)]) -> Result<Vec<Dialogue>, String> {
+        let mut dialogue = Vec::new();
+        while let Some(((line, text), rest)) = lines.split_first() {
+            let mut words = Words::new(*line, text);
+            match words.next("dialogue")? {
+                "say" => dialogue.push(Dialogue::Line(rest_of_line(text).to_string())),
+                "do" => {
+                    let action = self.action(&mut words)?;
+                    words.end()?;
+                    dialogue.push(Dialogue::Action(Box::new(action)));
+                }
+                "ask" => {
+                    *lines = rest;
+                    let mut options = Vec::new();
+                    loop {
+                        let ((line, option), rest) = lines
+                            .split_first()
+                            .ok_or_else(|| words.err("ask without an end"))?;
+                        let mut words = Words::new(*line, option);
+                        *lines = rest;
+                        match words.next("option or end")? {
+                            "option" => {
+                                let after = self.dialogue(lines)?;
+                                options.push((rest_of_line(option).to_string(), sequence(after)));
+                            }
+                            "end" => {
+                                words.end()?;
+                                break;
+                            }
+                            word => {
+                                return Err(
+                                    words.err(format!("expected option or end, not {}", word))
+                                )
+                            }
+                        }
+                    }
+                    dialogue.push(Dialogue::Choice(rest_of_line(text).to_string(), options));
+                    continue;
+                }
+                _ => return Ok(dialogue),
+            }
+            *lines = rest;
+        }
+        Ok(dialogue)
+    }
+
+    fn rule(&mut self, mut words: Words, lines: &[(usize, &str)]) -> Result<(), String> {
+        let event = match words.next("an event")? {
+            "key" => {
+                let action = words.next("an action")?;
+                let action_id = ActionID::from_binding_name(action)
+                    .ok_or_else(|| words.err(format!("unknown action {}", action)))?;
+                let event_type = match words.next("a key event")? {
+                    "pressed" => ControlEventType::KeyPressed,
+                    "released" => ControlEventType::KeyReleased,
+                    "held" => ControlEventType::KeyHeld,
+                    "unheld" => ControlEventType::KeyUnheld,
+                    "rebound" => ControlEventType::Rebound,
+                    "axis_changed" => ControlEventType::AxisChanged,
+                    word => return Err(words.err(format!("unknown key event {}", word))),
+                };
+                RuleEvent::Control(ControlEvent {
+                    event_type,
+                    action_id,
+                    set: 0,
+                })
+            }
+            "collide" => {
+                let room = self.room_name(&mut words)?;
+                let ent1 = self.ent(&mut words)?;
+                let ent2 = self.ent(&mut words)?;
+                RuleEvent::Collision((room, ent1, ent2))
+            }
+            "enter" => {
+                let to = self.room_node(&mut words)?;
+                if words.next("from")? != "from" {
+                    return Err(words.err("expected from"));
+                }
+                let from = self.room_node(&mut words)?;
+                RuleEvent::Linking(LinkingEvent {
+                    graph: 0,
+                    node: to,
+                    event_type: LinkingEventType::Traversed(from),
+                })
+            }
+            "activate" => RuleEvent::Linking(LinkingEvent {
+                graph: 0,
+                node: self.room_node(&mut words)?,
+                event_type: LinkingEventType::Activated,
+            }),
+            "resource" => {
+                let pool = self.pool(&mut words)?;
+                let transaction = self.transaction(&mut words)?;
+                let event_type = match words.next("a resource event")? {
+                    "updated" => ResourceEventType::PoolUpdated,
+                    "failed" => ResourceEventType::TransactionUnsuccessful(
+                        match words.next("why it failed")? {
+                            "not_found" => ResourceError::PoolNotFound,
+                            "too_big" => ResourceError::TooBig,
+                            "too_small" => ResourceError::TooSmall,
+                            "overflow" => ResourceError::Overflow,
+                            word => return Err(words.err(format!("unknown failure {}", word))),
+                        },
+                    ),
+                    "reached_max" => ResourceEventType::ReachedMax,
+                    "reached_min" => ResourceEventType::ReachedMin,
+                    "rolled_back" => ResourceEventType::RolledBack,
+                    "crossed" => ResourceEventType::CrossedThreshold(
+                        words.num("a threshold")?,
+                        match words.next("rising or falling")? {
+                            "rising" => ThresholdDirection::Rising,
+                            "falling" => ThresholdDirection::Falling,
+                            word => {
+                                return Err(
+                                    words.err(format!("expected rising or falling, not {}", word))
+                                )
+                            }
+                        },
+                    ),
+                    word => return Err(words.err(format!("unknown resource event {}", word))),
+                };
+                RuleEvent::Resource(RsrcEvent {
+                    pool,
+                    transaction,
+                    event_type,
+                })
+            }
+            word => return Err(words.err(format!("unknown event {}", word))),
+        };
+        words.end()?;
+
+        if lines.is_empty() {
+            return Err(words.err("a rule has to do something"));
+        }
+        for (line, text) in lines.iter() {
+            let mut words = Words::new(*line, text);
+            if words.next("do")? != "do" {
+                return Err(words.err("expected do"));
+            }
+            let action = self.action(&mut words)?;
+            words.end()?;
+            match &event {
+                RuleEvent::Control(event) => self.game.events.control.push((*event, action)),
+                RuleEvent::Collision(event) => self.game.add_collision_predicate(*event, action),
+                RuleEvent::Linking(event) => self.game.events.linking.push((*event, action)),
+                RuleEvent::Resource(event) => self.game.add_rsrc_predicate(event.clone(), action),
+            }
+        }
+        Ok(())
+    }
+
+    fn action(&mut self, words: &mut Words) -> Result<EngineAction, String> {
+        Ok(match words.next("an action")? {
+            "change" => {
+                let pool = self.pool(words)?;
+                EngineAction::ChangeResource(pool, self.transaction(words)?)
+            }
+            "move_tile" => EngineAction::MoveTile(words.pos()?, words.pos()?),
+            "move_character" => {
+                let id = match words.next("a character name")? {
+                    "none" => None,
+                    name => Some(self.char_id(words, name)?),
+                };
+                EngineAction::MoveCharacter(id, words.pos()?)
+            }
+            "move_room" => EngineAction::MoveRoom(self.room_name(words)?, words.pos()?),
+            "move_room_across" => {
+                EngineAction::MoveRoomAcross(self.room_name(words)?, edge(words)?)
+            }
+            "add_character" => {
+                let room = self.room_name(words)?;
+                let mut character = Character::new();
+                character.pos = words.pos()?;
+                character.color = words.color()?;
+                while let Ok(name) = words.next("") {
+                    let rsrc = self.rsrc_id(words, name)?;
+                    character.add_inventory_item(rsrc, words.num("an amount")?);
+                }
+                EngineAction::AddCharacter(character, room)
+            }
+            "add_tile" => {
+                let name = words.next("a tile name")?;
+                let tile = name
+                    .chars()
+                    .next()
+                    .filter(|_| name.chars().count() == 1)
+                    .and_then(|ch| self.tiles.get(&ch))
+                    .ok_or_else(|| words.err(format!("no tile named {}", name)))?;
+                EngineAction::AddTile(*tile, self.room_name(words)?, words.pos()?)
+            }
+            "remove_character" => {
+                let name = words.next("a character name")?;
+                EngineAction::RemoveCharacter(self.char_id(words, name)?)
+            }
+            "move_player" => EngineAction::MovePlayer(words.pos()?),
+            "move_player_by" => EngineAction::MovePlayerBy(words.pos()?),
+            "undo_last_trade" => EngineAction::UndoLastTrade,
+            "capture_key" => {
+                let action = words.next("an action")?;
+                EngineAction::CaptureKey(
+                    ActionID::from_binding_name(action)
+                        .ok_or_else(|| words.err(format!("unknown action {}", action)))?,
+                )
+            }
+            word => return Err(words.err(format!("unknown action {}", word))),
+        })
+    }
+
+    fn room_name(&self, words: &mut Words) -> Result<usize, String> {
+        let name = words.next("a room")?;
+        self.rooms
+            .get(name)
+            .copied()
+            .ok_or_else(|| words.err(format!("no room named {}", name)))
+    }
+
+    /// the room's node in the room graph, for linking events
+    fn room_node(&self, words: &mut Words) -> Result<usize, String> {
+        let room = self.room_name(words)?;
+        Ok(self.game.logics.linking.graphs[0]
+            .graph
+            .node_idx(&room)
+            .unwrap())
+    }
+
+    fn char_id(&self, words: &Words, name: &str) -> Result<CharacterID, String> {
+        self.chars
+            .get(name)
+            .copied()
+            .ok_or_else(|| words.err(format!("no character named {}", name)))
+    }
+
+    fn rsrc(&self, words: &mut Words) -> Result<RsrcID, String> {
+        let name = words.next("a resource")?;
+        self.rsrc_id(words, name)
+    }
+
+    fn rsrc_id(&self, words: &Words, name: &str) -> Result<RsrcID, String> {
+        self.rsrcs
+            .get(name)
+            .cloned()
+            .ok_or_else(|| words.err(format!("no resource named {}", name)))
+    }
+
+    fn ent(&self, words: &mut Words) -> Result<CollisionEnt, String> {
+        match words.next("player, character, tile, or edge")? {
+            "player" => Ok(CollisionEnt::Player),
+            "character" => {
+                let name = words.next("a character name")?;
+                Ok(CollisionEnt::Character(self.char_id(words, name)?))
+            }
+            "tile" => Ok(CollisionEnt::Tile(words.pos()?)),
+            "edge" => Ok(CollisionEnt::Edge(edge(words)?)),
+            word => Err(words.err(format!(
+                "expected player, character, tile, or edge, not {}",
+                word
+            ))),
+        }
+    }
+
+    fn pool(&self, words: &mut Words) -> Result<PoolID, String> {
+        let ent = match words.next("player or character")? {
+            "player" => EntID::Player,
+            "character" => {
+                let name = words.next("a character name")?;
+                EntID::Character(self.char_id(words, name)?)
+            }
+            word => return Err(words.err(format!("expected player or character, not {}", word))),
+        };
+        Ok(PoolID::new(ent, self.rsrc(words)?))
+    }
+
+    fn transaction(&self, words: &mut Words) -> Result<Transaction<i16, PoolID>, String> {
+        Ok(match words.next("a transaction")? {
+            "change" => Transaction::Change(words.num("an amount")?),
+            "set" => Transaction::Set(words.num("an amount")?),
+            "set_max" => Transaction::SetMax(words.num("an amount")?),
+            "set_min" => Transaction::SetMin(words.num("an amount")?),
+            "trade" => {
+                let val = words.num("an amount")?;
+                Transaction::Trade(val, self.pool(words)?)
+            }
+            word => return Err(words.err(format!("unknown transaction {}", word))),
+        })
+    }
+}
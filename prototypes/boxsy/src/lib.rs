@@ -6,7 +6,7 @@
 //! - items/inventory??
 //! - multiple rooms, moving from one to another
 //!
-//! drawing: I'm not doing the pixel art thing. The player, interactable characters, and tiles all have different colors
+//! drawing: I'm not doing the pixel art thing. The player, interactable characters, and tiles all have different colors. (ok, they can have pixel art now, see [Sprite]. but they don't need it)
 //!
 //! TODO:
 //! - [x] Write tilemap collision
@@ -18,6 +18,12 @@
 //! - [x] Add linking logics
 //!     - [x] graph/state machine struct
 //! - [x] composing multiple queries
+//! - [x] a file format for whole games (see [format](crate::format))
+//! - [x] importing Bitsy games (see [bitsy](crate::bitsy))
+//! - [x] rooms of any size, which scroll when they don't fit on screen
+//! - [x] walking off the edge of a room into the next one
+//! - [x] dialogue, when bumping into characters
+//! - [x] characters that move around on their own
 
 #![allow(clippy::upper_case_acronyms)]
 #![allow(clippy::new_without_default)]
@@ -33,21 +39,28 @@ use asterism::{
 use macroquad::prelude::*;
 
 // reexports
-pub use asterism::control::{Action, ControlEventType, ControlReaction, Values};
+pub use asterism::control::{Action, BindingName, ControlEventType, ControlReaction, Values};
 pub use asterism::linking::{LinkingEvent, LinkingEventType, LinkingReaction};
 pub use asterism::resources::{ResourceEventType, ResourceReaction, Transaction};
 pub use asterism::Logic;
 pub use collision::*;
+pub use dialogue::{Dialogue, Talk};
+pub use sprite::Sprite;
 pub use events::EngineAction;
 pub use types::*;
 
 const TILE_SIZE: usize = 32;
+/// how many tiles fit across the screen, and how big rooms are unless they're given a size
 pub const WORLD_SIZE: usize = 8;
 pub const GAME_SIZE: usize = TILE_SIZE * WORLD_SIZE;
 
+pub mod bitsy;
 mod collision;
+mod dialogue;
 mod entities;
 mod events;
+pub mod format;
+mod sprite;
 mod types;
 use events::*;
 
@@ -78,6 +91,8 @@ impl Game {
                 draw_timer: Vec::new(),
                 background_color: DARKBLUE,
                 colors: BTreeMap::new(),
+                sprites: BTreeMap::new(),
+                tick: 0,
             },
         }
     }
@@ -85,21 +100,74 @@ impl Game {
     pub fn get_current_room(&self) -> usize {
         self.logics.linking.graphs[0].get_current_node()
     }
+
+    /// Rebinds the player's controls from a binding profile (see [KeyboardControl::load_profile]). Call this after `set_player`, since only controls the player already has can be rebound.
+    pub fn load_controls(&mut self, profile: &str) -> Result<(), String> {
+        self.logics.control.load_profile(profile)
+    }
+
+    /// Writes the player's current controls out as a binding profile.
+    pub fn save_controls(&self) -> String {
+        self.logics.control.save_profile()
+    }
+
+    /// Saves the rooms, where everyone is, everyone's inventories, and the controls as JSON, ex. to pick a dungeon back up later. Rules and colors aren't saved, since they're part of the game. Needs the `serde` feature.
+    #[cfg(feature = "serde")]
+    pub fn save(&self) -> Result<String, String> {
+        serde_json::to_string(&(&self.state, &self.logics))
+            .map_err(|e| format!("couldn't save game: {}", e))
+    }
+
+    /// Loads a save made with `save` into a game with the same rules.
+    #[cfg(feature = "serde")]
+    pub fn load(&mut self, save: &str) -> Result<(), String> {
+        let (state, logics) =
+            serde_json::from_str(save).map_err(|e| format!("couldn't load game: {}", e))?;
+        self.state = state;
+        self.logics = logics;
+        Ok(())
+    }
 }
 
 pub struct Draw {
     draw_timer: Vec<(Box<dyn Fn()>, usize)>,
     background_color: Color,
     colors: BTreeMap<EntID, Color>,
+    sprites: BTreeMap<EntID, Sprite>,
+    /// frames drawn so far, for animating sprites
+    tick: usize,
 }
 
-#[derive(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Room {
+    #[cfg_attr(feature = "serde", serde(with = "asterism::serde_math"))]
     pub chars: Vec<(CharacterID, IVec2)>,
-    pub map: [[Option<TileID>; WORLD_SIZE]; WORLD_SIZE],
+    /// rows of tiles
+    pub map: Vec<Vec<Option<TileID>>>,
+}
+
+impl Default for Room {
+    fn default() -> Self {
+        Self::new(WORLD_SIZE, WORLD_SIZE)
+    }
 }
 
 impl Room {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            chars: Vec::new(),
+            map: vec![vec![None; width]; height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.map.first().map_or(0, |row| row.len())
+    }
+
+    pub fn height(&self) -> usize {
+        self.map.len()
+    }
+
     pub(crate) fn find_char(&self, id: CharacterID) -> Option<(usize, IVec2)> {
         self.chars
             .iter()
@@ -109,6 +177,7 @@ impl Room {
     }
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct State {
     pub rooms: Vec<Room>,
     pub player: bool,
@@ -116,8 +185,17 @@ pub struct State {
     rsrc_id_max: usize,
     char_id_max: usize,
     tile_type_count: usize,
+    /// entities waiting to be added or removed aren't saved
+    #[cfg_attr(feature = "serde", serde(skip))]
     add_queue: Vec<Ent>,
+    #[cfg_attr(feature = "serde", serde(skip))]
     remove_queue: Vec<EntID>,
+    /// frames gone by, for timing characters' steps
+    #[cfg_attr(feature = "serde", serde(skip))]
+    ticks: usize,
+    /// the conversation on screen, if there is one. The player can't move while talking
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub talking: Option<Talk>,
 }
 
 impl State {
@@ -131,6 +209,8 @@ impl State {
             tile_type_count: 0,
             add_queue: Vec::new(),
             remove_queue: Vec::new(),
+            ticks: 0,
+            talking: None,
         }
     }
 
@@ -158,7 +238,6 @@ impl State {
             .map(|(i, _)| i + 1)
     }
 
-    #[allow(unused)]
     pub(crate) fn queue_remove(&mut self, ent: EntID) {
         self.remove_queue.push(ent);
     }
@@ -167,6 +246,7 @@ impl State {
     }
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Logics {
     pub control: KeyboardControl<ActionID, MacroquadInputWrapper>,
     pub collision: TileMapCollision<TileID, ColEntType>,
@@ -180,7 +260,12 @@ impl Logics {
         Self {
             control: KeyboardControl::new(),
             collision: TileMapCollision::new(WORLD_SIZE, WORLD_SIZE),
-            resources: QueuedResources::new(),
+            resources: {
+                let mut resources = QueuedResources::new();
+                // keep a record of recent transactions so trades can be undone
+                resources.enable_ledger(256);
+                resources
+            },
             linking: {
                 let mut linking = GraphedLinking::new();
                 linking.add_graph(0, []);
@@ -208,8 +293,13 @@ pub async fn run(mut game: Game) {
             }
         }
 
-        control(&mut game);
-        collision(&mut game);
+        if game.state.talking.is_some() {
+            dialogue::update(&mut game);
+        } else {
+            control(&mut game);
+            game.move_characters();
+            collision(&mut game);
+        }
         resources(&mut game);
         linking(&mut game);
 
@@ -346,11 +436,55 @@ fn collision(game: &mut Game) {
                     };
                     matched1 && matched2
                 }
+                Contact::Edge(i, direction) => {
+                    let i = game.logics.collision.get_ident_data(ColIdent::EntIdx(*i));
+                    let matched1 = match i {
+                        TileMapColData::Ent { id, .. } => match id {
+                            ColEntType::Player => *ent1 == CollisionEnt::Player,
+                            ColEntType::Character(ch_id) => {
+                                if let CollisionEnt::Character(id) = ent1 {
+                                    id == ch_id
+                                } else {
+                                    false
+                                }
+                            }
+                        },
+                        _ => false,
+                    };
+                    let matched2 = if let CollisionEnt::Edge(edge) = *ent2 {
+                        Some(edge) == Edge::from_direction(*direction)
+                    } else {
+                        false
+                    };
+                    matched1 && matched2
+                }
             });
         if match_event {
             reaction.perform_action(&mut game.state, &mut game.logics);
         }
     }
+
+    // bumping into a character starts talking to them
+    let bumped = game.logics.collision.events().iter().find_map(|event| {
+        if let Contact::Ent(i, j) = event {
+            let ids = [*i, *j].map(|i| {
+                match game.logics.collision.get_ident_data(ColIdent::EntIdx(i)) {
+                    TileMapColData::Ent { id, .. } => Some(*id),
+                    _ => None,
+                }
+            });
+            match ids {
+                [Some(ColEntType::Player), Some(ColEntType::Character(ch))]
+                | [Some(ColEntType::Character(ch)), Some(ColEntType::Player)] => Some(ch),
+                _ => None,
+            }
+        } else {
+            None
+        }
+    });
+    if let Some(character) = bumped {
+        game.talk_to(character);
+    }
 }
 
 fn resources(game: &mut Game) {
@@ -386,73 +520,80 @@ fn linking(game: &mut Game) {
     }
 }
 
+/// Where the room's top left corner goes on screen. Rooms bigger than the screen scroll to keep the player in the middle of it, and smaller ones sit in the middle of the screen.
+fn camera(game: &Game) -> Vec2 {
+    let room = &game.state.rooms[game.get_current_room()];
+    let player = if game.state.player {
+        game.logics.collision.positions[0].as_vec2()
+    } else {
+        Vec2::ZERO
+    };
+    let follow = |room_size: usize, screen: f32, player: f32| {
+        let room_size = (room_size * TILE_SIZE) as f32;
+        if room_size <= screen {
+            (screen - room_size) / 2.0
+        } else {
+            (screen / 2.0 - (player + 0.5) * TILE_SIZE as f32).clamp(screen - room_size, 0.0)
+        }
+    };
+    Vec2::new(
+        follow(room.width(), screen_width(), player.x),
+        follow(room.height(), screen_height(), player.y),
+    )
+    .round()
+}
+
 fn draw(game: &mut Game) {
     clear_background(game.draw.background_color);
     let current_room = game.get_current_room();
+    let camera = camera(game);
 
     let mut col_data = game.logics.collision.data_iter();
 
     while let Some((id, col_data)) = col_data.next() {
         match (id, col_data) {
             (ColIdent::Position(pos), TileMapColDataMut::Position { id: tile, .. }) => {
+                let x = camera.x + pos.x as f32 * TILE_SIZE as f32;
+                let y = camera.y + pos.y as f32 * TILE_SIZE as f32;
+                if let Some(sprite) = game.draw.sprites.get(&EntID::Tile(*tile)) {
+                    sprite.draw(x, y, game.draw.tick);
+                    continue;
+                }
                 let color = game
                     .draw
                     .colors
                     .get(&EntID::Tile(*tile))
                     .unwrap_or_else(|| panic!("tile {} color undefined", tile.idx()));
-                draw_rectangle(
-                    pos.x as f32 * TILE_SIZE as f32,
-                    pos.y as f32 * TILE_SIZE as f32,
-                    TILE_SIZE as f32,
-                    TILE_SIZE as f32,
-                    *color,
-                );
+                draw_rectangle(x, y, TILE_SIZE as f32, TILE_SIZE as f32, *color);
             }
-            (ColIdent::EntIdx(idx), TileMapColDataMut::Ent { pos, id: ent, .. }) => match ent {
-                ColEntType::Player => {
-                    let color = game
-                        .draw
-                        .colors
-                        .get(&EntID::Player)
-                        .expect("player color not set");
-                    draw_rectangle(
-                        pos.x as f32 * TILE_SIZE as f32,
-                        pos.y as f32 * TILE_SIZE as f32,
-                        TILE_SIZE as f32,
-                        TILE_SIZE as f32,
-                        *color,
-                    );
-                    draw_text(
-                        "P",
-                        pos.x as f32 * TILE_SIZE as f32 + 4.0,
-                        (pos.y as f32 + 1.0) * TILE_SIZE as f32 - 4.0,
-                        TILE_SIZE as f32,
-                        WHITE,
-                    );
-                }
-                ColEntType::Character(_) => {
-                    let character = game.state.rooms[current_room].chars[idx - 1].0;
-                    let color = game
-                        .draw
-                        .colors
-                        .get(&EntID::Character(character))
-                        .unwrap_or_else(|| panic!("character {} color defined", character.idx()));
-                    draw_rectangle(
-                        pos.x as f32 * TILE_SIZE as f32,
-                        pos.y as f32 * TILE_SIZE as f32,
-                        TILE_SIZE as f32,
-                        TILE_SIZE as f32,
-                        *color,
-                    );
-                    draw_text(
+            (ColIdent::EntIdx(idx), TileMapColDataMut::Ent { pos, id: ent, .. }) => {
+                let x = camera.x + pos.x as f32 * TILE_SIZE as f32;
+                let y = camera.y + pos.y as f32 * TILE_SIZE as f32;
+                let (ent, letter) = match ent {
+                    ColEntType::Player => (EntID::Player, "P"),
+                    ColEntType::Character(_) => (
+                        EntID::Character(game.state.rooms[current_room].chars[idx - 1].0),
                         "C",
-                        pos.x as f32 * TILE_SIZE as f32 + 4.0,
-                        (pos.y as f32 + 1.0) * TILE_SIZE as f32 - 4.0,
-                        TILE_SIZE as f32,
-                        WHITE,
-                    );
+                    ),
+                };
+                if let Some(sprite) = game.draw.sprites.get(&ent) {
+                    sprite.draw(x, y, game.draw.tick);
+                    continue;
                 }
-            },
+                let color = game
+                    .draw
+                    .colors
+                    .get(&ent)
+                    .unwrap_or_else(|| panic!("{:?} color undefined", ent));
+                draw_rectangle(x, y, TILE_SIZE as f32, TILE_SIZE as f32, *color);
+                draw_text(
+                    letter,
+                    x + 4.0,
+                    y + TILE_SIZE as f32 - 4.0,
+                    TILE_SIZE as f32,
+                    WHITE,
+                );
+            }
             _ => unreachable!(),
         }
     }
@@ -467,8 +608,8 @@ fn draw(game: &mut Game) {
             Box::new(move || {
                 draw_text(
                     &format! {"{} get!", name},
-                    x as f32 * TILE_SIZE as f32,
-                    y as f32 * TILE_SIZE as f32,
+                    camera.x + x as f32 * TILE_SIZE as f32,
+                    camera.y + y as f32 * TILE_SIZE as f32,
                     (TILE_SIZE / 2) as f32,
                     WHITE,
                 )
@@ -517,12 +658,14 @@ fn draw(game: &mut Game) {
         *timer -= 1;
         event();
     }
+
+    if let Some(talk) = &game.state.talking {
+        dialogue::draw(talk);
+    }
+    game.draw.tick += 1;
 }
 
 fn setup(game: &mut Game) {
-    game.logics
-        .collision
-        .clear_and_resize_map(WORLD_SIZE, WORLD_SIZE);
     let current_room = game.get_current_room();
 
     entities::load_room(&mut game.state, &mut game.logics, current_room);
@@ -0,0 +1,136 @@
+use crate::*;
+
+/// the characters pixels are written with in a grid, for palette indices in order
+const PIXEL_NAMES: &str = "0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// Pixel art for a tile, the player, or a character, which is stretched to fill the tile they're on. Without one, they're drawn as a square of their color.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Sprite {
+    pub palette: Vec<Color>,
+    /// each frame of the animation's rows of pixels, which are indices into the palette, or `None` where it's see-through. Frames are square and all the same size
+    pub frames: Vec<Vec<Vec<Option<usize>>>>,
+    /// how many frames of the game each frame of the animation is shown for
+    pub frame_time: usize,
+}
+
+impl Sprite {
+    /// Reads pixel art from a grid of text. Each pixel is its color's place in the palette (`0`-`9`, then `a`-`z` and `A`-`Z`), and `.` is see-through. An animation's frames are separated by blank lines, and each frame is shown for half a second.
+    ///
+    /// ```
+    /// # use boxsy::*;
+    /// # use macroquad::prelude::*;
+    /// let blinking = Sprite::from_grid(
+    ///     vec![WHITE, BLACK],
+    ///     "0000\n0110\n0110\n0000\n\n0000\n0000\n0000\n0000",
+    /// )
+    /// .unwrap();
+    /// assert_eq!(blinking.frames.len(), 2);
+    /// assert_eq!(blinking.size(), 4);
+    /// ```
+    pub fn from_grid(palette: Vec<Color>, grid: &str) -> Result<Self, String> {
+        let mut frames = vec![Vec::new()];
+        for row in grid.lines().map(str::trim) {
+            if row.is_empty() {
+                frames.push(Vec::new());
+            } else {
+                frames.last_mut().unwrap().push(row);
+            }
+        }
+        frames.retain(|frame| !frame.is_empty());
+        Self::from_rows(palette, &frames)
+    }
+
+    /// reads frames that have already been split up into rows
+    pub(crate) fn from_rows(palette: Vec<Color>, frames: &[Vec<&str>]) -> Result<Self, String> {
+        let size = frames.first().map_or(0, |frame| frame.len());
+        if size == 0 {
+            return Err("sprite doesn't have any pixels".to_string());
+        }
+        let mut sprite = Self {
+            palette,
+            frames: Vec::new(),
+            frame_time: 30,
+        };
+        for (i, frame) in frames.iter().enumerate() {
+            if frame.len() != size || frame.iter().any(|row| row.chars().count() != size) {
+                return Err(format!(
+                    "frame {} of the sprite isn't {} by {} pixels",
+                    i + 1,
+                    size,
+                    size
+                ));
+            }
+            let mut pixels = Vec::new();
+            for row in frame.iter() {
+                let mut pixel_row = Vec::new();
+                for ch in row.chars() {
+                    if ch == '.' {
+                        pixel_row.push(None);
+                        continue;
+                    }
+                    match PIXEL_NAMES.find(ch) {
+                        Some(idx) if idx < sprite.palette.len() => pixel_row.push(Some(idx)),
+                        Some(_) => return Err(format!("pixel {} isn't in the palette", ch)),
+                        None => return Err(format!("unrecognized pixel: '{}'", ch)),
+                    }
+                }
+                pixels.push(pixel_row);
+            }
+            sprite.frames.push(pixels);
+        }
+        Ok(sprite)
+    }
+
+    /// Writes the pixels out the way [Sprite::from_grid] reads them.
+    pub fn to_grid(&self) -> Result<String, String> {
+        let frames = self
+            .frames
+            .iter()
+            .map(|frame| {
+                let mut grid = String::new();
+                for row in frame.iter() {
+                    for pixel in row.iter() {
+                        match pixel {
+                            Some(idx) => {
+                                grid.push(PIXEL_NAMES.chars().nth(*idx).ok_or_else(|| {
+                                    format!("sprites can only have {} colors", PIXEL_NAMES.len())
+                                })?)
+                            }
+                            None => grid.push('.'),
+                        }
+                    }
+                    grid.push('\n');
+                }
+                Ok(grid)
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(frames.join("\n"))
+    }
+
+    /// how many pixels across each frame is
+    pub fn size(&self) -> usize {
+        self.frames.first().map_or(0, |frame| frame.len())
+    }
+
+    /// Draws the frame of the animation that's showing at `tick` frames into the game.
+    pub(crate) fn draw(&self, x: f32, y: f32, tick: usize) {
+        if self.frames.is_empty() {
+            return;
+        }
+        let frame = &self.frames[(tick / self.frame_time.max(1)) % self.frames.len()];
+        let pixel = TILE_SIZE as f32 / frame.len() as f32;
+        for (py, row) in frame.iter().enumerate() {
+            for (px, idx) in row.iter().enumerate() {
+                if let Some(color) = idx.and_then(|idx| self.palette.get(idx)) {
+                    draw_rectangle(
+                        x + px as f32 * pixel,
+                        y + py as f32 * pixel,
+                        pixel,
+                        pixel,
+                        *color,
+                    );
+                }
+            }
+        }
+    }
+}
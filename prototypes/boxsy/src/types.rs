@@ -1,3 +1,4 @@
+use crate::{Dialogue, Sprite};
 use macroquad::{color::*, input::KeyCode, math::IVec2};
 
 /// generates identifier structs (i got tired of typing all of them out)
@@ -6,6 +7,7 @@ macro_rules! id_impl_new {
         $(
             $(#[$derive])*
             #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+            #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
             pub struct $id_type(usize);
 
             impl $id_type {
@@ -24,6 +26,7 @@ macro_rules! id_impl_new {
 id_impl_new!([derive(Hash, Ord, PartialOrd)] TileID, [derive(Hash, Ord, PartialOrd)] CharacterID);
 
 #[derive(Hash, Ord, PartialOrd, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PoolID {
     pub(crate) attached_to: EntID,
     pub(crate) rsrc: RsrcID,
@@ -36,6 +39,7 @@ impl PoolID {
 }
 
 #[derive(Hash, Ord, PartialOrd, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RsrcID {
     idx: usize,
     name: String,
@@ -61,6 +65,7 @@ pub enum Ent {
 
 // the stonks meme but it says derive
 #[derive(PartialOrd, Ord, PartialEq, Eq, Hash, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EntID {
     Player,
     Tile(TileID),
@@ -68,6 +73,7 @@ pub enum EntID {
 }
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ActionID {
     Left,
     Right,
@@ -75,11 +81,29 @@ pub enum ActionID {
     Down,
 }
 
+impl asterism::control::BindingName for ActionID {
+    fn binding_name(&self) -> String {
+        format!("{:?}", self)
+    }
+
+    fn from_binding_name(name: &str) -> Option<Self> {
+        match name {
+            "Left" => Some(Self::Left),
+            "Right" => Some(Self::Right),
+            "Up" => Some(Self::Up),
+            "Down" => Some(Self::Down),
+            _ => None,
+        }
+    }
+}
+
 // players are unfixed
 pub struct Player {
     pub pos: IVec2,
     pub amt_moved: IVec2,
     pub color: Color,
+    /// drawn instead of a square of `color`
+    pub sprite: Option<Sprite>,
     pub inventory: Vec<(RsrcID, i16)>,
     pub controls: Vec<(ActionID, KeyCode, bool)>,
 }
@@ -90,6 +114,7 @@ impl Player {
             pos: IVec2::ZERO,
             amt_moved: IVec2::ZERO,
             color: WHITE,
+            sprite: None,
             inventory: Vec::new(),
             controls: vec![
                 (ActionID::Up, KeyCode::Up, true),
@@ -116,10 +141,12 @@ impl Player {
 }
 
 // tiles can be solid or not
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub struct Tile {
     pub solid: bool,
     pub color: Color,
+    /// drawn instead of a square of `color`
+    pub sprite: Option<Sprite>,
 }
 
 impl Tile {
@@ -135,6 +162,7 @@ impl Tile {
                     gen_range(0.3, 0.7),
                 )
             },
+            sprite: None,
         }
     }
 }
@@ -146,6 +174,12 @@ pub struct Character {
     pub inventory: Vec<(RsrcID, i16)>,
     pub pos: IVec2,
     pub color: Color,
+    /// drawn instead of a square of `color`
+    pub sprite: Option<Sprite>,
+    /// what they say when the player bumps into them
+    pub dialogue: Option<Dialogue>,
+    /// how they move around on their own, and how many frames go by between steps
+    pub behavior: Option<(Behavior, usize)>,
 }
 
 impl Character {
@@ -154,6 +188,9 @@ impl Character {
             inventory: Vec::new(),
             pos: IVec2::ZERO,
             color: LIME,
+            sprite: None,
+            dialogue: None,
+            behavior: None,
         }
     }
 
@@ -162,10 +199,24 @@ impl Character {
     }
 }
 
+/// How a character moves around on their own, one tile at a time. They can't walk through solid tiles or anyone else.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Behavior {
+    /// walks to each position in turn, going back to the first after the last
+    Patrol(Vec<IVec2>),
+    /// steps in a random direction
+    Wander,
+    /// steps toward the player
+    Follow,
+    /// steps away from the player
+    Flee,
+}
+
 use asterism::control::ControlEvent;
 use asterism::resources::ResourceEvent;
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ColEntType {
     Player,
     Character(CharacterID),
@@ -176,6 +227,33 @@ pub enum CollisionEnt {
     Player,
     Tile(IVec2),
     Character(CharacterID),
+    /// one side of the room, which is run into when walking off of it
+    Edge(Edge),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Edge {
+    North,
+    South,
+    East,
+    West,
+}
+
+impl Edge {
+    /// which edge an entity ran into when trying to move off the map in `direction`. Going off a corner counts as going off the side.
+    pub fn from_direction(direction: IVec2) -> Option<Self> {
+        if direction.x > 0 {
+            Some(Self::East)
+        } else if direction.x < 0 {
+            Some(Self::West)
+        } else if direction.y > 0 {
+            Some(Self::South)
+        } else if direction.y < 0 {
+            Some(Self::North)
+        } else {
+            None
+        }
+    }
 }
 
 pub type CtrlEvent = ControlEvent<ActionID>;
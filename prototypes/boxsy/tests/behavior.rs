@@ -0,0 +1,151 @@
+//! Characters should move around on their own, without walking through walls or anyone else.
+
+use boxsy::*;
+use macroquad::prelude::*;
+
+const GAME: &str = "tile 0
+solid
+
+room 0
+......
+.0....
+......
+
+player
+room 0
+pos 0 1
+
+character guard
+room 0
+pos 4 1
+move every 2 follow
+
+character lookout
+room 0
+pos 5 0
+move every 1 patrol 5 0 5 2
+";
+
+/// puts the first room's characters into the collision logic, like the game does when it starts
+fn load(game: &mut Game) {
+    let room = &game.state.rooms[0];
+    game.logics
+        .collision
+        .clear_and_resize_map(room.width(), room.height());
+    for (id, pos) in game.state.rooms[0].chars.clone() {
+        game.logics.collision.positions.push(pos);
+        game.logics.collision.amt_moved.push(IVec2::ZERO);
+        game.logics.collision.metadata.push(CollisionData::new(
+            true,
+            true,
+            ColEntType::Character(id),
+        ));
+    }
+}
+
+fn frame(game: &mut Game) {
+    game.move_characters();
+    game.logics.collision.update();
+}
+
+fn pos(game: &Game, i: usize) -> IVec2 {
+    game.logics.collision.positions[i]
+}
+
+#[test]
+fn follow_and_patrol() {
+    let mut game = Game::from_text(GAME).unwrap();
+    load(&mut game);
+    game.logics
+        .collision
+        .handle_predicate(&CollisionReaction::SetTileAtPos(
+            IVec2::new(1, 1),
+            TileID::new(0),
+        ));
+
+    // the guard only moves every other frame
+    frame(&mut game);
+    assert_eq!(pos(&game, 1), IVec2::new(4, 1));
+    assert_eq!(pos(&game, 2), IVec2::new(5, 1));
+    frame(&mut game);
+    assert_eq!(pos(&game, 1), IVec2::new(3, 1));
+    assert_eq!(pos(&game, 2), IVec2::new(5, 2));
+
+    // the lookout turns around at the end of their patrol
+    frame(&mut game);
+    assert_eq!(pos(&game, 2), IVec2::new(5, 1));
+
+    // the guard can't walk through the wall between them and the player
+    for _ in 0..4 {
+        frame(&mut game);
+    }
+    assert_eq!(pos(&game, 1), IVec2::new(2, 1));
+
+    // but catches up when the player comes around, and gets stopped by them
+    game.logics
+        .collision
+        .handle_predicate(&CollisionReaction::SetEntPos(0, IVec2::new(1, 0)));
+    for _ in 0..4 {
+        frame(&mut game);
+    }
+    assert_eq!(pos(&game, 1), IVec2::new(2, 0));
+    assert_eq!(pos(&game, 0), IVec2::new(1, 0));
+    frame(&mut game);
+    assert_eq!(pos(&game, 1), IVec2::new(2, 0));
+    assert!(game.logics.collision.events().contains(&Contact::Ent(0, 1)));
+}
+
+#[test]
+fn flee_and_wander() {
+    let mut game = Game::new();
+    let room = game.add_room(3, 3);
+    game.set_player(Player::new());
+    let mut scared = Character::new();
+    scared.pos = IVec2::new(1, 1);
+    scared.behavior = Some((Behavior::Flee, 1));
+    game.add_character(scared, room);
+    load(&mut game);
+
+    // running into the corner, and staying there
+    for _ in 0..5 {
+        frame(&mut game);
+    }
+    assert_eq!(pos(&game, 1), IVec2::new(2, 2));
+    assert!(game.to_text().unwrap().contains("move every 1 flee\n"));
+
+    let mut game = Game::new();
+    let room = game.add_room(3, 3);
+    game.set_player(Player::new());
+    let mut wanderer = Character::new();
+    wanderer.pos = IVec2::new(2, 2);
+    wanderer.behavior = Some((Behavior::Wander, 1));
+    game.add_character(wanderer, room);
+    load(&mut game);
+
+    // wandering stays in the room
+    for _ in 0..20 {
+        frame(&mut game);
+        let wanderer = pos(&game, 1);
+        assert!(wanderer.cmpge(IVec2::ZERO).all() && wanderer.cmplt(IVec2::splat(3)).all());
+        assert_ne!(wanderer, pos(&game, 0));
+    }
+}
+
+#[test]
+fn behavior_in_files() {
+    let game = Game::from_text(GAME).unwrap();
+    let text = game.to_text().unwrap();
+    assert!(text.contains("move every 2 follow\n"));
+    assert!(text.contains("move every 1 patrol 5 0 5 2\n"));
+    assert_eq!(Game::from_text(&text).unwrap().to_text().unwrap(), text);
+
+    let err = |text: &str| Game::from_text(text).err().unwrap();
+    assert_eq!(
+        err("room 0\n.\n\ncharacter 0\nroom 0\nmove every 2 run"),
+        "line 6: expected patrol, wander, follow, or flee, not run"
+    );
+    assert_eq!(
+        err("room 0\n.\n\ncharacter 0\nroom 0\nmove every 2 patrol 1"),
+        "line 6: expected a y position"
+    );
+}
@@ -0,0 +1,267 @@
+//! Bitsy games should come over as boxsy rooms, tiles, characters, resources and links.
+
+use boxsy::*;
+use macroquad::prelude::*;
+
+const GAME: &str = r#"Write your game's title here
+
+# BITSY VERSION 7.12
+
+! ROOM_FORMAT 1
+
+PAL 0
+NAME blueprint
+0,82,204
+128,159,255
+255,255,255
+
+PAL 1
+0,0,0
+255,0,0
+255,255,255
+
+ROOM 0
+0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0
+0,a,a,a,a,a,a,a,a,a,a,a,a,a,a,0
+0,a,0,0,0,0,0,0,0,0,0,0,0,0,a,0
+0,a,0,0,0,0,0,0,0,0,0,0,0,0,a,0
+0,a,0,0,0,0,0,0,0,0,0,0,0,0,a,0
+0,a,0,0,0,0,0,0,0,0,0,0,0,0,a,0
+0,a,0,0,0,0,0,0,0,0,0,0,0,0,0,0
+0,a,0,0,0,0,0,0,0,0,0,0,0,0,0,0
+0,a,0,0,0,0,0,0,0,0,0,0,0,0,a,0
+0,a,0,0,0,0,0,0,0,0,0,0,0,0,a,0
+0,a,0,0,0,0,0,0,0,0,0,0,0,0,a,0
+0,a,0,0,0,0,0,0,0,0,0,0,0,0,a,0
+0,a,0,0,0,0,0,0,0,0,0,0,0,0,a,0
+0,a,0,0,0,0,0,0,0,0,0,0,0,0,a,0
+0,a,a,a,a,a,a,a,a,a,a,a,a,a,a,0
+0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0
+NAME example room
+ITM 0 10,5
+EXT 14,7 1 2,7
+PAL 0
+
+ROOM 1
+0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0
+0,b,b,b,b,b,b,b,b,b,b,b,b,b,b,0
+0,b,0,0,0,0,0,0,0,0,0,0,0,0,b,0
+0,b,0,0,0,0,0,0,0,0,0,0,0,0,b,0
+0,b,0,0,0,0,0,0,0,0,0,0,0,0,b,0
+0,b,0,0,0,0,0,0,0,0,0,0,0,0,b,0
+0,0,0,0,0,0,0,0,0,0,0,0,0,0,b,0
+0,0,0,0,0,0,0,0,0,0,0,0,0,0,b,0
+0,b,0,0,0,0,0,0,0,0,0,0,0,0,b,0
+0,b,0,0,0,0,0,0,0,0,0,0,0,0,b,0
+0,b,0,0,0,0,0,0,0,0,0,0,0,0,b,0
+0,b,0,0,0,0,0,0,0,0,0,0,0,0,b,0
+0,b,0,0,0,0,0,0,0,0,0,0,0,0,b,0
+0,b,0,0,0,0,0,0,0,0,0,0,0,0,b,0
+0,b,b,b,b,b,b,b,b,b,b,b,b,b,b,0
+0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0
+EXT 1,7 0 13,7
+END 0 8,8
+PAL 1
+
+TIL a
+11111111
+10000001
+10000001
+10011001
+10011001
+10000001
+10000001
+11111111
+NAME block
+WAL true
+
+TIL b
+00000000
+01111110
+01000010
+01000010
+01000010
+01000010
+01111110
+00000000
+>
+00000000
+00000000
+00111100
+00100100
+00100100
+00111100
+00000000
+00000000
+COL 2
+
+SPR A
+00011000
+00011000
+00011000
+00111100
+01111110
+10111101
+00100100
+00100100
+POS 0 4,4
+
+SPR a
+00000000
+00000000
+01010001
+01110001
+01110010
+01111100
+00111100
+00100100
+NAME cat
+DLG 0
+POS 0 8,12
+
+SPR b
+00000000
+00000000
+00000000
+00000000
+00000000
+00000000
+00000000
+00000000
+NAME nobody
+
+ITM 0
+00000000
+00000000
+00000000
+00111100
+01100100
+00100100
+00011000
+00000000
+NAME cup of tea
+DLG 1
+
+DLG 0
+I'm a cat
+NAME cat dialog
+
+DLG 1
+"""
+You found a nice warm cup of tea
+
+{a = a + 1}
+"""
+NAME tea dialog
+
+END 0
+The end
+
+VAR a
+42
+"#;
+
+#[test]
+fn import() {
+    let (game, warnings) = Game::from_bitsy(GAME).unwrap();
+    assert_eq!(game.state.rooms.len(), 2);
+    assert_eq!(game.get_current_room(), 0);
+    assert_eq!(game.state.rooms[0].width(), 16);
+    assert_eq!(game.state.rooms[0].height(), 16);
+    assert_eq!(game.logics.collision.positions[0], IVec2::new(4, 4));
+
+    // walls are solid
+    let block = TileID::new(0);
+    assert!(game.logics.collision.tile_solid[&block]);
+    assert!(!game.logics.collision.tile_solid[&TileID::new(1)]);
+    assert_eq!(game.state.rooms[0].map[0][0], None);
+    assert_eq!(game.state.rooms[0].map[1][1], Some(block));
+
+    // the cat and the cup of tea are characters, and the sprite that isn't anywhere is left out
+    assert_eq!(game.state.rooms[0].chars.len(), 2);
+    assert_eq!(game.state.rooms[0].chars[1].1, IVec2::new(10, 5));
+    let tea = game.state.resources[0].clone();
+    assert_eq!(tea.name(), "cup_of_tea");
+    let tea_char = game.state.rooms[0].chars[1].0;
+    assert_eq!(
+        game.logics
+            .resources
+            .get_value_by_itemtype(&PoolID::new(EntID::Character(tea_char), tea.clone())),
+        Some(1)
+    );
+    assert_eq!(
+        game.logics
+            .resources
+            .get_value_by_itemtype(&PoolID::new(EntID::Player, tea)),
+        Some(0)
+    );
+
+    // tile b blinks between two frames, drawn in its color
+    let tile = game.get_sprite(EntID::Tile(TileID::new(1))).unwrap();
+    assert_eq!(tile.frames.len(), 2);
+    assert_eq!(tile.palette.len(), 1);
+    assert_eq!(tile.frames[0][0][0], None);
+    assert_eq!(tile.frames[0][1][1], Some(0));
+    assert!(game.get_sprite(EntID::Player).is_some());
+
+    // the cat talks, and so does the tea when it's picked up, without the script
+    let cat = game.state.rooms[0].chars[0].0;
+    assert!(matches!(
+        &game.events.dialogue[&cat],
+        Dialogue::Line(line) if line == "I'm a cat"
+    ));
+    assert!(matches!(
+        &game.events.dialogue[&tea_char],
+        Dialogue::Line(line) if line == "You found a nice warm cup of tea"
+    ));
+
+    // picking up the tea, and the two exits
+    assert_eq!(game.events.collision.len(), 4);
+    let graph = &game.logics.linking.graphs[0].graph;
+    assert!(graph.edges[0][1] && graph.edges[1][0]);
+    assert!(matches!(
+        game.events.collision[2],
+        ((0, CollisionEnt::Player, CollisionEnt::Tile(pos)), EngineAction::MoveRoom(1, to))
+            if pos == IVec2::new(14, 7) && to == IVec2::new(2, 7)
+    ));
+    // the exit in room 0 was in a gap in the wall, so it has a tile to run into now
+    assert!(game.state.rooms[0].map[7][14].is_some());
+
+    assert_eq!(
+        warnings,
+        vec![
+            "rooms use different palettes, but they all use the first room's now",
+            "1 dialogues had scripts in them, which were left out",
+            "2 endings were left out, since boxsy games don't end",
+            "1 variables were left out, since they're only used in dialogue scripts",
+        ]
+    );
+
+    // and it can be written out as a boxsy game
+    let text = game.to_text().unwrap();
+    assert!(text.contains("background #0052cc"));
+    assert!(text.contains("say I'm a cat\n"));
+    let loaded = Game::from_text(&text).unwrap();
+    assert_eq!(loaded.to_text().unwrap(), text);
+}
+
+#[test]
+fn exported_page() {
+    let page = format!(
+        "<html><script type=\"text/bitsy\" id=\"exportedGameData\">\n{}</script></html>",
+        GAME
+    );
+    let (game, _) = Game::from_bitsy(&page).unwrap();
+    assert_eq!(game.state.rooms.len(), 2);
+
+    assert_eq!(
+        Game::from_bitsy("title\n\nPAL 0\n0,0,0").err().unwrap(),
+        "there aren't any rooms in this game"
+    );
+    assert_eq!(
+        Game::from_bitsy("title\n\nROOM 0\n0,0\nEXT 1 0 0,0")
+            .err()
+            .unwrap(),
+        "line 5: expected a position like 4,5"
+    );
+}
@@ -0,0 +1,107 @@
+//! Characters should say their lines in order, branch on the player's choices, and do things partway through.
+
+use boxsy::*;
+
+const GAME: &str = "resource rock
+
+room 0
+........
+........
+
+player
+room 0
+pos 0 0
+item rock 0
+
+character shopkeeper
+room 0
+pos 1 0
+item rock 3
+say hello!
+ask want a rock?
+  option yes
+    say here you go
+    do change character shopkeeper rock trade 1 player rock
+  option no
+    say ok
+end
+say bye
+";
+
+fn line(talk: &Talk) -> &str {
+    match talk.showing() {
+        Some(Dialogue::Line(line)) => line,
+        Some(Dialogue::Choice(question, _)) => question,
+        _ => "",
+    }
+}
+
+fn rocks(game: &Game) -> Option<i16> {
+    game.logics
+        .resources
+        .get_value_by_itemtype(&PoolID::new(EntID::Player, game.state.resources[0].clone()))
+}
+
+#[test]
+fn talking() {
+    let mut game = Game::from_text(GAME).unwrap();
+    let shopkeeper = game.state.rooms[0].chars[0].0;
+    let dialogue = game.events.dialogue[&shopkeeper].clone();
+
+    let mut talk = Talk::new(&dialogue);
+    assert!(talk.showing().is_none());
+    talk.next(&mut game.state, &mut game.logics);
+    assert_eq!(line(&talk), "hello!");
+    talk.next(&mut game.state, &mut game.logics);
+    assert_eq!(line(&talk), "want a rock?");
+
+    // saying yes gives the player a rock
+    talk.choose(0, &mut game.state, &mut game.logics);
+    assert_eq!(line(&talk), "here you go");
+    talk.next(&mut game.state, &mut game.logics);
+    assert_eq!(line(&talk), "bye");
+    game.logics.resources.update();
+    assert_eq!(rocks(&game), Some(1));
+    talk.next(&mut game.state, &mut game.logics);
+    assert!(talk.showing().is_none());
+
+    // saying no doesn't
+    let mut talk = Talk::new(&dialogue);
+    talk.next(&mut game.state, &mut game.logics);
+    talk.next(&mut game.state, &mut game.logics);
+    talk.choose(1, &mut game.state, &mut game.logics);
+    assert_eq!(line(&talk), "ok");
+    talk.next(&mut game.state, &mut game.logics);
+    assert_eq!(line(&talk), "bye");
+    game.logics.resources.update();
+    assert_eq!(rocks(&game), Some(1));
+}
+
+#[test]
+fn dialogue_in_files() {
+    let game = Game::from_text(GAME).unwrap();
+    let text = game.to_text().unwrap();
+    assert!(text.contains("say hello!\nask want a rock?\n  option yes\n    say here you go\n    do change character 0 rock trade 1 player rock\n  option no\n    say ok\nend\nsay bye\n"));
+    assert_eq!(Game::from_text(&text).unwrap().to_text().unwrap(), text);
+
+    let mut game = Game::new();
+    let room = game.add_room(2, 2);
+    let mut character = Character::new();
+    character.dialogue = Some(Dialogue::lines(["hi", "there"]));
+    game.add_character(character, room);
+    assert!(game.to_text().unwrap().ends_with("say hi\nsay there\n"));
+
+    let err = |text: &str| Game::from_text(text).err().unwrap();
+    assert_eq!(
+        err("room 0\n..\n\ncharacter 0\nroom 0\nask well?\noption yes\nsay ok"),
+        "line 6: ask without an end"
+    );
+    assert_eq!(
+        err("room 0\n..\n\ncharacter 0\nroom 0\nsay hi\noption yes"),
+        "line 7: option without an ask"
+    );
+    assert_eq!(
+        err("room 0\n..\n\ncharacter 0\nroom 0\ndo remove_character 1"),
+        "line 6: no character named 1"
+    );
+}
@@ -0,0 +1,79 @@
+//! Walking off the edge of a room should go to the room next to it, even when the rooms are different sizes.
+
+use boxsy::*;
+use macroquad::prelude::*;
+
+fn player_pos(game: &Game) -> IVec2 {
+    game.logics.collision.positions[0]
+}
+
+#[test]
+fn walk_across() {
+    let mut game = Game::new();
+    let west = game.add_room(4, 4);
+    let east = game.add_room(6, 8);
+    game.add_edge_link((west, Edge::East), east);
+    game.add_edge_link((east, Edge::West), west);
+    let mut player = Player::new();
+    player.pos = IVec2::new(3, 2);
+    game.set_player(player);
+    game.logics.collision.clear_and_resize_map(4, 4);
+
+    // the player is kept in the room, and the edge they ran into is reported
+    game.logics
+        .collision
+        .handle_predicate(&CollisionReaction::SetEntPos(0, IVec2::new(4, 2)));
+    game.logics.collision.update();
+    assert_eq!(player_pos(&game), IVec2::new(3, 2));
+    assert_eq!(
+        game.logics.collision.events(),
+        &[Contact::Edge(0, IVec2::X)]
+    );
+    let ((_, _, edge), action) = &game.events.collision[0];
+    assert_eq!(*edge, CollisionEnt::Edge(Edge::East));
+
+    action.perform_action(&mut game.state, &mut game.logics);
+    assert_eq!(game.get_current_room(), east);
+    assert_eq!(player_pos(&game), IVec2::new(0, 2));
+    assert_eq!(game.logics.collision.map.len(), 8);
+    assert_eq!(game.logics.collision.map[0].len(), 6);
+
+    // coming back from further down than the other room goes keeps the player in it
+    game.logics
+        .collision
+        .handle_predicate(&CollisionReaction::SetEntPos(0, IVec2::new(0, 7)));
+    let (_, action) = &game.events.collision[1];
+    action.perform_action(&mut game.state, &mut game.logics);
+    assert_eq!(game.get_current_room(), west);
+    assert_eq!(player_pos(&game), IVec2::new(3, 3));
+}
+
+#[test]
+fn edge_links_in_files() {
+    let text = "tile 0\n\nroom west\n00\n0.\n\nroom east\n...\n...\n\nplayer\nroom west\npos 1 1\n\nlink west edge east to east\n\nlink east edge north to west 1 0\n";
+    let game = Game::from_text(text).unwrap();
+    assert_eq!(game.state.rooms[1].width(), 3);
+    assert!(matches!(
+        game.events.collision[0],
+        (
+            (0, CollisionEnt::Player, CollisionEnt::Edge(Edge::East)),
+            EngineAction::MoveRoomAcross(1, Edge::East)
+        )
+    ));
+    // with a position, it's an ordinary link
+    assert!(matches!(
+        game.events.collision[1],
+        (
+            (1, CollisionEnt::Player, CollisionEnt::Edge(Edge::North)),
+            EngineAction::MoveRoom(0, _)
+        )
+    ));
+
+    let written = game.to_text().unwrap();
+    assert!(written.contains("link 0 edge east to 1\n"));
+    assert!(written.contains("link 1 edge north to 0 1 0\n"));
+    assert_eq!(
+        Game::from_text(&written).unwrap().to_text().unwrap(),
+        written
+    );
+}
@@ -0,0 +1,149 @@
+//! Games should come back the same after being written out and read back in.
+
+use boxsy::*;
+use macroquad::prelude::*;
+
+const GAME: &str = include_str!("../games/extreme-dungeon-crawler.boxsy");
+
+fn assert_same(a: &Game, b: &Game) {
+    assert_eq!(a.state.rooms.len(), b.state.rooms.len());
+    for (a, b) in a.state.rooms.iter().zip(b.state.rooms.iter()) {
+        assert_eq!(a.map, b.map);
+        assert_eq!(a.chars, b.chars);
+    }
+    assert_eq!(a.state.player, b.state.player);
+    assert_eq!(a.state.resources, b.state.resources);
+    assert_eq!(a.get_current_room(), b.get_current_room());
+    assert_eq!(a.logics.collision.tile_solid, b.logics.collision.tile_solid);
+    assert_eq!(a.logics.collision.positions, b.logics.collision.positions);
+    assert_eq!(a.logics.resources.items, b.logics.resources.items);
+    assert_eq!(a.save_controls(), b.save_controls());
+    assert_eq!(
+        a.logics.linking.graphs[0].graph.edges,
+        b.logics.linking.graphs[0].graph.edges
+    );
+    assert_eq!(a.events.control.len(), b.events.control.len());
+    assert_eq!(a.events.collision.len(), b.events.collision.len());
+    assert_eq!(a.events.linking.len(), b.events.linking.len());
+    assert_eq!(a.events.resource_event.len(), b.events.resource_event.len());
+}
+
+#[test]
+fn round_trip() {
+    let game = Game::from_text(GAME).unwrap();
+    assert_eq!(game.state.rooms.len(), 3);
+    assert_eq!(game.state.rooms[1].chars.len(), 1);
+    assert_eq!(game.events.collision.len(), 6);
+
+    let text = game.to_text().unwrap();
+    let loaded = Game::from_text(&text).unwrap();
+    assert_same(&game, &loaded);
+    assert_eq!(loaded.to_text().unwrap(), text);
+}
+
+/// a game made in code, with one of every kind of rule
+fn built() -> Game {
+    let mut game = Game::new();
+    game.set_background(Color::from_rgba(10, 20, 30, 128));
+    let gems = game.log_rsrc("gems".to_string());
+
+    let mut tile = Tile::new();
+    tile.solid = true;
+    let wall = game.log_tile_info(tile);
+    let floor = game.log_tile_info(Tile::new());
+    // rooms don't have to be the size of the screen
+    let room = game.add_room(12, 9);
+    game.add_tile_at_pos(wall, room, IVec2::new(0, 0));
+    game.add_tile_at_pos(floor, room, IVec2::new(7, 7));
+    let other = game
+        .add_room_from_str(
+            "00000000\n0      0\n0      0\n0      0\n0      0\n0      0\n0      0\n00000000",
+        )
+        .unwrap();
+
+    let mut player = Player::new();
+    player.pos = IVec2::new(2, 3);
+    player.add_inventory_item(gems.clone(), 5);
+    player.set_control_map(ActionID::Up, KeyCode::W, false);
+    game.set_player(player);
+
+    let mut character = Character::new();
+    character.pos = IVec2::new(4, 4);
+    character.add_inventory_item(gems.clone(), 1);
+    let ch = game.add_character(character.clone(), other);
+
+    let player_gems = PoolID::new(EntID::Player, gems.clone());
+    let ch_gems = PoolID::new(EntID::Character(ch), gems);
+    game.add_link(
+        (room, CollisionEnt::Tile(IVec2::new(7, 7))),
+        (other, IVec2::ONE),
+    );
+    game.add_collision_predicate(
+        (other, CollisionEnt::Player, CollisionEnt::Character(ch)),
+        EngineAction::ChangeResource(player_gems.clone(), Transaction::Trade(2, ch_gems.clone())),
+    );
+    game.add_collision_predicate(
+        (other, CollisionEnt::Player, CollisionEnt::Character(ch)),
+        EngineAction::MoveCharacter(Some(ch), IVec2::new(5, 5)),
+    );
+    game.add_ctrl_predicate(
+        ActionID::Up,
+        ControlEventType::KeyReleased,
+        EngineAction::CaptureKey(ActionID::Down),
+    );
+    game.add_link_predicate(other, room, EngineAction::AddCharacter(character, room));
+    game.add_rsrc_predicate(
+        RsrcEvent {
+            pool: player_gems,
+            transaction: Transaction::Trade(2, ch_gems),
+            event_type: ResourceEventType::TransactionUnsuccessful(
+                asterism::resources::ResourceError::TooSmall,
+            ),
+        },
+        EngineAction::AddTile(wall, other, IVec2::new(3, 3)),
+    );
+    game
+}
+
+#[test]
+fn round_trip_rules() {
+    let game = built();
+    let text = game.to_text().unwrap();
+    assert!(text.contains("background #0a141e80"));
+    assert!(text.contains("key Up W off"));
+    assert!(text.contains("link 0 tile 7 7 to 1 1 1"));
+    // both of the collision's actions are under one rule
+    assert!(text.contains(
+        "on collide 1 player character 0\ndo change player gems trade 2 character 0 gems\ndo move_character 0 5 5\n"
+    ));
+    assert!(text.contains("on enter 1 from 0\ndo add_character 0 4 4"));
+    assert!(text.contains(
+        "on resource player gems trade 2 character 0 gems failed too_small\ndo add_tile 0 1 3 3"
+    ));
+
+    let loaded = Game::from_text(&text).unwrap();
+    assert_same(&game, &loaded);
+    assert_eq!(loaded.to_text().unwrap(), text);
+}
+
+#[test]
+fn errors() {
+    let err = |text: &str| Game::from_text(text).err().unwrap();
+    assert_eq!(
+        err("tile 0\nsolid\n\nroom 0\n000\n00\n"),
+        "line 6: every row of a room has to be 3 tiles wide"
+    );
+    assert_eq!(
+        err("tile 0\n\nroom 0\n0000000x\n0\n0\n0\n0\n0\n0\n0"),
+        "line 4: no tile named x"
+    );
+    assert_eq!(err("player\nitem gold 1"), "line 2: no resource named gold");
+    assert_eq!(
+        err("on key Jump pressed\ndo undo_last_trade"),
+        "line 1: unknown action Jump"
+    );
+    assert_eq!(
+        err("resource a\nresource a"),
+        "line 2: there's already a resource named a"
+    );
+}
@@ -0,0 +1,76 @@
+//! Pixel art should read from grids the same way it's written out, in code and in game files.
+
+use boxsy::*;
+use macroquad::prelude::*;
+
+const GHOST: &str = "
+.00.
+0110
+0000
+0.0.
+
+.00.
+0110
+0000
+.0.0
+";
+
+#[test]
+fn grids() {
+    let ghost = Sprite::from_grid(vec![WHITE, BLACK], GHOST).unwrap();
+    assert_eq!(ghost.size(), 4);
+    assert_eq!(ghost.frames.len(), 2);
+    assert_eq!(ghost.frames[0][0], vec![None, Some(0), Some(0), None]);
+    assert_eq!(ghost.frames[1][3], vec![None, Some(0), None, Some(0)]);
+    assert_eq!(ghost.to_grid().unwrap(), GHOST.trim_start());
+
+    let err = |grid: &str| Sprite::from_grid(vec![WHITE], grid).err().unwrap();
+    assert_eq!(err(""), "sprite doesn't have any pixels");
+    assert_eq!(err("00\n0"), "frame 1 of the sprite isn't 2 by 2 pixels");
+    assert_eq!(
+        err("00\n00\n\n000\n000\n000"),
+        "frame 2 of the sprite isn't 2 by 2 pixels"
+    );
+    assert_eq!(err("01\n00"), "pixel 1 isn't in the palette");
+    assert_eq!(err("0?\n00"), "unrecognized pixel: '?'");
+}
+
+#[test]
+fn sprites_in_files() {
+    let mut game = Game::new();
+    let mut tile = Tile::new();
+    tile.sprite =
+        Some(Sprite::from_grid(vec![Color::from_rgba(255, 0, 0, 255)], "0.\n.0").unwrap());
+    game.log_tile_info(tile);
+    let room = game.add_room(2, 2);
+    let mut player = Player::new();
+    player.sprite = Some(Sprite::from_grid(vec![WHITE, BLACK], GHOST).unwrap());
+    game.set_player(player);
+    let mut character = Character::new();
+    let mut ghost = Sprite::from_grid(vec![WHITE, Color::from_rgba(0, 0, 0, 0)], GHOST).unwrap();
+    ghost.frame_time = 10;
+    character.sprite = Some(ghost.clone());
+    character.dialogue = Some(Dialogue::Line("boo".to_string()));
+    let ch = game.add_character(character, room);
+
+    let text = game.to_text().unwrap();
+    assert!(text.contains("tile 0\ncolor"));
+    assert!(text.contains("sprite #ff0000\n0.\n.0\n"));
+    assert!(text.contains("sprite #ffffff #000000 every 30\n.00.\n0110\n0000\n0.0.\n>\n.00.\n"));
+    // the sprite goes after the dialogue
+    assert!(text.contains("say boo\nsprite #ffffff #00000000 every 10\n"));
+
+    let loaded = Game::from_text(&text).unwrap();
+    assert_eq!(loaded.get_sprite(EntID::Character(ch)), Some(&ghost));
+    assert_eq!(loaded.to_text().unwrap(), text);
+
+    let err = |text: &str| Game::from_text(text).err().unwrap();
+    assert_eq!(
+        err("tile 0\nsprite #ffffff\n00\n0"),
+        "line 2: frame 1 of the sprite isn't 2 by 2 pixels"
+    );
+    assert_eq!(
+        err("player\nsprite #ffffff every\n0"),
+        "line 2: expected a number of frames"
+    );
+}
@@ -77,6 +77,21 @@ fn init(game: &mut Game) {
     let paddle = game.add_paddle(paddle);
 
     let score = game.add_score(Score::new(0, Vec2::new(0.0, (HEIGHT - 22) as f32)));
+    // combo goes up with each block broken and slowly drains back to 0
+    let combo = game.add_score(Score::new(
+        0,
+        Vec2::new((WIDTH - PADDLE_OFF_X) as f32, (HEIGHT - 22) as f32),
+    ));
+    game.add_score_generator(
+        combo,
+        Generator::new(
+            RateRule::DecayToward {
+                target: 0,
+                amount: 1,
+            },
+            60,
+        ),
+    );
 
     // define paddle controls
     game.events.add_ctrl_events(
@@ -112,6 +127,7 @@ fn init(game: &mut Game) {
         vec![
             EngineAction::RemoveEntity(None),
             EngineAction::ChangeScoreBy(score, 1),
+            EngineAction::ChangeScoreBy(combo, 1),
         ],
     );
 
@@ -125,14 +141,14 @@ fn init(game: &mut Game) {
     );
 
     // reset score when score == 40
-    game.events.add_rsrc_event(
+    game.add_rsrc_event(
         EngineRsrcEvent::ScoreEquals(score, 40),
         EngineAction::ChangeScore(score, 0),
     );
 
     // score reset
     // clear board
-    game.events.add_rsrc_event(
+    game.add_rsrc_event(
         EngineRsrcEvent::ScoreReset(score),
         EngineAction::RemoveEntity(Some(EntityMatch::Filter(Box::new(|ent: EntID| {
             ent.get_type() == EntType::Wall && ent.get_wall().unwrap().idx() >= 4
@@ -140,7 +156,7 @@ fn init(game: &mut Game) {
     );
 
     // add a million blocks
-    game.events.add_rsrc_events(
+    game.add_rsrc_events(
         EngineRsrcEvent::ScoreReset(score),
         add_blocks()
             .iter()
@@ -149,7 +165,7 @@ fn init(game: &mut Game) {
     );
 
     // reset ball
-    game.events.add_rsrc_events(
+    game.add_rsrc_events(
         EngineRsrcEvent::ScoreReset(score),
         vec![
             EngineAction::SetBallPos(
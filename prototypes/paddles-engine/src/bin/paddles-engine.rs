@@ -0,0 +1,47 @@
+use macroquad::prelude::*;
+use paddles_engine::definition::GameDef;
+use paddles_engine::*;
+
+// `paddles-engine games/paddles.json` runs the game described in the file
+fn game_def() -> GameDef {
+    let path = match std::env::args().nth(1) {
+        Some(path) => path,
+        None => {
+            eprintln!("usage: paddles-engine <game.json>");
+            std::process::exit(1);
+        }
+    };
+    let def = std::fs::read_to_string(&path)
+        .map_err(|e| format!("couldn't read {}: {}", path, e))
+        .and_then(|json| GameDef::from_json(&json));
+    match def {
+        Ok(def) => def,
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn window_conf() -> Conf {
+    let def = game_def();
+    Conf {
+        window_title: def.title,
+        window_width: def.width as i32,
+        window_height: def.height as i32,
+        fullscreen: false,
+        ..Default::default()
+    }
+}
+
+#[macroquad::main(window_conf)]
+async fn main() {
+    let game = match game_def().build() {
+        Ok(game) => game,
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    };
+    run(game).await;
+}
@@ -22,11 +22,40 @@ fn window_conf() -> Conf {
 async fn main() {
     // initialize game
     let mut game = Game::new();
-    init(&mut game);
+    // `paddles --net 127.0.0.1:4000 127.0.0.1:4001 1` plays as player 1 against `paddles --net 127.0.0.1:4001 127.0.0.1:4000 2`
+    let args: Vec<String> = std::env::args().collect();
+    let mut remote_player = None;
+    if let Some(i) = args.iter().position(|arg| arg == "--net") {
+        let (local, peer, player) = match &args[i + 1..] {
+            [local, peer, player, ..] => (local, peer, player),
+            _ => panic!("usage: paddles --net <local address> <peer address> <1 or 2>"),
+        };
+        let net = NetPeer::bind(local.as_str()).expect("couldn't open socket");
+        net.connect(peer.as_str())
+            .expect("couldn't connect to peer");
+        game.connect(net);
+        remote_player = Some(if player == "1" { 2 } else { 1 });
+    }
+    init(&mut game, remote_player);
+    // players can change the default keys with a binding profile
+    if let Ok(profile) = std::fs::read_to_string("paddles-controls.txt") {
+        if let Err(err) = game.load_controls(&profile) {
+            eprintln!("{}", err);
+        }
+    }
     run(game).await;
 }
 
-fn init(game: &mut Game) {
+/// Maps a key for a paddle played on this computer or on the other end of the connection.
+fn map(paddle: &mut Paddle, key: KeyCode, valid: bool, remote: bool) -> ActionID {
+    if remote {
+        paddle.add_remote_control_map(key, valid)
+    } else {
+        paddle.add_control_map(key, valid)
+    }
+}
+
+fn init(game: &mut Game, remote_player: Option<u8>) {
     // ball
     let center = Vec2::new(
         WIDTH as f32 / 2.0 - BALL_SIZE as f32 / 2.0,
@@ -65,9 +94,11 @@ fn init(game: &mut Game) {
         ),
         Vec2::new(PADDLE_WIDTH as f32, PADDLE_HEIGHT as f32),
     );
-    let action_q = p1.add_control_map(KeyCode::Q, true);
-    let action_a = p1.add_control_map(KeyCode::A, true);
-    let action_w = p1.add_control_map(KeyCode::W, true);
+    let remote = remote_player == Some(1);
+    let action_q = map(&mut p1, KeyCode::Q, true, remote);
+    let action_a = map(&mut p1, KeyCode::A, true, remote);
+    let action_w = map(&mut p1, KeyCode::W, true, remote);
+    let action_r = map(&mut p1, KeyCode::R, true, remote);
     let paddle1 = game.add_paddle(p1);
 
     // paddle 2
@@ -78,9 +109,10 @@ fn init(game: &mut Game) {
         ),
         Vec2::new(PADDLE_WIDTH as f32, PADDLE_HEIGHT as f32),
     );
-    let action_o = p2.add_control_map(KeyCode::O, true);
-    let action_l = p2.add_control_map(KeyCode::L, true);
-    let action_i = p2.add_control_map(KeyCode::I, false);
+    let remote = remote_player == Some(2);
+    let action_o = map(&mut p2, KeyCode::O, true, remote);
+    let action_l = map(&mut p2, KeyCode::L, true, remote);
+    let action_i = map(&mut p2, KeyCode::I, false, remote);
     let paddle2 = game.add_paddle(p2);
 
     let score1 = game.add_score(Score::new(0, Vec2::ZERO));
@@ -104,14 +136,18 @@ fn init(game: &mut Game) {
         EngineAction::MovePaddleBy(paddle2, Vec2::new(0.0, 1.0)),
     );
 
-    // serving
+    // serving: whoever just scored gets to serve, and player 1 serves first
+    let p1_serving = game.add_context("p1 serving", false, &[(paddle1, action_w)]);
+    let p2_serving = game.add_context("p2 serving", false, &[(paddle2, action_i)]);
+    game.logics.control.push_context(p1_serving);
+
     game.events.add_ctrl_event(
         EngineCtrlEvent::ServePressed(paddle1, action_w),
         EngineAction::SetBallVel(ball, Vec2::splat(1.0)),
     );
     game.events.add_ctrl_event(
         EngineCtrlEvent::ServePressed(paddle1, action_w),
-        EngineAction::SetKeyInvalid(paddle1, action_w),
+        EngineAction::PopContext,
     );
 
     game.events.add_ctrl_event(
@@ -120,9 +156,18 @@ fn init(game: &mut Game) {
     );
     game.events.add_ctrl_event(
         EngineCtrlEvent::ServePressed(paddle2, action_i),
-        EngineAction::SetKeyInvalid(paddle2, action_i),
+        EngineAction::PopContext,
     );
 
+    // R rewinds three seconds, except over the network, where the other player's game wouldn't rewind too
+    if remote_player.is_none() {
+        game.enable_rewind(180);
+        game.events.add_ctrl_event(
+            EngineCtrlEvent::ServePressed(paddle1, action_r),
+            EngineAction::Rewind(180),
+        );
+    }
+
     // increase score on collision with side wall
     game.events.add_col_events(
         EngineCollisionEvent::Match(
@@ -131,7 +176,7 @@ fn init(game: &mut Game) {
         ),
         vec![
             EngineAction::ChangeScoreBy(score1, 1),
-            EngineAction::SetKeyValid(paddle1, action_w),
+            EngineAction::PushContext(p1_serving),
             EngineAction::SetBallPos(ball, center),
             EngineAction::SetBallVel(ball, Vec2::ZERO),
         ],
@@ -144,12 +189,41 @@ fn init(game: &mut Game) {
         ),
         vec![
             EngineAction::ChangeScoreBy(score2, 1),
-            EngineAction::SetKeyValid(paddle2, action_i),
+            EngineAction::PushContext(p2_serving),
             EngineAction::SetBallPos(ball, center),
             EngineAction::SetBallVel(ball, Vec2::ZERO),
         ],
     );
 
+    // `paddles --ai` puts the computer in charge of player 2. It presses the same actions a player would, so it can only serve when it's allowed to
+    if std::env::args().any(|arg| arg == "--ai") {
+        game.add_ai(paddle2, move |obs: &Observation| {
+            let (ball_center, ball_vel) = obs.ball(ball).unwrap();
+            let paddle_center = obs.paddle(paddle2).unwrap();
+            let mut actions = Vec::new();
+            if ball_vel == Vec2::ZERO {
+                actions.push(action_i);
+            }
+            if ball_center.y < paddle_center.y - 4.0 {
+                actions.push(action_o);
+            } else if ball_center.y > paddle_center.y + 4.0 {
+                actions.push(action_l);
+            }
+            actions
+        });
+    }
+
+    // first to 10 wins, then start over
+    for score in [score1, score2] {
+        game.add_rsrc_events(
+            EngineRsrcEvent::ScoreReached(score, 10),
+            vec![
+                EngineAction::ChangeScore(score1, 0),
+                EngineAction::ChangeScore(score2, 0),
+            ],
+        );
+    }
+
     game.events.add_col_events(
         EngineCollisionEvent::Match(
             EntityMatch::ByID(ball.into()),
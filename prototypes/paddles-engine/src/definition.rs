@@ -0,0 +1,501 @@
+//! games described in JSON instead of code
+//!
+//! Entities are given names, and everything else (controls, contexts, events and the actions they trigger) refers to them by name. Several entities can share a name, ex. a grid of blocks, and then anything that refers to the name means all of them. See `games/` for examples.
+//!
+//! Needs the `serde` feature, ex. `cargo run --features serde --bin paddles-engine games/paddles.json`.
+
+use std::collections::BTreeMap;
+
+use asterism::control::{BindingName, NetKey, PointerInput};
+use asterism::resources::{Generator, RateRule};
+use macroquad::math::Vec2;
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+#[derive(Serialize, Deserialize)]
+pub struct GameDef {
+    #[serde(default = "GameDef::default_title")]
+    pub title: String,
+    #[serde(default = "GameDef::default_size")]
+    pub width: u32,
+    #[serde(default = "GameDef::default_size")]
+    pub height: u32,
+    #[serde(default)]
+    pub balls: Vec<BallDef>,
+    #[serde(default)]
+    pub walls: Vec<WallDef>,
+    #[serde(default)]
+    pub paddles: Vec<PaddleDef>,
+    #[serde(default)]
+    pub scores: Vec<ScoreDef>,
+    #[serde(default)]
+    pub contexts: Vec<ContextDef>,
+    #[serde(default)]
+    pub controls: Vec<Rule<CtrlEventDef>>,
+    #[serde(default)]
+    pub collisions: Vec<Rule<(Selector, Selector)>>,
+    #[serde(default)]
+    pub resources: Vec<Rule<RsrcEventDef>>,
+    /// how many frames can be rewound, see [EngineAction::Rewind]
+    #[serde(default)]
+    pub rewind: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BallDef {
+    pub name: String,
+    #[serde(with = "asterism::serde_math")]
+    pub pos: Vec2,
+    #[serde(with = "asterism::serde_math")]
+    pub size: Vec2,
+    #[serde(default, with = "asterism::serde_math")]
+    pub vel: Vec2,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct WallDef {
+    pub name: String,
+    #[serde(with = "asterism::serde_math")]
+    pub pos: Vec2,
+    #[serde(with = "asterism::serde_math")]
+    pub size: Vec2,
+    /// repeats the wall this many times across and down, each one `size` away from the last
+    #[serde(default)]
+    pub grid: Option<(usize, usize)>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PaddleDef {
+    pub name: String,
+    #[serde(with = "asterism::serde_math")]
+    pub pos: Vec2,
+    #[serde(with = "asterism::serde_math")]
+    pub size: Vec2,
+    #[serde(default)]
+    pub controls: Vec<ControlDef>,
+}
+
+/// A paddle's action and the input that does it. Inputs are written like in binding profiles, ex. `"Q"`, `"Mouse:Left"`, `"Cursor"`, or `"Remote:Q"` for the other player's keyboard over the network.
+#[derive(Serialize, Deserialize)]
+pub struct ControlDef {
+    pub action: String,
+    pub input: String,
+    #[serde(default = "ControlDef::default_valid")]
+    pub valid: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ScoreDef {
+    pub name: String,
+    #[serde(default)]
+    pub value: i16,
+    #[serde(with = "asterism::serde_math")]
+    pub pos: Vec2,
+    #[serde(default)]
+    pub generators: Vec<GeneratorDef>,
+}
+
+/// see [Generator]
+#[derive(Serialize, Deserialize)]
+pub struct GeneratorDef {
+    pub rule: RateRule<i16>,
+    pub period: usize,
+    #[serde(default)]
+    pub cooldown: usize,
+}
+
+/// A group of paddle actions that are turned on and off together, see `Game::add_context`.
+#[derive(Serialize, Deserialize)]
+pub struct ContextDef {
+    pub name: String,
+    #[serde(default)]
+    pub blocking: bool,
+    /// (paddle, action) pairs
+    pub actions: Vec<(String, String)>,
+    /// whether the context starts out pushed
+    #[serde(default)]
+    pub pushed: bool,
+}
+
+/// Actions to take when an event happens.
+#[derive(Serialize, Deserialize)]
+pub struct Rule<Event> {
+    pub when: Event,
+    #[serde(rename = "do")]
+    pub actions: Vec<ActionDef>,
+}
+
+/// see [EngineCtrlEvent], with (paddle, action) names
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CtrlEventDef {
+    Held(String, String),
+    Pressed(String, String),
+    PointerMoved(String, String),
+}
+
+/// see [EngineRsrcEvent], with score names
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RsrcEventDef {
+    ScoreIncreased(String),
+    ScoreReset(String),
+    ScoreEquals(String, i16),
+    ScoreReached(String, i16),
+}
+
+/// see [EntityMatch]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Selector {
+    Entity(String),
+    Type(EntTypeDef),
+    All,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntTypeDef {
+    Wall,
+    Paddle,
+    Ball,
+    Score,
+}
+
+/// see [EngineAction], with names instead of IDs. Actions on balls, paddles, and scores apply to each entity with the name.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActionDef {
+    /// bounces off whatever the ball collided with
+    BounceBall(String),
+    SetBallVel(String, #[serde(with = "asterism::serde_math")] Vec2),
+    SetBallPos(String, #[serde(with = "asterism::serde_math")] Vec2),
+    SetPaddlePos(String, #[serde(with = "asterism::serde_math")] Vec2),
+    MovePaddleBy(String, #[serde(with = "asterism::serde_math")] Vec2),
+    FollowPointer(String, String),
+    SetKeyValid(String, String),
+    SetKeyInvalid(String, String),
+    CaptureKey(String, String),
+    PushContext(String),
+    PopContext,
+    ChangeScoreBy(String, i16),
+    ChangeScore(String, i16),
+    /// removes the matching entities, or whatever was collided with if there isn't a selector
+    RemoveEntity(Option<Selector>),
+    /// Adds a wall. Walls added while the game is running don't have a name.
+    AddWall(
+        #[serde(with = "asterism::serde_math")] Vec2,
+        #[serde(with = "asterism::serde_math")] Vec2,
+    ),
+    Rewind(usize),
+}
+
+/// the IDs of everything in the game, by name
+#[derive(Default)]
+struct Names {
+    ents: BTreeMap<String, Vec<EntID>>,
+    /// each paddle's actions
+    actions: BTreeMap<(String, String), ActionID>,
+    contexts: BTreeMap<String, ContextID>,
+}
+
+impl Names {
+    fn add(&mut self, name: &str, id: EntID) {
+        self.ents.entry(name.to_string()).or_default().push(id);
+    }
+
+    fn ents(&self, name: &str) -> Result<&[EntID], String> {
+        self.ents
+            .get(name)
+            .map(|ids| ids.as_slice())
+            .ok_or_else(|| format!("no entity named {}", name))
+    }
+
+    fn balls(&self, name: &str) -> Result<Vec<BallID>, String> {
+        self.of_type(name, "ball", EntID::get_ball)
+    }
+
+    fn paddles(&self, name: &str) -> Result<Vec<PaddleID>, String> {
+        self.of_type(name, "paddle", EntID::get_paddle)
+    }
+
+    fn scores(&self, name: &str) -> Result<Vec<ScoreID>, String> {
+        self.of_type(name, "score", |id| match id {
+            EntID::Score(score) => Some(*score),
+            _ => None,
+        })
+    }
+
+    fn of_type<ID>(
+        &self,
+        name: &str,
+        type_name: &str,
+        get: impl Fn(&EntID) -> Option<ID>,
+    ) -> Result<Vec<ID>, String> {
+        let ids: Vec<ID> = self.ents(name)?.iter().filter_map(get).collect();
+        if ids.is_empty() {
+            Err(format!("{} isn't a {}", name, type_name))
+        } else {
+            Ok(ids)
+        }
+    }
+
+    fn action(&self, paddle: &str, action: &str) -> Result<(PaddleID, ActionID), String> {
+        let paddle_id = self.paddles(paddle)?[0];
+        self.actions
+            .get(&(paddle.to_string(), action.to_string()))
+            .map(|action| (paddle_id, *action))
+            .ok_or_else(|| format!("paddle {} doesn't have an action {}", paddle, action))
+    }
+
+    fn context(&self, name: &str) -> Result<ContextID, String> {
+        self.contexts
+            .get(name)
+            .copied()
+            .ok_or_else(|| format!("no context named {}", name))
+    }
+
+    fn select(&self, selector: &Selector) -> Result<EntityMatch, String> {
+        Ok(match selector {
+            Selector::Entity(name) => match self.ents(name)? {
+                [id] => EntityMatch::ByID(*id),
+                ids => {
+                    let ids = ids.to_vec();
+                    EntityMatch::Filter(Box::new(move |id| ids.contains(&id)))
+                }
+            },
+            Selector::Type(ty) => EntityMatch::ByType(match ty {
+                EntTypeDef::Wall => EntType::Wall,
+                EntTypeDef::Paddle => EntType::Paddle,
+                EntTypeDef::Ball => EntType::Ball,
+                EntTypeDef::Score => EntType::Score,
+            }),
+            Selector::All => EntityMatch::All,
+        })
+    }
+
+    fn actions(&self, actions: &[ActionDef]) -> Result<Vec<EngineAction>, String> {
+        let mut engine_actions = Vec::new();
+        for action in actions.iter() {
+            match action {
+                ActionDef::BounceBall(ball) => {
+                    for ball in self.balls(ball)? {
+                        engine_actions.push(EngineAction::BounceBall(ball, None));
+                    }
+                }
+                ActionDef::SetBallVel(ball, vel) => {
+                    for ball in self.balls(ball)? {
+                        engine_actions.push(EngineAction::SetBallVel(ball, *vel));
+                    }
+                }
+                ActionDef::SetBallPos(ball, pos) => {
+                    for ball in self.balls(ball)? {
+                        engine_actions.push(EngineAction::SetBallPos(ball, *pos));
+                    }
+                }
+                ActionDef::SetPaddlePos(paddle, pos) => {
+                    for paddle in self.paddles(paddle)? {
+                        engine_actions.push(EngineAction::SetPaddlePos(paddle, *pos));
+                    }
+                }
+                ActionDef::MovePaddleBy(paddle, delta) => {
+                    for paddle in self.paddles(paddle)? {
+                        engine_actions.push(EngineAction::MovePaddleBy(paddle, *delta));
+                    }
+                }
+                ActionDef::FollowPointer(paddle, action) => {
+                    let (paddle, action) = self.action(paddle, action)?;
+                    engine_actions.push(EngineAction::FollowPointer(paddle, action));
+                }
+                ActionDef::SetKeyValid(paddle, action) => {
+                    let (paddle, action) = self.action(paddle, action)?;
+                    engine_actions.push(EngineAction::SetKeyValid(paddle, action));
+                }
+                ActionDef::SetKeyInvalid(paddle, action) => {
+                    let (paddle, action) = self.action(paddle, action)?;
+                    engine_actions.push(EngineAction::SetKeyInvalid(paddle, action));
+                }
+                ActionDef::CaptureKey(paddle, action) => {
+                    let (paddle, action) = self.action(paddle, action)?;
+                    engine_actions.push(EngineAction::CaptureKey(paddle, action));
+                }
+                ActionDef::PushContext(context) => {
+                    engine_actions.push(EngineAction::PushContext(self.context(context)?));
+                }
+                ActionDef::PopContext => engine_actions.push(EngineAction::PopContext),
+                ActionDef::ChangeScoreBy(score, by) => {
+                    for score in self.scores(score)? {
+                        engine_actions.push(EngineAction::ChangeScoreBy(score, *by));
+                    }
+                }
+                ActionDef::ChangeScore(score, to) => {
+                    for score in self.scores(score)? {
+                        engine_actions.push(EngineAction::ChangeScore(score, *to));
+                    }
+                }
+                ActionDef::RemoveEntity(selector) => {
+                    let selector = selector.as_ref().map(|s| self.select(s)).transpose()?;
+                    engine_actions.push(EngineAction::RemoveEntity(selector));
+                }
+                ActionDef::AddWall(pos, size) => {
+                    engine_actions.push(EngineAction::AddEntity(Ent::Wall(Wall::new(*pos, *size))));
+                }
+                ActionDef::Rewind(frames) => engine_actions.push(EngineAction::Rewind(*frames)),
+            }
+        }
+        Ok(engine_actions)
+    }
+}
+
+impl GameDef {
+    fn default_title() -> String {
+        "paddles".to_string()
+    }
+
+    fn default_size() -> u32 {
+        255
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| format!("couldn't read game: {}", e))
+    }
+
+    /// Makes the game. Fails if anything refers to a name that isn't defined, or to the wrong kind of entity.
+    pub fn build(&self) -> Result<Game, String> {
+        let mut game = Game::new();
+        let mut names = Names::default();
+
+        // balls, paddles, and walls are added in the order the collision logic keeps them in
+        for ball in self.balls.iter() {
+            let mut b = Ball::new(ball.pos, ball.size);
+            b.vel = ball.vel;
+            names.add(&ball.name, game.add_ball(b).into());
+        }
+        for wall in self.walls.iter() {
+            let (across, down) = wall.grid.unwrap_or((1, 1));
+            for y in 0..down {
+                for x in 0..across {
+                    let pos = wall.pos + wall.size * Vec2::new(x as f32, y as f32);
+                    names.add(&wall.name, game.add_wall(Wall::new(pos, wall.size)).into());
+                }
+            }
+        }
+        for paddle in self.paddles.iter() {
+            if names.paddles(&paddle.name).is_ok() {
+                return Err(format!(
+                    "there's more than one paddle named {}",
+                    paddle.name
+                ));
+            }
+            let mut p = Paddle::new(paddle.pos, paddle.size);
+            for control in paddle.controls.iter() {
+                let input = NetKey::<PointerInput>::from_binding_name(&control.input)
+                    .ok_or_else(|| format!("unknown input {}", control.input))?;
+                let action = match input {
+                    NetKey::Local(input) => p.add_control_map(input, control.valid),
+                    NetKey::Remote(input) => p.add_remote_control_map(input, control.valid),
+                };
+                names
+                    .actions
+                    .insert((paddle.name.clone(), control.action.clone()), action);
+            }
+            names.add(&paddle.name, game.add_paddle(p).into());
+        }
+        for score in self.scores.iter() {
+            let id = game.add_score(Score::new(score.value, score.pos));
+            for generator in score.generators.iter() {
+                game.add_score_generator(
+                    id,
+                    Generator::new(generator.rule, generator.period)
+                        .with_cooldown(generator.cooldown),
+                );
+            }
+            names.add(&score.name, id.into());
+        }
+
+        for context in self.contexts.iter() {
+            let actions = context
+                .actions
+                .iter()
+                .map(|(paddle, action)| names.action(paddle, action))
+                .collect::<Result<Vec<_>, _>>()?;
+            let id = game.add_context(&context.name, context.blocking, &actions);
+            if context.pushed {
+                game.logics.control.push_context(id);
+            }
+            names.contexts.insert(context.name.clone(), id);
+        }
+
+        for rule in self.controls.iter() {
+            let event = match &rule.when {
+                CtrlEventDef::Held(paddle, action) => {
+                    let (paddle, action) = names.action(paddle, action)?;
+                    EngineCtrlEvent::MovePaddle(paddle, action)
+                }
+                CtrlEventDef::Pressed(paddle, action) => {
+                    let (paddle, action) = names.action(paddle, action)?;
+                    EngineCtrlEvent::ServePressed(paddle, action)
+                }
+                CtrlEventDef::PointerMoved(paddle, action) => {
+                    let (paddle, action) = names.action(paddle, action)?;
+                    EngineCtrlEvent::PointerMoved(paddle, action)
+                }
+            };
+            game.events
+                .add_ctrl_events(event, names.actions(&rule.actions)?);
+        }
+        for rule in self.collisions.iter() {
+            let (fst, snd) = &rule.when;
+            game.events.add_col_events(
+                EngineCollisionEvent::Match(names.select(fst)?, names.select(snd)?),
+                names.actions(&rule.actions)?,
+            );
+        }
+        for rule in self.resources.iter() {
+            let events = match &rule.when {
+                RsrcEventDef::ScoreIncreased(score) => names
+                    .scores(score)?
+                    .into_iter()
+                    .map(EngineRsrcEvent::ScoreIncreased)
+                    .collect::<Vec<_>>(),
+                RsrcEventDef::ScoreReset(score) => names
+                    .scores(score)?
+                    .into_iter()
+                    .map(EngineRsrcEvent::ScoreReset)
+                    .collect(),
+                RsrcEventDef::ScoreEquals(score, v) => names
+                    .scores(score)?
+                    .into_iter()
+                    .map(|score| EngineRsrcEvent::ScoreEquals(score, *v))
+                    .collect(),
+                RsrcEventDef::ScoreReached(score, v) => names
+                    .scores(score)?
+                    .into_iter()
+                    .map(|score| EngineRsrcEvent::ScoreReached(score, *v))
+                    .collect(),
+            };
+            for event in events {
+                game.add_rsrc_events(event, names.actions(&rule.actions)?);
+            }
+        }
+
+        if self.rewind > 0 {
+            game.enable_rewind(self.rewind);
+        }
+        Ok(game)
+    }
+}
+
+impl ControlDef {
+    fn default_valid() -> bool {
+        true
+    }
+}
+
+impl Game {
+    /// Makes a game from a JSON [GameDef].
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        GameDef::from_json(json)?.build()
+    }
+}
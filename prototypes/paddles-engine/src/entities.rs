@@ -2,7 +2,11 @@
 use asterism::collision::CollisionData;
 use asterism::graphics::draw;
 use asterism::Logic;
-use asterism::{collision::CollisionReaction, physics::PhysicsReaction, resources::PoolValues};
+use asterism::{
+    collision::CollisionReaction,
+    physics::PhysicsReaction,
+    resources::{Generator, Overflow, PoolValues},
+};
 use macroquad::math::Vec2;
 
 use crate::types::*;
@@ -31,12 +35,15 @@ macro_rules! add_ent {
         for (act_id, keycode, valid) in $ent_name.controls {
             control.add_key_map($id.idx(), keycode, act_id, valid);
         }
+        for (act_id, composite) in $ent_name.composites {
+            control.add_composite($id.idx(), act_id, composite);
+        }
     };
 
     (@attach $game:expr, $id:ident $gamefield:ident $ent_name:ident $ent_ty:ty; $id_ty:ty [resource]) => {
         $game.logics.resources
             .items
-            .insert(RsrcPool::Score($id), PoolValues{ val: $ent_name.value, min: <$ent_ty>::MIN, max: <$ent_ty>::MAX});
+            .insert(RsrcPool::Score($id), PoolValues::new($ent_name.value, <$ent_ty>::MIN, <$ent_ty>::MAX).with_overflow(Overflow::Saturating));
     };
 
     (@attach $game:expr, $id:ident $gamefield:ident $ent_name:ident $ent_ty:ty; $id_ty:ty [resource draw: $pos:expr]) => {
@@ -145,6 +152,13 @@ impl Game {
         id
     }
 
+    /// Attaches a [Generator][asterism::resources::Generator] to a score, so it changes over time (ex. a combo multiplier that decays).
+    pub fn add_score_generator(&mut self, score: ScoreID, generator: Generator<i16>) {
+        self.logics
+            .resources
+            .add_generator(RsrcPool::Score(score), generator);
+    }
+
     pub(crate) fn remove_paddle(&mut self, paddle: PaddleID) {
         let col_idx = self.state.get_col_idx(paddle.into());
         let ent_idx = self
@@ -154,7 +168,7 @@ impl Game {
             .position(|pid| *pid == paddle)
             .unwrap();
 
-        self.logics.control.mapping.remove(ent_idx);
+        self.logics.control.remove_set(ent_idx);
         self.logics
             .collision
             .handle_predicate(&CollisionReaction::RemoveBody(col_idx));
@@ -209,6 +223,14 @@ impl Game {
             .unwrap();
         let rsrc = RsrcPool::Score(score);
         self.logics.resources.items.remove(&rsrc);
+        self.logics
+            .resources
+            .generators
+            .retain(|(id, _)| *id != rsrc);
+        self.logics
+            .resources
+            .thresholds
+            .retain(|(id, _)| *id != rsrc);
 
         self.state.scores.remove(ent_i);
     }
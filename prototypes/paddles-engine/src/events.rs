@@ -1,4 +1,8 @@
-use crate::{types::*, Ent, EntID, EntType};
+use crate::{types::*, ContextID, Ent, EntID, EntType, Game, LogicIDs};
+use asterism::collision::CollisionEventType;
+use asterism::control::ControlEventType;
+use asterism::predicates::{PredicateTable, Rule};
+use asterism::resources::{ResourceEventType, ThresholdDirection, Transaction};
 use asterism::Logic;
 use macroquad::math::Vec2;
 
@@ -6,6 +10,8 @@ use macroquad::math::Vec2;
 pub enum EngineCtrlEvent {
     MovePaddle(PaddleID, ActionID),
     ServePressed(PaddleID, ActionID),
+    /// an analog control (ex. the cursor) moved
+    PointerMoved(PaddleID, ActionID),
 }
 
 pub enum EngineCollisionEvent {
@@ -20,6 +26,21 @@ pub enum EntityMatch {
     Filter(Box<dyn Fn(EntID) -> bool>),
 }
 
+impl EntityMatch {
+    /// whether the entity at this index in the collision logic matches
+    pub(crate) fn matches(&self, col_idx: usize, state: &crate::State) -> bool {
+        match self {
+            EntityMatch::ByID(id) => state.get_col_idx(*id) == col_idx,
+            EntityMatch::ByType(ty) => {
+                let ent_type: EntType = state.get_id(col_idx).get_col_type().into();
+                ent_type == *ty
+            }
+            EntityMatch::All => true,
+            EntityMatch::Filter(filter) => filter(state.get_id(col_idx)),
+        }
+    }
+}
+
 impl std::fmt::Debug for EntityMatch {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -35,7 +56,10 @@ impl std::fmt::Debug for EntityMatch {
 pub enum EngineRsrcEvent {
     ScoreIncreased(ScoreID),
     ScoreReset(ScoreID),
+    /// the score changed and is now equal to the value
     ScoreEquals(ScoreID, i16),
+    /// the score went from below the value to at or above it
+    ScoreReached(ScoreID, i16),
 }
 
 #[derive(Debug)]
@@ -46,12 +70,22 @@ pub enum EngineAction {
     SetBallPos(BallID, Vec2),
     SetPaddlePos(PaddleID, Vec2),
     MovePaddleBy(PaddleID, Vec2),
+    /// centers the paddle on the position of an analog control, ex. [crate::PointerInput::Cursor]
+    FollowPointer(PaddleID, ActionID),
     SetKeyValid(PaddleID, ActionID),
     SetKeyInvalid(PaddleID, ActionID),
+    /// binds the action to the next key the player presses
+    CaptureKey(PaddleID, ActionID),
+    /// turns on a group of controls made with `Game::add_context`
+    PushContext(ContextID),
+    /// turns off the most recently pushed group of controls
+    PopContext,
     ChangeScoreBy(ScoreID, i16),
     ChangeScore(ScoreID, i16),
     RemoveEntity(Option<EntityMatch>),
     AddEntity(Ent),
+    /// puts the game back the way it was this many frames ago, if rewinding was turned on with `Game::enable_rewind`
+    Rewind(usize),
 }
 
 impl EngineAction {
@@ -113,6 +147,24 @@ impl EngineAction {
                     .collision
                     .handle_predicate(&crate::CollisionReaction::SetCenter(col_idx, new_pos))
             }
+            Self::FollowPointer(paddle, action) => {
+                if let Some(values) = logics.control.get_action_in_set(paddle.idx(), *action) {
+                    let col_idx = state.get_col_idx((*paddle).into());
+                    let old_pos = *logics.collision.get_ident_data(col_idx).center;
+                    logics
+                        .collision
+                        .handle_predicate(&crate::CollisionReaction::SetVel(
+                            col_idx,
+                            values.axes - old_pos,
+                        ));
+                    logics
+                        .collision
+                        .handle_predicate(&crate::CollisionReaction::SetCenter(
+                            col_idx,
+                            values.axes,
+                        ));
+                }
+            }
             Self::SetKeyValid(set, action) => {
                 logics
                     .control
@@ -123,6 +175,21 @@ impl EngineAction {
                     .control
                     .handle_predicate(&crate::ControlReaction::SetKeyInvalid(set.idx(), *action));
             }
+            Self::CaptureKey(set, action) => {
+                logics
+                    .control
+                    .handle_predicate(&crate::ControlReaction::CaptureNextKey(set.idx(), *action));
+            }
+            Self::PushContext(context) => {
+                logics
+                    .control
+                    .handle_predicate(&crate::ControlReaction::PushContext(*context));
+            }
+            Self::PopContext => {
+                logics
+                    .control
+                    .handle_predicate(&crate::ControlReaction::PopContext);
+            }
             Self::RemoveEntity(None) => {} // no entity to remove
             Self::RemoveEntity(Some(match_ent)) => match match_ent {
                 EntityMatch::ByID(id) => state.queue_remove(*id),
@@ -202,61 +269,119 @@ impl EngineAction {
                 }
             },
             Self::AddEntity(ent) => state.queue_add(ent.clone()),
+            Self::Rewind(frames) => state.rewind = Some(*frames),
         }
     }
 }
 
+/// The game's rules, checked right after the logic they need events from updates.
 pub struct Events {
-    pub(crate) control: Vec<(EngineCtrlEvent, Vec<EngineAction>)>,
-    pub(crate) collision: Vec<(EngineCollisionEvent, Vec<EngineAction>)>,
-    pub(crate) resources: Vec<(EngineRsrcEvent, Vec<EngineAction>)>,
+    ids: LogicIDs,
+    pub(crate) control: PredicateTable<Game, Vec<EngineAction>>,
+    pub(crate) collision: PredicateTable<Game, Vec<EngineAction>>,
+    pub(crate) resources: PredicateTable<Game, Vec<EngineAction>>,
+    /// the thresholds that [EngineRsrcEvent::ScoreReached] rules watch for, which the game registers before resources next update
+    pub(crate) thresholds: Vec<(RsrcPool, i16)>,
 }
 
 impl Events {
-    pub fn new() -> Self {
+    pub(crate) fn new(ids: LogicIDs) -> Self {
         Self {
-            control: Vec::new(),
-            collision: Vec::new(),
-            resources: Vec::new(),
+            ids,
+            control: PredicateTable::new(),
+            collision: PredicateTable::new(),
+            resources: PredicateTable::new(),
+            thresholds: Vec::new(),
         }
     }
 
     pub fn add_ctrl_event(&mut self, event: EngineCtrlEvent, reaction: EngineAction) {
-        if let Some(idx) = self.control.iter().position(|(e, _)| *e == event) {
-            let (_, reactions) = &mut self.control[idx];
-            reactions.push(reaction);
-        } else {
-            self.control.push((event, vec![reaction]));
-        }
+        self.add_ctrl_events(event, vec![reaction]);
     }
 
-    pub fn add_ctrl_events(&mut self, event: EngineCtrlEvent, mut reactions: Vec<EngineAction>) {
-        if let Some(idx) = self.control.iter().position(|(e, _)| *e == event) {
-            let (_, r) = &mut self.control[idx];
-            r.append(&mut reactions);
-        } else {
-            self.control.push((event, reactions));
-        }
+    pub fn add_ctrl_events(&mut self, event: EngineCtrlEvent, reactions: Vec<EngineAction>) {
+        let (paddle, action, event_type) = match event {
+            EngineCtrlEvent::MovePaddle(paddle, action) => {
+                (paddle, action, ControlEventType::KeyHeld)
+            }
+            EngineCtrlEvent::ServePressed(paddle, action) => {
+                (paddle, action, ControlEventType::KeyPressed)
+            }
+            EngineCtrlEvent::PointerMoved(paddle, action) => {
+                (paddle, action, ControlEventType::AxisChanged)
+            }
+        };
+        self.control.add_rule(Rule::new(reactions).on_event(
+            self.ids.control,
+            event_type,
+            move |e: &CtrlEvent, _| {
+                e.action_id == action && e.set == paddle.idx() && e.event_type == event_type
+            },
+        ));
     }
 
     pub fn add_col_events(&mut self, event: EngineCollisionEvent, reactions: Vec<EngineAction>) {
-        self.collision.push((event, reactions));
+        let rule = Rule::new(reactions);
+        let rule = match event {
+            EngineCollisionEvent::Match(fst, snd) => rule.on_event(
+                self.ids.collision,
+                CollisionEventType::Touching,
+                move |contact: &ColEvent, game: &Game| {
+                    fst.matches(contact.i, &game.state) && snd.matches(contact.j, &game.state)
+                },
+            ),
+            EngineCollisionEvent::Filter(filter) => rule.on_event(
+                self.ids.collision,
+                CollisionEventType::Touching,
+                move |contact: &ColEvent, game: &Game| {
+                    filter(game.state.get_id(contact.i), game.state.get_id(contact.j))
+                },
+            ),
+        };
+        self.collision.add_rule(rule);
     }
 
     pub fn add_rsrc_event(&mut self, event: EngineRsrcEvent, reaction: EngineAction) {
-        if let Some(idx) = self.resources.iter().position(|(e, _)| *e == event) {
-            let (_, reactions) = &mut self.resources[idx];
-            reactions.push(reaction);
-        } else {
-            self.resources.push((event, vec![reaction]));
-        }
+        self.add_rsrc_events(event, vec![reaction]);
     }
-    pub fn add_rsrc_events(&mut self, event: EngineRsrcEvent, mut reactions: Vec<EngineAction>) {
-        if let Some(idx) = self.resources.iter().position(|(e, _)| *e == event) {
-            let (_, r) = &mut self.resources[idx];
-            r.append(&mut reactions);
-        } else {
-            self.resources.push((event, reactions));
-        }
+
+    pub fn add_rsrc_events(&mut self, event: EngineRsrcEvent, reactions: Vec<EngineAction>) {
+        let rule = Rule::new(reactions);
+        let updated = ResourceEventType::PoolUpdated;
+        let rule = match event {
+            EngineRsrcEvent::ScoreIncreased(score) => {
+                let score = RsrcPool::Score(score);
+                rule.on_event(self.ids.resources, updated, move |e: &RsrcEvent, _| {
+                    e.pool == score && e.transaction == Transaction::Change(1)
+                })
+            }
+            EngineRsrcEvent::ScoreReset(score) => {
+                let score = RsrcPool::Score(score);
+                rule.on_event(self.ids.resources, updated, move |e: &RsrcEvent, _| {
+                    e.pool == score && e.transaction == Transaction::Set(0)
+                })
+            }
+            EngineRsrcEvent::ScoreEquals(score, v) => {
+                let score = RsrcPool::Score(score);
+                rule.on_event(
+                    self.ids.resources,
+                    updated,
+                    move |e: &RsrcEvent, game: &Game| {
+                        e.pool == score && game.logics.resources.get_ident_data(score).val == v
+                    },
+                )
+            }
+            EngineRsrcEvent::ScoreReached(score, v) => {
+                let score = RsrcPool::Score(score);
+                if !self.thresholds.contains(&(score, v)) {
+                    self.thresholds.push((score, v));
+                }
+                let crossed = ResourceEventType::CrossedThreshold(v, ThresholdDirection::Rising);
+                rule.on_event(self.ids.resources, crossed, move |e: &RsrcEvent, _| {
+                    e.pool == score && e.event_type == crossed
+                })
+            }
+        };
+        self.resources.add_rule(rule);
     }
 }
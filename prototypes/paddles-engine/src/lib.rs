@@ -3,32 +3,45 @@
 
 use asterism::{
     collision::Contact,
-    control::{KeyboardControl, MacroquadInputWrapper},
-    graphics::draw::{self, Draw},
+    control::{
+        AiController, ControlSnapshot, KeyboardControl, MacroquadPointerWrapper,
+        NetworkInputWrapper,
+    },
+    graphics::draw::{self, Draw, DrawSnapshot},
     physics::PointPhysics,
     resources::QueuedResources,
-    Event,
+    predicates::PredicateTable,
+    scheduler::{LogicID, Scheduler},
+    snapshot::{History, Snapshot},
 };
 use macroquad::prelude::*;
 
+#[cfg(feature = "serde")]
+pub mod definition;
 mod entities;
 mod events;
 mod types;
 
 // reexports
 pub use asterism::collision::{AabbColData, AabbCollision, CollisionReaction};
-pub use asterism::control::{Action, ControlEventType, ControlReaction, Values};
+pub use asterism::control::{
+    Action, BindingName, Composite, ContextID, ControlEventType, ControlReaction, NetKey, NetPeer,
+    PointerInput, Policy, Values,
+};
 pub use asterism::physics::{PhysicsEvent, PhysicsReaction, PointPhysData};
-pub use asterism::resources::{ResourceEventType, ResourceReaction, Transaction};
+pub use asterism::resources::{
+    Generator, RateRule, ResourceEventType, ResourceReaction, ThresholdDirection, Transaction,
+};
 pub use asterism::{LendingIterator, Logic};
 pub use events::*;
 pub use types::*;
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Logics {
     pub collision: AabbCollision<CollisionEnt>,
     pub physics: PointPhysics,
     pub resources: QueuedResources<RsrcPool, i16>,
-    pub control: KeyboardControl<ActionID, MacroquadInputWrapper>,
+    pub control: KeyboardControl<ActionID, NetworkInputWrapper<MacroquadPointerWrapper>>,
 }
 
 impl Logics {
@@ -42,6 +55,43 @@ impl Logics {
     }
 }
 
+/// Everything in [Logics] that changes as the game runs. The control logic's input wrapper isn't included, so rewinding doesn't drop a network connection.
+#[derive(Clone)]
+pub struct LogicsSnapshot {
+    pub collision: AabbCollision<CollisionEnt>,
+    pub physics: PointPhysics,
+    pub resources: QueuedResources<RsrcPool, i16>,
+    pub control: ControlSnapshot<ActionID, NetKey<PointerInput>>,
+}
+
+impl Snapshot for Logics {
+    type State = LogicsSnapshot;
+
+    fn snapshot(&self) -> LogicsSnapshot {
+        LogicsSnapshot {
+            collision: self.collision.snapshot(),
+            physics: self.physics.snapshot(),
+            resources: self.resources.snapshot(),
+            control: self.control.snapshot(),
+        }
+    }
+
+    fn snapshot_into(&self, state: &mut LogicsSnapshot) {
+        self.collision.snapshot_into(&mut state.collision);
+        self.physics.snapshot_into(&mut state.physics);
+        self.resources.snapshot_into(&mut state.resources);
+        self.control.snapshot_into(&mut state.control);
+    }
+
+    fn restore(&mut self, state: &LogicsSnapshot) {
+        self.collision.restore(&state.collision);
+        self.physics.restore(&state.physics);
+        self.resources.restore(&state.resources);
+        self.control.restore(&state.control);
+    }
+}
+
+#[derive(Clone, Copy)]
 pub enum LogicsList {
     Collision,
     Physics,
@@ -50,6 +100,7 @@ pub enum LogicsList {
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EntID {
     Wall(WallID),
     Ball(BallID),
@@ -145,9 +196,12 @@ impl std::fmt::Debug for Ent {
     }
 }
 
-#[derive(Default)]
+#[derive(Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct State {
     remove_queue: Vec<EntID>,
+    /// entities waiting to be added aren't saved
+    #[cfg_attr(feature = "serde", serde(skip))]
     add_queue: Vec<Ent>,
     paddles: Vec<PaddleID>,
     walls: Vec<WallID>,
@@ -157,6 +211,25 @@ pub struct State {
     ball_id_max: usize,
     wall_id_max: usize,
     score_id_max: usize,
+    /// how far back to rewind at the end of this frame, see [EngineAction::Rewind]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    rewind: Option<usize>,
+}
+
+impl Snapshot for State {
+    type State = State;
+
+    fn snapshot(&self) -> State {
+        self.clone()
+    }
+
+    fn snapshot_into(&self, state: &mut State) {
+        state.clone_from(self);
+    }
+
+    fn restore(&mut self, state: &State) {
+        self.clone_from(state);
+    }
 }
 
 impl State {
@@ -216,30 +289,206 @@ impl State {
     }
 }
 
+/// What an AI paddle can see of the game each frame.
+pub struct Observation {
+    /// each ball's center and velocity
+    pub balls: Vec<(BallID, Vec2, Vec2)>,
+    /// each paddle's center
+    pub paddles: Vec<(PaddleID, Vec2)>,
+}
+
+impl Observation {
+    fn new(state: &State, logics: &Logics) -> Self {
+        let balls = state
+            .balls
+            .iter()
+            .enumerate()
+            .map(|(i, ball)| {
+                let col_idx = state.get_col_idx(EntID::Ball(*ball));
+                (
+                    *ball,
+                    logics.collision.centers[col_idx],
+                    logics.physics.velocities[i],
+                )
+            })
+            .collect();
+        let paddles = state
+            .paddles
+            .iter()
+            .map(|paddle| {
+                let col_idx = state.get_col_idx(EntID::Paddle(*paddle));
+                (*paddle, logics.collision.centers[col_idx])
+            })
+            .collect();
+        Self { balls, paddles }
+    }
+
+    pub fn ball(&self, id: BallID) -> Option<(Vec2, Vec2)> {
+        self.balls
+            .iter()
+            .find(|(ball, ..)| *ball == id)
+            .map(|(_, center, vel)| (*center, *vel))
+    }
+
+    pub fn paddle(&self, id: PaddleID) -> Option<Vec2> {
+        self.paddles
+            .iter()
+            .find(|(paddle, _)| *paddle == id)
+            .map(|(_, center)| *center)
+    }
+}
+
 pub struct Game {
     pub state: State,
     pub logics: Logics,
     pub events: Events,
     pub draw: Draw<LogicsList>,
+    /// computer-controlled paddles
+    pub ai: Vec<AiController<Observation, ActionID>>,
+    rewind: Rewind,
+    /// taken out while the game runs, since it needs the whole game
+    scheduler: Scheduler<Game>,
+}
+
+/// The last few frames of the game, see `Game::enable_rewind`.
+struct Rewind {
+    states: History<State>,
+    logics: History<LogicsSnapshot>,
+    draw: History<DrawSnapshot<LogicsList>>,
+}
+
+impl Rewind {
+    fn new(frames: usize) -> Self {
+        Self {
+            states: History::new(frames),
+            logics: History::new(frames),
+            draw: History::new(frames),
+        }
+    }
 }
 
 impl Game {
     pub fn new() -> Self {
         let mut draw = Draw::new();
         draw.background_color = draw::DARKBLUE;
+        let (scheduler, ids) = schedule();
 
         Self {
             state: State::default(),
             logics: Logics::new(),
-            events: Events::new(),
+            events: Events::new(ids),
+            draw,
+            ai: Vec::new(),
+            rewind: Rewind::new(0),
+            scheduler,
+        }
+    }
+
+    /// Rebinds paddle controls from a binding profile, where each control set is a paddle. See [KeyboardControl::load_profile].
+    pub fn load_controls(&mut self, profile: &str) -> Result<(), String> {
+        self.logics.control.load_profile(profile)
+    }
+
+    /// Writes the current paddle controls out as a binding profile.
+    pub fn save_controls(&self) -> String {
+        self.logics.control.save_profile()
+    }
+
+    /// Groups paddle actions into a context that can be turned on and off all at once with [EngineAction::PushContext] and [EngineAction::PopContext]. Actions in a context are off unless it's been pushed.
+    pub fn add_context(
+        &mut self,
+        name: &str,
+        blocking: bool,
+        actions: &[(PaddleID, ActionID)],
+    ) -> ContextID {
+        let control = &mut self.logics.control;
+        let context = control.add_context(name, blocking);
+        for (paddle, action) in actions.iter() {
+            control.add_to_context(context, paddle.idx(), *action);
+        }
+        context
+    }
+
+    /// Saves the positions of everything, the scores, and the controls as JSON. Rules, AIs, and how things are drawn are part of the game rather than the save, so they aren't included.
+    #[cfg(feature = "serde")]
+    pub fn save(&self) -> Result<String, String> {
+        serde_json::to_string(&(&self.state, &self.logics))
+            .map_err(|e| format!("couldn't save game: {}", e))
+    }
+
+    /// Loads a save made with `save` into a game with the same rules. Keeps the current input wrapper, so a network connection isn't dropped.
+    #[cfg(feature = "serde")]
+    pub fn load(&mut self, save: &str) -> Result<(), String> {
+        let (state, mut logics): (State, Logics) =
+            serde_json::from_str(save).map_err(|e| format!("couldn't load game: {}", e))?;
+        std::mem::swap(
+            logics.control.input_wrapper_mut(),
+            self.logics.control.input_wrapper_mut(),
+        );
+        self.state = state;
+        self.logics = logics;
+        Ok(())
+    }
+
+    /// Remembers the last `frames` frames so the game can be rewound, ex. 180 for three seconds at 60fps. Off (0) by default.
+    pub fn enable_rewind(&mut self, frames: usize) {
+        // one more than asked for, since the current frame is recorded too
+        self.rewind = Rewind::new(frames + 1);
+    }
+
+    /// records the current frame for rewinding, at the end of every frame
+    fn record_frame(&mut self) {
+        self.rewind.states.record(&self.state);
+        self.rewind.logics.record(&self.logics);
+        self.rewind.draw.record(&self.draw);
+    }
+
+    /// Puts the game back the way it was `frames` frames ago and forgets everything since, ex. to re-simulate from there when a networked player's late inputs arrive. Returns false, without changing anything, if not enough frames have been recorded.
+    pub fn rewind(&mut self, frames: usize) -> bool {
+        let Rewind {
+            states,
+            logics,
             draw,
+        } = &mut self.rewind;
+        states.rewind(&mut self.state, frames)
+            && logics.rewind(&mut self.logics, frames)
+            && draw.rewind(&mut self.draw, frames)
+    }
+
+    /// Plays against someone in another process. Paddles with remote controls are moved by the keys they hold. Both sides should connect before the game starts running.
+    pub fn connect(&mut self, peer: NetPeer) {
+        self.logics.control.input_wrapper_mut().connect(peer);
+    }
+
+    pub fn add_rsrc_event(&mut self, event: EngineRsrcEvent, reaction: EngineAction) {
+        self.events.add_rsrc_event(event, reaction);
+        self.add_thresholds();
+    }
+
+    pub fn add_rsrc_events(&mut self, event: EngineRsrcEvent, reactions: Vec<EngineAction>) {
+        self.events.add_rsrc_events(event, reactions);
+        self.add_thresholds();
+    }
+
+    /// scores report when they reach a value through threshold events, so this registers the ones the rules watch for, including rules added straight to [Events]
+    fn add_thresholds(&mut self) {
+        for &(pool, v) in self.events.thresholds.iter() {
+            if !self.logics.resources.thresholds.contains(&(pool, v)) {
+                self.logics.resources.add_threshold(pool, v);
+            }
         }
     }
+
+    /// Hands a paddle's controls over to a policy. Its actions get pressed the same way a player's keys would, so they trigger the same control events and follow the same validity rules.
+    pub fn add_ai(&mut self, paddle: PaddleID, policy: impl Policy<Observation, ActionID> + 'static) {
+        self.ai.push(AiController::new(paddle.idx(), policy));
+    }
 }
 
 pub async fn run(mut game: Game) {
     use std::collections::VecDeque;
     let mut fps = VecDeque::with_capacity(1000);
+    let mut scheduler = std::mem::replace(&mut game.scheduler, Scheduler::new());
     loop {
         if is_key_down(KeyCode::Escape) {
             break;
@@ -289,47 +538,154 @@ pub async fn run(mut game: Game) {
             }
         }
 
-        control(&mut game);
-        physics(&mut game);
-        collision(&mut game);
-        resources(&mut game);
+        scheduler.update(&mut game);
+
+        let rewind = game.state.rewind.take();
+        game.record_frame();
+        if let Some(frames) = rewind {
+            game.rewind(frames);
+        }
 
         next_frame().await;
     }
     println!("{}", fps.iter().sum::<i32>() / fps.len() as i32);
 }
 
-fn control(game: &mut Game) {
-    game.logics.control.update(&());
+/// Where the scheduler finds each logic, for the rules in [Events].
+#[derive(Clone, Copy)]
+pub(crate) struct LogicIDs {
+    pub(crate) control: LogicID<
+        Game,
+        KeyboardControl<ActionID, NetworkInputWrapper<MacroquadPointerWrapper>>,
+    >,
+    pub(crate) collision: LogicID<Game, AabbCollision<CollisionEnt>>,
+    pub(crate) resources: LogicID<Game, QueuedResources<RsrcPool, i16>>,
+}
 
-    for (event_data, actions) in game.events.control.iter() {
-        let events = game.logics.control.events();
-        match event_data {
-            EngineCtrlEvent::MovePaddle(paddle, id) => {
-                let relevant = events.iter().any(|e| {
-                    e.action_id == *id
-                        && e.set == paddle.idx()
-                        && e.event_type == ControlEventType::KeyHeld
-                });
-                if relevant {
-                    for action in actions {
-                        action.perform_action(&mut game.state, &mut game.logics);
-                    }
-                }
-            }
-            EngineCtrlEvent::ServePressed(paddle, id) => {
-                let relevant = events.iter().any(|e| {
-                    e.action_id == *id
-                        && e.set == paddle.idx()
-                        && e.event_type == ControlEventType::KeyPressed
-                });
-                if relevant {
-                    for action in actions {
-                        action.perform_action(&mut game.state, &mut game.logics);
+/// The order the logics update in. Each one's step also syncs positions to the other logics, and the rules that need its events are checked right after it.
+fn schedule() -> (Scheduler<Game>, LogicIDs) {
+    let mut scheduler = Scheduler::new();
+    let control = scheduler.add_logic(
+        "control",
+        |game: &Game| &game.logics.control,
+        |game| &mut game.logics.control,
+        control,
+    );
+    scheduler.add_logic(
+        "physics",
+        |game: &Game| &game.logics.physics,
+        |game| &mut game.logics.physics,
+        physics,
+    );
+    let collision = scheduler.add_logic(
+        "collision",
+        |game: &Game| &game.logics.collision,
+        |game| &mut game.logics.collision,
+        collision,
+    );
+    let resources = scheduler.add_logic(
+        "resources",
+        |game: &Game| &game.logics.resources,
+        |game| &mut game.logics.resources,
+        |game| {
+            game.add_thresholds();
+            game.logics.resources.update()
+        },
+    );
+
+    scheduler.after(control, |game| {
+        let fired = fired_rules(&game.events.control, game);
+        perform(game, |events| &events.control, fired);
+    });
+    scheduler.after(collision, move |game| {
+        // collision actions happen once per contact, and can refer to what the first entity touched
+        let fired: Vec<(usize, Vec<Contact>)> = game
+            .events
+            .collision
+            .evaluate(game)
+            .iter()
+            .map(|fired| {
+                let contacts = fired.events(collision, game).into_iter().copied();
+                (fired.rule, contacts.collect())
+            })
+            .collect();
+        let Game {
+            state,
+            logics,
+            events,
+            ..
+        } = game;
+        for (rule, contacts) in fired {
+            for contact in contacts {
+                for action in events.collision.rules()[rule].action() {
+                    match action {
+                        EngineAction::BounceBall(ball, None) => {
+                            let j = state.get_id(contact.j);
+                            EngineAction::BounceBall(*ball, Some(j)).perform_action(state, logics);
+                        }
+                        EngineAction::RemoveEntity(None) => {
+                            let j = state.get_id(contact.j);
+                            EngineAction::RemoveEntity(Some(EntityMatch::ByID(j)))
+                                .perform_action(state, logics);
+                        }
+                        _ => action.perform_action(state, logics),
                     }
                 }
             }
         }
+    });
+    scheduler.after(resources, |game| {
+        let fired = fired_rules(&game.events.resources, game);
+        perform(game, |events| &events.resources, fired);
+    });
+
+    (
+        scheduler,
+        LogicIDs {
+            control,
+            collision,
+            resources,
+        },
+    )
+}
+
+/// the rules in the table that this frame's events satisfy
+fn fired_rules(table: &PredicateTable<Game, Vec<EngineAction>>, game: &Game) -> Vec<usize> {
+    table
+        .evaluate(game)
+        .iter()
+        .map(|fired| fired.rule)
+        .collect()
+}
+
+fn perform(
+    game: &mut Game,
+    table: fn(&Events) -> &PredicateTable<Game, Vec<EngineAction>>,
+    rules: Vec<usize>,
+) {
+    let Game {
+        state,
+        logics,
+        events,
+        ..
+    } = game;
+    for rule in rules {
+        for action in table(events).rules()[rule].action() {
+            action.perform_action(state, logics);
+        }
+    }
+}
+
+fn control(game: &mut Game) {
+    if !game.ai.is_empty() {
+        let observation = Observation::new(&game.state, &game.logics);
+        for ai in game.ai.iter_mut() {
+            ai.act(&observation, &mut game.logics.control);
+        }
+    }
+    game.logics.control.update(&());
+    if let Some(err) = game.logics.control.input_wrapper_mut().take_error() {
+        eprintln!("lost connection to the other player: {}", err);
     }
 }
 
@@ -371,169 +727,6 @@ fn collision(game: &mut Game) {
                 ));
         }
     }
-
-    for (event_data, actions) in game.events.collision.iter() {
-        let events = game.logics.collision.events().iter();
-
-        // "how should we filter these events?"
-        let events: Vec<Contact> = match event_data {
-            EngineCollisionEvent::Match(fst, snd) => match (fst, snd) {
-                (EntityMatch::ByID(id1), EntityMatch::ByID(id2)) => events
-                    .filter(|Contact { i, j, .. }| {
-                        game.state.get_col_idx(*id1) == *i && game.state.get_col_idx(*id2) == *j
-                    })
-                    .copied()
-                    .collect(),
-                (EntityMatch::ByID(id1), EntityMatch::ByType(type2)) => events
-                    .filter(|Contact { i, j, .. }| {
-                        let ty2: EntType = game.state.get_id(*j).get_col_type().into();
-                        game.state.get_col_idx(*id1) == *i && ty2 == *type2
-                    })
-                    .copied()
-                    .collect(),
-                (EntityMatch::ByID(id1), EntityMatch::All) => events
-                    .filter(|Contact { i, .. }| game.state.get_col_idx(*id1) == *i)
-                    .copied()
-                    .collect(),
-                (EntityMatch::ByID(id1), EntityMatch::Filter(filter2)) => events
-                    .filter(|Contact { i, .. }| game.state.get_col_idx(*id1) == *i)
-                    .filter(|Contact { j, .. }| filter2(game.state.get_id(*j)))
-                    .copied()
-                    .collect(),
-                (EntityMatch::ByType(type1), EntityMatch::ByID(id2)) => events
-                    .filter(|Contact { i, j, .. }| {
-                        let ty1: EntType = game.state.get_id(*i).get_col_type().into();
-                        ty1 == *type1 && game.state.get_col_idx(*id2) == *j
-                    })
-                    .copied()
-                    .collect(),
-                (EntityMatch::ByType(type1), EntityMatch::ByType(type2)) => events
-                    .filter(|Contact { i, j, .. }| {
-                        let ty1: EntType = game.state.get_id(*i).get_col_type().into();
-                        let ty2: EntType = game.state.get_id(*j).get_col_type().into();
-                        ty1 == *type1 && ty2 == *type2
-                    })
-                    .copied()
-                    .collect(),
-                (EntityMatch::ByType(type1), EntityMatch::All) => events
-                    .filter(|Contact { i, .. }| {
-                        let ty1: EntType = game.state.get_id(*i).get_col_type().into();
-                        ty1 == *type1
-                    })
-                    .copied()
-                    .collect(),
-                (EntityMatch::ByType(type1), EntityMatch::Filter(filter2)) => events
-                    .filter(|Contact { i, .. }| {
-                        let ty1: EntType = game.state.get_id(*i).get_col_type().into();
-                        ty1 == *type1
-                    })
-                    .filter(|Contact { j, .. }| filter2(game.state.get_id(*j)))
-                    .copied()
-                    .collect(),
-                (EntityMatch::All, EntityMatch::ByID(id2)) => events
-                    .filter(|Contact { j, .. }| game.state.get_col_idx(*id2) == *j)
-                    .copied()
-                    .collect(),
-                (EntityMatch::All, EntityMatch::ByType(type2)) => events
-                    .filter(|Contact { j, .. }| {
-                        let ty2: EntType = game.state.get_id(*j).get_col_type().into();
-                        ty2 == *type2
-                    })
-                    .copied()
-                    .collect(),
-                (EntityMatch::All, EntityMatch::All) => events.copied().collect(),
-                (EntityMatch::All, EntityMatch::Filter(filter2)) => events
-                    .filter(|Contact { j, .. }| filter2(game.state.get_id(*j)))
-                    .copied()
-                    .collect(),
-                (EntityMatch::Filter(filter1), EntityMatch::ByID(id2)) => events
-                    .filter(|Contact { i, .. }| filter1(game.state.get_id(*i)))
-                    .filter(|Contact { j, .. }| game.state.get_col_idx(*id2) == *j)
-                    .copied()
-                    .collect(),
-                (EntityMatch::Filter(filter1), EntityMatch::ByType(type2)) => events
-                    .filter(|Contact { i, .. }| filter1(game.state.get_id(*i)))
-                    .filter(|Contact { j, .. }| {
-                        let ty2: EntType = game.state.get_id(*j).get_col_type().into();
-                        ty2 == *type2
-                    })
-                    .copied()
-                    .collect(),
-                (EntityMatch::Filter(filter1), EntityMatch::All) => events
-                    .filter(|Contact { i, .. }| filter1(game.state.get_id(*i)))
-                    .copied()
-                    .collect(),
-                (EntityMatch::Filter(filter1), EntityMatch::Filter(filter2)) => events
-                    .filter(|Contact { i, j, .. }| {
-                        filter1(game.state.get_id(*i)) && filter2(game.state.get_id(*j))
-                    })
-                    .copied()
-                    .collect(),
-            },
-            EngineCollisionEvent::Filter(filter) => events
-                .filter(|Contact { i, j, .. }| filter(game.state.get_id(*i), game.state.get_id(*j)))
-                .copied()
-                .collect(),
-        };
-
-        for event in events {
-            for action in actions.iter() {
-                match action {
-                    EngineAction::BounceBall(ball, None) => {
-                        let j = game.state.get_id(event.j);
-                        EngineAction::BounceBall(*ball, Some(j))
-                            .perform_action(&mut game.state, &mut game.logics);
-                    }
-                    EngineAction::RemoveEntity(None) => {
-                        let j = game.state.get_id(event.j);
-                        EngineAction::RemoveEntity(Some(EntityMatch::ByID(j)))
-                            .perform_action(&mut game.state, &mut game.logics);
-                    }
-                    _ => action.perform_action(&mut game.state, &mut game.logics),
-                }
-            }
-        }
-    }
-}
-
-fn resources(game: &mut Game) {
-    game.logics.resources.update();
-
-    for (event_data, actions) in game.events.resources.iter() {
-        let events = game.logics.resources.events();
-        let relevant = match event_data {
-            EngineRsrcEvent::ScoreIncreased(score) => {
-                let score = RsrcPool::Score(*score);
-                events.iter().any(|e| {
-                    e.pool == score
-                        && *e.get_type() == ResourceEventType::PoolUpdated
-                        && e.transaction == Transaction::Change(1)
-                })
-            }
-            EngineRsrcEvent::ScoreReset(score) => {
-                let score = RsrcPool::Score(*score);
-                events.iter().any(|e| {
-                    e.pool == score
-                        && *e.get_type() == ResourceEventType::PoolUpdated
-                        && e.transaction == Transaction::Set(0)
-                })
-            }
-            EngineRsrcEvent::ScoreEquals(score, v) => {
-                let score = RsrcPool::Score(*score);
-                let val = game.logics.resources.get_ident_data(score).val;
-                let events = game.logics.resources.events();
-
-                events.iter().any(|e| {
-                    e.pool == score && *e.get_type() == ResourceEventType::PoolUpdated && val == *v
-                })
-            }
-        };
-        if relevant {
-            for action in actions.iter() {
-                action.perform_action(&mut game.state, &mut game.logics);
-            }
-        }
-    }
 }
 
 pub fn draw(game: &mut Game) {
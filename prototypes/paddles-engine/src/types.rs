@@ -1,10 +1,12 @@
-use macroquad::{input::KeyCode, math::Vec2};
+use asterism::control::{Composite, NetKey, PointerInput};
+use macroquad::math::Vec2;
 
 /// generates identifier unit structs. example: `id_impl_new!([derive(PartialOrd, Ord)] ScoreID)` expands out to
 ///
 /// ```
 /// #[derive(PartialOrd, Ord)]
 /// #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+/// #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// pub struct ScoreID(usize);
 /// impl ScoreID {
 ///     pub fn new(idx: usize) -> Self {
@@ -20,6 +22,7 @@ macro_rules! id_impl_new {
         $(
             $(#[$derive])*
             #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+            #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
             pub struct $id_type(usize);
 
             impl $id_type {
@@ -43,7 +46,19 @@ macro_rules! id_impl_new {
 
 id_impl_new!([] PaddleID Paddle, [] WallID Wall, [] BallID Ball, [derive(PartialOrd, Ord)] ScoreID Score, [derive(PartialOrd, Ord)] ActionID);
 
+/// actions are saved in binding profiles by their index in the paddle's controls
+impl asterism::control::BindingName for ActionID {
+    fn binding_name(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn from_binding_name(name: &str) -> Option<Self> {
+        name.parse().ok().map(Self)
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CollisionEnt {
     Paddle,
     Wall,
@@ -51,6 +66,7 @@ pub enum CollisionEnt {
 }
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RsrcPool {
     Score(ScoreID),
 }
@@ -59,7 +75,9 @@ pub enum RsrcPool {
 pub struct Paddle {
     pub pos: Vec2,
     pub size: Vec2,
-    pub controls: Vec<(ActionID, KeyCode, bool)>,
+    pub controls: Vec<(ActionID, NetKey<PointerInput>, bool)>,
+    /// actions made of the paddle's other actions, ex. a double-tap
+    pub composites: Vec<(ActionID, Composite<ActionID>)>,
 }
 
 impl Paddle {
@@ -68,12 +86,34 @@ impl Paddle {
             pos,
             size,
             controls: Vec::new(),
+            composites: Vec::new(),
         }
     }
 
-    pub fn add_control_map(&mut self, keycode: KeyCode, valid: bool) -> ActionID {
-        let act_id = ActionID(self.controls.len());
-        self.controls.push((act_id, keycode, valid));
+    /// Adds a key, mouse button, or [PointerInput::Cursor] to the paddle's controls.
+    pub fn add_control_map(&mut self, input: impl Into<PointerInput>, valid: bool) -> ActionID {
+        let act_id = ActionID(self.controls.len() + self.composites.len());
+        self.controls
+            .push((act_id, NetKey::Local(input.into()), valid));
+        act_id
+    }
+
+    /// Adds a key held by the player on the other end of a network connection, see [Game::connect].
+    pub fn add_remote_control_map(
+        &mut self,
+        input: impl Into<PointerInput>,
+        valid: bool,
+    ) -> ActionID {
+        let act_id = ActionID(self.controls.len() + self.composites.len());
+        self.controls
+            .push((act_id, NetKey::Remote(input.into()), valid));
+        act_id
+    }
+
+    /// Adds an action made up of actions already added to the paddle, ex. `Composite::Chord(vec![action_q, action_a])`.
+    pub fn add_composite_control(&mut self, composite: Composite<ActionID>) -> ActionID {
+        let act_id = ActionID(self.controls.len() + self.composites.len());
+        self.composites.push((act_id, composite));
         act_id
     }
 }
@@ -124,7 +164,10 @@ impl Score {
 use asterism::control::ControlEvent;
 
 pub type CtrlEvent = ControlEvent<ActionID>;
-pub type CtrlIdent<'a> = (usize, &'a [asterism::control::Action<ActionID, KeyCode>]);
+pub type CtrlIdent<'a> = (
+    usize,
+    &'a [asterism::control::Action<ActionID, NetKey<PointerInput>>],
+);
 pub type ColEvent = asterism::collision::Contact;
 pub type ColIdent<'a> = (usize, asterism::collision::AabbColData<'a, CollisionEnt>);
 pub type RsrcIdent = (RsrcPool, (i16, i16, i16));
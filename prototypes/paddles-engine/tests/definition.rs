@@ -0,0 +1,82 @@
+//! Games described in JSON should build, and mistakes in them should say what's wrong.
+#![cfg(feature = "serde")]
+
+use paddles_engine::definition::GameDef;
+use paddles_engine::*;
+
+#[test]
+fn example_games() {
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("games");
+    let mut count = 0;
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+            continue;
+        }
+        let json = std::fs::read_to_string(&path).unwrap();
+        let def = GameDef::from_json(&json).unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+        if let Err(e) = def.build() {
+            panic!("{}: {}", path.display(), e);
+        }
+        count += 1;
+    }
+    assert!(count >= 3);
+}
+
+const BASE: &str = r#"
+  "balls": [{ "name": "ball", "pos": [0, 0], "size": [10, 10] }],
+  "paddles": [
+    { "name": "p1", "pos": [16, 0], "size": [8, 48], "controls": [{ "action": "up", "input": "Q" }] }
+  ]
+"#;
+
+fn err(rules: &str) -> String {
+    Game::from_json(&format!("{{{}, {}}}", BASE, rules))
+        .err()
+        .unwrap()
+}
+
+#[test]
+fn errors() {
+    assert!(Game::from_json(&format!("{{{}}}", BASE)).is_ok());
+
+    // unknown entity names
+    assert_eq!(
+        err(
+            r#""collisions": [{ "when": [{ "entity": "ball" }, { "entity": "goal" }], "do": [] }]"#
+        ),
+        "no entity named goal"
+    );
+    assert_eq!(
+        err(
+            r#""controls": [{ "when": { "held": ["p1", "up"] }, "do": [{ "bounce_ball": "puck" }] }]"#
+        ),
+        "no entity named puck"
+    );
+
+    // unknown actions
+    assert_eq!(
+        err(r#""controls": [{ "when": { "held": ["p1", "down"] }, "do": [] }]"#),
+        "paddle p1 doesn't have an action down"
+    );
+    assert!(err(
+        r#""controls": [{ "when": { "held": ["p1", "up"] }, "do": [{ "teleport": "ball" }] }]"#
+    )
+    .starts_with("couldn't read game: unknown variant `teleport`"));
+
+    // bad selectors
+    assert!(err(
+        r#""collisions": [{ "when": [{ "entity": "ball" }, { "type": "goal" }], "do": [] }]"#
+    )
+    .starts_with("couldn't read game: unknown variant `goal`"));
+    assert!(err(
+        r#""collisions": [{ "when": [{ "entity": "ball" }, { "name": "p1" }], "do": [] }]"#
+    )
+    .starts_with("couldn't read game: unknown variant `name`"));
+    assert_eq!(
+        err(
+            r#""collisions": [{ "when": [{ "entity": "p1" }, "all"], "do": [{ "set_ball_vel": ["p1", [0, 0]] }] }]"#
+        ),
+        "p1 isn't a ball"
+    );
+}
@@ -0,0 +1,48 @@
+//! Score rules that watch for a score reaching a value should set up the thresholds they need as soon as they're added.
+
+use macroquad::math::Vec2;
+use paddles_engine::*;
+
+#[test]
+fn thresholds() {
+    let mut game = Game::new();
+    let score = game.add_score(Score::new(0, Vec2::ZERO));
+    game.add_rsrc_event(
+        EngineRsrcEvent::ScoreReached(score, 10),
+        EngineAction::ChangeScore(score, 0),
+    );
+    game.add_rsrc_event(
+        EngineRsrcEvent::ScoreReached(score, 10),
+        EngineAction::ChangeScore(score, 1),
+    );
+    // equality doesn't need a threshold
+    game.add_rsrc_event(
+        EngineRsrcEvent::ScoreEquals(score, 0),
+        EngineAction::ChangeScore(score, 1),
+    );
+
+    assert_eq!(
+        game.logics.resources.thresholds,
+        vec![(RsrcPool::Score(score), 10)]
+    );
+}
+
+#[test]
+fn thresholds_from_events() {
+    let mut game = Game::new();
+    let score = game.add_score(Score::new(0, Vec2::ZERO));
+    // rules added straight to the events still get their thresholds, the next time the game registers them
+    game.events.add_rsrc_event(
+        EngineRsrcEvent::ScoreReached(score, 5),
+        EngineAction::ChangeScore(score, 0),
+    );
+    game.add_rsrc_event(
+        EngineRsrcEvent::ScoreReached(score, 10),
+        EngineAction::ChangeScore(score, 0),
+    );
+
+    assert_eq!(
+        game.logics.resources.thresholds,
+        vec![(RsrcPool::Score(score), 5), (RsrcPool::Score(score), 10)]
+    );
+}